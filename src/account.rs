@@ -7,6 +7,7 @@ use auto_ops::{impl_op_ex, impl_op_ex_commutative};
 use curve25519_dalek::{
     constants::ED25519_BASEPOINT_POINT as G,
     edwards::{CompressedEdwardsY, EdwardsPoint},
+    montgomery::MontgomeryPoint,
     Scalar as RawScalar,
 };
 use std::fmt::Display;
@@ -56,6 +57,21 @@ impl Key {
     pub fn sign_block(&self, block: &Block) -> Signature {
         self.sign_message(&block.hash())
     }
+
+    /// This key's X25519 private key: the same scalar, as little-endian bytes.
+    ///
+    /// Unlike most X25519 private keys, it is not clamped, so other X25519 implementations (which clamp it)
+    /// will not give the same results as `x25519`.
+    pub fn to_x25519(&self) -> SecretBytes<32> {
+        SecretBytes::from(*self.as_scalar().as_bytes())
+    }
+
+    /// X25519 Diffie-Hellman: the shared secret between this key and an X25519 public key
+    /// (such as `Account::to_x25519`)
+    pub fn x25519(&self, public_key: &[u8; 32]) -> SecretBytes<32> {
+        let shared = MontgomeryPoint(*public_key) * self.as_scalar().as_ref();
+        SecretBytes::from(shared.to_bytes())
+    }
 }
 impl From<[u8; 32]> for Key {
     fn from(value: [u8; 32]) -> Self {
@@ -124,6 +140,11 @@ impl Account {
     pub fn is_valid_signature(&self, message: &[u8], signature: &Signature) -> bool {
         is_valid_signature(message, signature, self)
     }
+
+    /// This account's X25519 public key: the Montgomery form of its ed25519 point
+    pub fn to_x25519(&self) -> [u8; 32] {
+        self.point.to_montgomery().to_bytes()
+    }
 }
 #[cfg(feature = "serde")]
 impl Serialize for Account {
@@ -284,6 +305,22 @@ mod tests {
         let account_2 = key_2.to_account();
         assert!((key_1 + key_2).to_account() == account_1 + account_2)
     }
+
+    #[test]
+    fn x25519() {
+        let seed = SecretBytes::from([0; 32]);
+        let key_1 = Key::from_seed(&seed, 0);
+        let key_2 = Key::from_seed(&seed, 1);
+
+        let shared = key_1.x25519(&key_2.to_account().to_x25519());
+        assert!(shared == key_2.x25519(&key_1.to_account().to_x25519()));
+        let point = key_1.as_scalar() * key_2.to_account().point;
+        assert!(*shared.as_bytes() == point.to_montgomery().to_bytes());
+
+        let public = MontgomeryPoint::mul_base(key_1.as_scalar().as_ref());
+        assert!(public.to_bytes() == key_1.to_account().to_x25519());
+        assert!(*key_1.to_x25519().as_bytes() == *key_1.as_bytes());
+    }
 }
 
 #[cfg(test)]
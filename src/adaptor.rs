@@ -0,0 +1,178 @@
+//! Adaptor signatures, for atomic swaps.
+//!
+//! A pre-signature is made with an adaptor point `T = tG`. Anybody can check it against `T`,
+//! but it only becomes a valid signature once it is completed with the secret `t`.
+//! Whoever sees both the pre-signature and the completed signature learns `t`.
+//!
+//! In a swap, Alice pre-signs her Nano block with Bob's adaptor point. Bob must reveal `t` to complete
+//! the signature and publish the block, which lets Alice extract `t`, and use it to claim Bob's side of the swap.
+//!
+//! To pre-sign a block, use the block's hash (`block.hash()`) as the message.
+
+use crate::hashes::blake2b512;
+use crate::nanopy::sign_message_with_r_point;
+use crate::{scalar, Account, Key, NanoError, Scalar, Signature};
+use curve25519_dalek::{
+    constants::ED25519_BASEPOINT_POINT as G, EdwardsPoint, Scalar as RawScalar,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A signature which is only valid once it is completed with the adaptor's secret
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PreSignature {
+    /// The nonce of the completed signature, `rG + T`
+    pub r: EdwardsPoint,
+    /// `s - t`, where `s` is the `s` of the completed signature
+    pub s: RawScalar,
+}
+impl PreSignature {
+    /// Pre-sign the `message` with the `Key`, for the adaptor point `T`.
+    ///
+    /// Returns `NanoError::InvalidCurvePoint` if the adaptor point has a small order.
+    pub fn new(
+        message: &[u8],
+        key: &Key,
+        adaptor: &EdwardsPoint,
+    ) -> Result<PreSignature, NanoError> {
+        if adaptor.is_small_order() {
+            return Err(NanoError::InvalidCurvePoint);
+        }
+        // the domain separator keeps this distinct from the `r` of `sign_message()`
+        let r = scalar!(blake2b512(
+            &[
+                b"nanopyrs adaptor".as_slice(),
+                key.as_bytes(),
+                adaptor.compress().as_bytes(),
+                message
+            ]
+            .concat()
+        ));
+        let signature = sign_message_with_r_point(message, key, &r, &(&r * G + adaptor));
+        Ok(PreSignature {
+            r: signature.r,
+            s: signature.s,
+        })
+    }
+
+    /// Check if the account's pre-signature for the `message` is valid, for the adaptor point `T`.
+    /// If so, completing it with `t` gives a valid signature.
+    pub fn is_valid(&self, message: &[u8], account: &Account, adaptor: &EdwardsPoint) -> bool {
+        let challenge = scalar!(blake2b512(
+            &[
+                self.r.compress().as_bytes().as_slice(),
+                account.compressed.as_bytes(),
+                message
+            ]
+            .concat()
+        ));
+        //s'G == R - T + H(R, A, m)A
+        !adaptor.is_small_order() && self.s * G == self.r - adaptor + challenge * account.point
+    }
+
+    /// Complete the pre-signature with the adaptor's secret `t`, giving a normal signature
+    pub fn complete(&self, secret: &Scalar) -> Signature {
+        Signature {
+            r: self.r,
+            s: self.s + secret.as_ref(),
+        }
+    }
+
+    /// Extract the adaptor's secret `t` from the completed signature.
+    ///
+    /// Returns `NanoError::MismatchedSignature` if the signature was not completed from this pre-signature.
+    pub fn extract_secret(
+        &self,
+        signature: &Signature,
+        adaptor: &EdwardsPoint,
+    ) -> Result<Scalar, NanoError> {
+        let secret = Scalar::from(signature.s - self.s);
+        if signature.r != self.r || &secret * G != *adaptor {
+            return Err(NanoError::MismatchedSignature);
+        }
+        Ok(secret)
+    }
+
+    /// Encode the pre-signature as `R' || s'`: the compressed nonce point, followed by the scalar,
+    /// in the same layout as a `Signature`
+    pub fn to_bytes(&self) -> [u8; 64] {
+        Signature::from(self).to_bytes()
+    }
+}
+
+impl From<&PreSignature> for Signature {
+    fn from(value: &PreSignature) -> Self {
+        Signature {
+            r: value.r,
+            s: value.s,
+        }
+    }
+}
+impl TryFrom<&[u8; 64]> for PreSignature {
+    type Error = NanoError;
+
+    fn try_from(value: &[u8; 64]) -> Result<Self, NanoError> {
+        let signature = Signature::try_from(value)?;
+        Ok(PreSignature {
+            r: signature.r,
+            s: signature.s,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SecretBytes;
+
+    fn key(i: u32) -> Key {
+        Key::from_seed(&SecretBytes::from([18; 32]), i)
+    }
+
+    fn secret() -> Scalar {
+        Scalar::from([19; 64])
+    }
+
+    #[test]
+    fn swap() {
+        let (message, account) = ([20; 32], key(0).to_account());
+        let adaptor = &secret() * G;
+        let pre_signature = PreSignature::new(&message, &key(0), &adaptor).unwrap();
+        assert!(pre_signature.is_valid(&message, &account, &adaptor));
+        assert!(!account.is_valid_signature(&message, &Signature::from(&pre_signature)));
+
+        let signature = pre_signature.complete(&secret());
+        assert!(account.is_valid_signature(&message, &signature));
+        let extracted = pre_signature.extract_secret(&signature, &adaptor).unwrap();
+        assert!(extracted == secret());
+
+        let bytes = pre_signature.to_bytes();
+        assert!(PreSignature::try_from(&bytes).unwrap() == pre_signature);
+    }
+
+    #[test]
+    fn invalid() {
+        let (message, account) = ([20; 32], key(0).to_account());
+        let adaptor = &secret() * G;
+        let pre_signature = PreSignature::new(&message, &key(0), &adaptor).unwrap();
+
+        assert!(!pre_signature.is_valid(&[21; 32], &account, &adaptor));
+        assert!(!pre_signature.is_valid(&message, &key(1).to_account(), &adaptor));
+        assert!(!pre_signature.is_valid(&message, &account, &(adaptor + G)));
+        let identity = adaptor - adaptor;
+        assert!(
+            PreSignature::new(&message, &key(0), &identity) == Err(NanoError::InvalidCurvePoint)
+        );
+
+        // a signature which was not completed from the pre-signature
+        let other = key(0).sign_message(&message);
+        let result = pre_signature.extract_secret(&other, &adaptor);
+        assert!(result.unwrap_err() == NanoError::MismatchedSignature);
+        let wrong = pre_signature.complete(&Scalar::from([22; 64]));
+        assert!(!account.is_valid_signature(&message, &wrong));
+        let result = pre_signature.extract_secret(&wrong, &adaptor);
+        assert!(result.unwrap_err() == NanoError::MismatchedSignature);
+    }
+}
@@ -11,7 +11,7 @@ const ALPHABET_ARRAY: [char; 32] = [
 pub fn encode(bytes: &[u8]) -> String {
     let bits = bytes.view_bits::<Msb0>();
     assert_eq!(bits.len() % 5, 0);
-    let mut s = String::new();
+    let mut s = String::with_capacity(bits.len() / 5);
     for idx in (0..bits.len()).step_by(5) {
         let chunk: &BitSlice<Msb0, u8> = &bits[idx..idx + 5];
         let value: u8 = chunk.load_be();
@@ -0,0 +1,33 @@
+//! Serves `work_generate`, `work_cancel` and `work_validate` over HTTP, using the local CPU.
+//!
+//! Usage: `nanopyrs-work-server [ADDRESS]` (default: `127.0.0.1:7076`)
+
+use nanopyrs::rpc::work::{server::WorkService, LocalWork};
+use std::net::TcpListener;
+use std::process::ExitCode;
+use std::sync::Arc;
+
+const DEFAULT_ADDRESS: &str = "127.0.0.1:7076";
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let address = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| DEFAULT_ADDRESS.into());
+    let listener = match TcpListener::bind(&address) {
+        Ok(listener) => listener,
+        Err(err) => {
+            eprintln!("could not listen on {address}: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    eprintln!("listening on {address}");
+    match WorkService::new(Arc::new(LocalWork)).serve(listener).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,363 @@
+use crate::keys::{account_arg, hex_arg, key_from_private, secret_arg};
+use crate::CliResult;
+use clap::{Args, Subcommand};
+use json::{json, Value as JsonValue};
+use nanopyrs::block::{check_work, get_local_work, get_work_difficulty};
+use nanopyrs::rpc::util::{block_to_json, to_uppercase_hex};
+use nanopyrs::{Account, Block, BlockType, Key, Network, SecretBytes, Signature};
+use serde_json as json;
+use std::error::Error;
+use std::io::Read;
+
+#[derive(Debug, Subcommand)]
+pub(crate) enum BlockCommand {
+    /// Print the block's hash
+    Hash(BlockInput),
+    /// Sign the block, and optionally generate its work
+    Sign(Box<Sign>),
+}
+
+#[derive(Debug, Subcommand)]
+pub(crate) enum Verify {
+    /// Verify a signature of an arbitrary message
+    Signature(Box<VerifySignature>),
+    /// Verify a block's signature and work
+    Block {
+        #[command(flatten)]
+        input: BlockInput,
+        #[command(flatten)]
+        difficulty: Difficulty,
+    },
+    /// Verify work for a hash
+    Work {
+        /// The work hash (the previous block's hash, or the public key for open blocks), as hex
+        #[arg(long, value_parser = hex_arg::<32>)]
+        hash: [u8; 32],
+        /// The work, as hex
+        #[arg(long, value_parser = hex_arg::<8>)]
+        work: [u8; 8],
+        #[command(flatten)]
+        difficulty: Difficulty,
+    },
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct VerifySignature {
+    /// Address or public key of the signer
+    #[arg(long, value_parser = account_arg)]
+    account: Account,
+    /// The signed message, as hex
+    #[arg(long)]
+    message: String,
+    /// The signature, as hex
+    #[arg(long, value_parser = hex_arg::<64>)]
+    signature: [u8; 64],
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct Work {
+    /// The work hash (the previous block's hash, or the public key for open blocks), as hex
+    #[arg(long, value_parser = hex_arg::<32>)]
+    hash: [u8; 32],
+    #[command(flatten)]
+    difficulty: Difficulty,
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct BlockInput {
+    /// The block as JSON, in the node's format. Read from stdin if omitted or `-`.
+    block: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct Sign {
+    #[command(flatten)]
+    input: BlockInput,
+    /// Private key, as hex
+    #[arg(long, env = "NANOPYRS_KEY", hide_env_values = true, value_parser = secret_arg)]
+    #[arg(required_unless_present = "seed", conflicts_with = "seed")]
+    key: Option<SecretBytes<32>>,
+    /// Seed, as hex (used with `--index`)
+    #[arg(long, env = "NANOPYRS_SEED", hide_env_values = true, value_parser = secret_arg)]
+    seed: Option<SecretBytes<32>>,
+    /// Index of the account to sign with
+    #[arg(long, default_value_t = 0, requires = "seed")]
+    index: u32,
+    /// Also generate the block's work locally
+    #[arg(long)]
+    work: bool,
+    #[command(flatten)]
+    difficulty: Difficulty,
+}
+
+/// Work difficulty options. By default, the difficulty depends on the network and the block type.
+#[derive(Debug, Args)]
+pub(crate) struct Difficulty {
+    /// Work difficulty, as hex
+    #[arg(long, value_parser = hex_arg::<8>, conflicts_with = "receive")]
+    difficulty: Option<[u8; 8]>,
+    /// Use the difficulty for receive blocks
+    #[arg(long)]
+    receive: bool,
+    /// Network whose work thresholds and epoch signers to use: `live`, `beta` or `test`
+    #[arg(long, default_value = "live", value_parser = network_arg)]
+    network: Network,
+}
+impl Difficulty {
+    fn or_default_for(&self, block_type: &BlockType) -> [u8; 8] {
+        match (self.difficulty, self.receive) {
+            (Some(difficulty), _) => difficulty,
+            (None, true) => self.network.work_difficulty(&BlockType::Receive),
+            (None, false) => self.network.work_difficulty(block_type),
+        }
+    }
+}
+
+fn network_arg(value: &str) -> Result<Network, String> {
+    match value {
+        "live" => Ok(Network::live()),
+        "beta" => Ok(Network::beta()),
+        "test" => Ok(Network::test()),
+        _ => Err("expected `live`, `beta` or `test`".into()),
+    }
+}
+
+pub(crate) fn block(command: BlockCommand) -> CliResult {
+    match command {
+        BlockCommand::Hash(input) => {
+            let block = input.read()?;
+            Ok(json!({ "hash": to_uppercase_hex(&block.hash()) }))
+        }
+        BlockCommand::Sign(args) => {
+            let mut block = args.input.read()?;
+            let key = match (args.key, args.seed) {
+                (Some(key), _) => key_from_private(&key),
+                (None, Some(seed)) => Key::from_seed(&seed, args.index),
+                (None, None) => return Err("a key or seed is required".into()),
+            };
+            if key.to_account() != block.account {
+                return Err("the key does not belong to the block's account".into());
+            }
+
+            block.sign(&key);
+            if args.work {
+                let difficulty = args.difficulty.or_default_for(&block.block_type);
+                block.work = get_local_work(block.work_hash(), difficulty);
+            }
+            Ok(json!({
+                "hash": to_uppercase_hex(&block.hash()),
+                "block": block_json(&block)
+            }))
+        }
+    }
+}
+
+pub(crate) fn verify(command: Verify) -> CliResult {
+    match command {
+        Verify::Signature(args) => {
+            let message = hex::decode(&args.message)?;
+            let valid = Signature::try_from(args.signature)
+                .is_ok_and(|signature| args.account.is_valid_signature(&message, &signature));
+            Ok(json!({ "valid": valid }))
+        }
+        Verify::Block { input, difficulty } => {
+            let block = input.read()?;
+            let work_difficulty = difficulty.or_default_for(&block.block_type);
+            Ok(json!({
+                "hash": to_uppercase_hex(&block.hash()),
+                "valid_signature": block.has_valid_signature_on(&difficulty.network),
+                "valid_work": block.has_valid_work(work_difficulty)
+            }))
+        }
+        Verify::Work {
+            hash,
+            work,
+            difficulty,
+        } => {
+            let difficulty = difficulty.or_default_for(&BlockType::Send);
+            Ok(json!({
+                "valid": check_work(hash, difficulty, work),
+                "difficulty": hex::encode(get_work_difficulty(hash, work))
+            }))
+        }
+    }
+}
+
+pub(crate) fn work(args: Work) -> CliResult {
+    let difficulty = args.difficulty.or_default_for(&BlockType::Send);
+    let work = get_local_work(args.hash, difficulty);
+    Ok(json!({
+        "hash": to_uppercase_hex(&args.hash),
+        "work": hex::encode(work),
+        "difficulty": hex::encode(get_work_difficulty(args.hash, work))
+    }))
+}
+
+impl BlockInput {
+    pub(crate) fn read(&self) -> Result<Block, Box<dyn Error>> {
+        let input = match self.block.as_deref() {
+            None | Some("-") => {
+                let mut input = String::new();
+                std::io::stdin().read_to_string(&mut input)?;
+                input
+            }
+            Some(block) => block.into(),
+        };
+        Ok(block_from_json(&json::from_str(&input)?)?)
+    }
+}
+
+/// Like `rpc::util::block_from_json`, but the signature and work are optional (or zero),
+/// and accounts may be given as public keys (and `link` as an address)
+pub(crate) fn block_from_json(block: &JsonValue) -> Result<Block, String> {
+    let field = |name: &str| {
+        block[name]
+            .as_str()
+            .ok_or_else(|| format!("missing string field `{name}`"))
+    };
+
+    if block["type"]
+        .as_str()
+        .is_some_and(|block_type| block_type != "state")
+    {
+        return Err("only state blocks are supported".into());
+    }
+    let block_type =
+        BlockType::from_subtype_string(field("subtype")?).ok_or("invalid `subtype`")?;
+    let link = field("link")?;
+    let link = match hex_arg::<32>(link) {
+        Ok(link) => link,
+        Err(_) => account_arg(link)?.compressed.to_bytes(),
+    };
+    let signature = match block["signature"].as_str().map(hex_arg::<64>) {
+        None => Signature::default(),
+        Some(Ok(signature)) if signature == [0; 64] => Signature::default(),
+        Some(signature) => Signature::try_from(signature?).map_err(|_| "invalid `signature`")?,
+    };
+    let work = match block["work"].as_str() {
+        Some(work) => hex_arg(work)?,
+        None => [0; 8],
+    };
+
+    Ok(Block {
+        block_type,
+        account: account_arg(field("account")?)?,
+        previous: hex_arg(field("previous")?)?,
+        representative: account_arg(field("representative")?)?,
+        balance: field("balance")?.parse().map_err(|_| "invalid `balance`")?,
+        link,
+        signature,
+        work,
+    })
+}
+
+/// The block in the node's format, including `subtype`
+pub(crate) fn block_json(block: &Block) -> JsonValue {
+    let mut json = block_to_json(block);
+    json.insert("subtype".into(), block.block_type.to_string().into());
+    if block.signature == Signature::default() {
+        json.insert("signature".into(), hex::encode([0; 64]).into());
+    }
+    json.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nanopyrs::hashes;
+
+    fn unsigned_block() -> JsonValue {
+        let account = Key::from_seed(&SecretBytes::from([0; 32]), 0).to_account();
+        json!({
+            "type": "state",
+            "subtype": "receive",
+            "account": account.to_string(),
+            "previous": hex::encode([0; 32]),
+            "representative": account.to_string(),
+            "balance": "1000",
+            "link": account.to_string()
+        })
+    }
+
+    #[test]
+    fn sign() {
+        let seed = SecretBytes::from([0; 32]);
+        let signed = block(BlockCommand::Sign(Box::new(Sign {
+            input: BlockInput {
+                block: Some(unsigned_block().to_string()),
+            },
+            key: None,
+            seed: Some(seed),
+            index: 0,
+            work: false,
+            difficulty: Difficulty {
+                difficulty: None,
+                receive: false,
+                network: Network::live(),
+            },
+        })))
+        .unwrap();
+
+        let block = block_from_json(&signed["block"]).unwrap();
+        assert!(block.has_valid_signature());
+        assert!(block.block_type == BlockType::Receive);
+        assert!(signed["hash"] == to_uppercase_hex(&block.hash()));
+        assert!(block_from_json(&block_json(&block)).unwrap() == block);
+
+        let unsigned = block_from_json(&unsigned_block()).unwrap();
+        assert!(block_from_json(&block_json(&unsigned)).unwrap() == unsigned);
+    }
+
+    #[test]
+    fn sign_with_private_key() {
+        let private_key = hashes::hazmat::get_account_seed(&SecretBytes::from([0; 32]), 0);
+        let signed = block(BlockCommand::Sign(Box::new(Sign {
+            input: BlockInput {
+                block: Some(unsigned_block().to_string()),
+            },
+            key: Some(private_key),
+            seed: None,
+            index: 0,
+            work: false,
+            difficulty: Difficulty {
+                difficulty: None,
+                receive: false,
+                network: Network::live(),
+            },
+        })))
+        .unwrap();
+        assert!(block_from_json(&signed["block"])
+            .unwrap()
+            .has_valid_signature());
+    }
+
+    #[test]
+    fn wrong_key() {
+        let result = block(BlockCommand::Sign(Box::new(Sign {
+            input: BlockInput {
+                block: Some(unsigned_block().to_string()),
+            },
+            key: None,
+            seed: Some(SecretBytes::from([1; 32])),
+            index: 0,
+            work: false,
+            difficulty: Difficulty {
+                difficulty: None,
+                receive: false,
+                network: Network::live(),
+            },
+        })));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn invalid_blocks() {
+        let mut legacy = unsigned_block();
+        legacy["type"] = "open".into();
+        assert!(block_from_json(&legacy).is_err());
+
+        let mut no_subtype = unsigned_block();
+        no_subtype["subtype"] = JsonValue::Null;
+        assert!(block_from_json(&no_subtype).is_err());
+    }
+}
@@ -0,0 +1,191 @@
+use crate::CliResult;
+use clap::Args;
+use json::json;
+use nanopyrs::camo::{CamoKeys, CamoVersion, CamoVersions};
+use nanopyrs::hashes::blake2b_scalar;
+use nanopyrs::hashes::hazmat::get_account_seed;
+use nanopyrs::rpc::util::to_uppercase_hex;
+use nanopyrs::{Account, Key, SecretBytes};
+use serde_json as json;
+
+#[derive(Debug, Args)]
+pub(crate) struct Derive {
+    /// Seed, as hex
+    #[arg(long, env = "NANOPYRS_SEED", hide_env_values = true, value_parser = secret_arg)]
+    seed: SecretBytes<32>,
+    /// Index of the first account
+    #[arg(long, default_value_t = 0)]
+    index: u32,
+    /// Number of accounts to derive
+    #[arg(long, default_value_t = 1)]
+    count: u32,
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct Convert {
+    /// Address or public key (or private key, with `--private`), as hex
+    value: String,
+    /// Treat the value as a private key
+    #[arg(long)]
+    private: bool,
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct Camo {
+    /// Seed, as hex
+    #[arg(long, env = "NANOPYRS_SEED", hide_env_values = true, value_parser = secret_arg)]
+    seed: SecretBytes<32>,
+    /// Index of the camo account
+    #[arg(long, default_value_t = 0)]
+    index: u32,
+}
+
+pub(crate) fn derive(args: Derive) -> CliResult {
+    if args.count == 0 {
+        return Ok(json!([]));
+    }
+    let last = args
+        .index
+        .checked_add(args.count - 1)
+        .ok_or("index out of range")?;
+    let accounts: Vec<_> = (args.index..=last)
+        .map(|i| {
+            let private_key = get_account_seed(&args.seed, i);
+            let mut value = account_json(&key_from_private(&private_key).to_account());
+            value["index"] = i.into();
+            value["private_key"] = to_uppercase_hex(private_key.as_slice()).into();
+            value
+        })
+        .collect();
+    Ok(accounts.into())
+}
+
+pub(crate) fn convert(args: Convert) -> CliResult {
+    if args.private {
+        let private_key = secret_arg(&args.value)?;
+        let mut value = account_json(&key_from_private(&private_key).to_account());
+        value["private_key"] = to_uppercase_hex(private_key.as_slice()).into();
+        return Ok(value);
+    }
+    Ok(account_json(&account_arg(&args.value)?))
+}
+
+pub(crate) fn camo(args: Camo) -> CliResult {
+    let versions = CamoVersions::new(&[CamoVersion::One]);
+    let keys =
+        CamoKeys::from_seed(&args.seed, args.index, versions).ok_or("unsupported camo version")?;
+    let camo_account = keys.to_camo_account();
+    Ok(json!({
+        "index": args.index,
+        "camo_account": camo_account.to_string(),
+        "signer_account": camo_account.signer_account().to_string()
+    }))
+}
+
+pub(crate) fn account_json(account: &Account) -> json::Value {
+    json!({
+        "account": account.to_string(),
+        "public_key": to_uppercase_hex(account.compressed.as_bytes())
+    })
+}
+
+/// Parse hex into an array, accepting either case
+pub(crate) fn hex_arg<const N: usize>(value: &str) -> Result<[u8; N], String> {
+    let bytes = hex::decode(value).map_err(|err| err.to_string())?;
+    bytes
+        .try_into()
+        .map_err(|_| format!("expected {N} bytes of hex"))
+}
+
+/// Parse either an address or a public key as hex
+pub(crate) fn account_arg(value: &str) -> Result<Account, String> {
+    if Account::is_valid(value) {
+        return Account::try_from(value).map_err(|err| err.to_string());
+    }
+    let public_key = hex_arg::<32>(value).map_err(|_| format!("invalid account: {value}"))?;
+    Account::from_bytes(public_key).map_err(|err| err.to_string())
+}
+
+/// The key of a standard Nano private key (as derived from a seed), which is hashed into the signing scalar
+pub(crate) fn key_from_private(private_key: &SecretBytes<32>) -> Key {
+    Key::from(blake2b_scalar(private_key.as_slice()))
+}
+
+pub(crate) fn secret_arg(value: &str) -> Result<SecretBytes<32>, String> {
+    hex_arg(value).map(SecretBytes::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEED: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+    const PRIVATE_KEY: &str = "9F0E444C69F77A49BD0BE89DB92C38FE713E0963165CCA12FAF5712D7657120F";
+    const ADDRESS: &str = "nano_3i1aq1cchnmbn9x5rsbap8b15akfh7wj7pwskuzi7ahz8oq6cobd99d4r3b7";
+
+    #[test]
+    fn derive() {
+        let seed = secret_arg(SEED).unwrap();
+        let accounts = super::derive(Derive {
+            seed,
+            index: 0,
+            count: 2,
+        })
+        .unwrap();
+        assert!(accounts[0]["account"] == ADDRESS);
+        assert!(accounts[0]["private_key"] == PRIVATE_KEY);
+        assert!(accounts[1]["index"] == 1);
+
+        let seed = secret_arg(SEED).unwrap();
+        let last = super::derive(Derive {
+            seed,
+            index: u32::MAX,
+            count: 1,
+        })
+        .unwrap();
+        assert!(last.as_array().unwrap().len() == 1);
+        assert!(last[0]["index"] == u32::MAX);
+
+        let seed = secret_arg(SEED).unwrap();
+        let empty = super::derive(Derive {
+            seed,
+            index: u32::MAX,
+            count: 0,
+        })
+        .unwrap();
+        assert!(empty.as_array().unwrap().is_empty());
+
+        let seed = secret_arg(SEED).unwrap();
+        assert!(super::derive(Derive {
+            seed,
+            index: u32::MAX,
+            count: 2,
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn convert() {
+        let from_address = super::convert(Convert {
+            value: ADDRESS.into(),
+            private: false,
+        })
+        .unwrap();
+        let public_key = from_address["public_key"].as_str().unwrap().to_lowercase();
+        let from_hex = super::convert(Convert {
+            value: public_key,
+            private: false,
+        })
+        .unwrap();
+        assert!(from_address == from_hex);
+
+        let from_private = super::convert(Convert {
+            value: PRIVATE_KEY.into(),
+            private: true,
+        })
+        .unwrap();
+        assert!(from_private["account"] == ADDRESS);
+        assert!(from_private["private_key"] == PRIVATE_KEY);
+        assert!(account_arg("nano_1234").is_err());
+    }
+}
@@ -0,0 +1,74 @@
+//! Command-line tool for keys, blocks, work and RPC queries.
+//!
+//! Run `nanopyrs help` for usage. Output is human-readable by default, or JSON with `--json`.
+
+mod block;
+mod keys;
+mod output;
+mod rpc;
+
+use clap::{Parser, Subcommand};
+use json::Value as JsonValue;
+use serde_json as json;
+use std::error::Error;
+use std::process::ExitCode;
+
+pub(crate) type CliResult = Result<JsonValue, Box<dyn Error>>;
+
+#[derive(Debug, Parser)]
+#[command(name = "nanopyrs", version, about = "Nano keys, blocks, work and RPC")]
+struct Cli {
+    /// Print the output as JSON
+    #[arg(long, global = true)]
+    json: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Derive accounts from a seed
+    Derive(keys::Derive),
+    /// Convert between an address, a public key and a private key
+    Convert(keys::Convert),
+    /// Derive a camo address from a seed
+    Camo(keys::Camo),
+    /// Hash or sign a block, given as JSON
+    #[command(subcommand)]
+    Block(block::BlockCommand),
+    /// Verify a signature, a block or work
+    #[command(subcommand)]
+    Verify(block::Verify),
+    /// Generate work locally
+    Work(block::Work),
+    /// Send a query to a node
+    Rpc(Box<rpc::RpcArgs>),
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let result = match cli.command {
+        Command::Derive(args) => keys::derive(args),
+        Command::Convert(args) => keys::convert(args),
+        Command::Camo(args) => keys::camo(args),
+        Command::Block(command) => block::block(command),
+        Command::Verify(command) => block::verify(command),
+        Command::Work(args) => block::work(args),
+        Command::Rpc(args) => rpc::rpc(*args).await,
+    };
+
+    match result {
+        Ok(value) => {
+            match cli.json {
+                true => println!("{}", json::to_string_pretty(&value).unwrap()),
+                false => print!("{}", output::human_readable(&value)),
+            }
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
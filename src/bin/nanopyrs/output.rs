@@ -0,0 +1,78 @@
+use json::Value as JsonValue;
+use serde_json as json;
+
+/// Format the value as indented `key: value` lines
+pub(crate) fn human_readable(value: &JsonValue) -> String {
+    let mut output = String::new();
+    write_value(&mut output, value, 0);
+    output
+}
+
+fn write_value(output: &mut String, value: &JsonValue, indent: usize) {
+    let padding = "  ".repeat(indent);
+    match value {
+        JsonValue::Object(map) => {
+            for (key, value) in map {
+                match value {
+                    JsonValue::Object(map) if !map.is_empty() => {
+                        output.push_str(&format!("{padding}{key}:\n"));
+                        write_value(output, value, indent + 1);
+                    }
+                    JsonValue::Array(array) if !array.is_empty() => {
+                        output.push_str(&format!("{padding}{key}:\n"));
+                        write_value(output, value, indent + 1);
+                    }
+                    _ => output.push_str(&format!("{padding}{key}: {}\n", scalar(value))),
+                }
+            }
+        }
+        JsonValue::Array(array) => {
+            for (i, value) in array.iter().enumerate() {
+                match value {
+                    JsonValue::Object(_) | JsonValue::Array(_) => {
+                        if i > 0 {
+                            output.push('\n');
+                        }
+                        write_value(output, value, indent);
+                    }
+                    _ => output.push_str(&format!("{padding}{}\n", scalar(value))),
+                }
+            }
+        }
+        _ => output.push_str(&format!("{padding}{}\n", scalar(value))),
+    }
+}
+
+fn scalar(value: &JsonValue) -> String {
+    match value {
+        JsonValue::String(string) => string.clone(),
+        JsonValue::Object(_) | JsonValue::Array(_) => "(none)".into(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use json::json;
+
+    #[test]
+    fn human_readable() {
+        let value = json!({
+            "account": "nano_1",
+            "valid": true,
+            "block": {"balance": "10"},
+            "blocks": [{"hash": "AB"}, {"hash": "CD"}],
+            "hashes": ["AB", "CD"],
+            "empty": []
+        });
+        // serde_json sorts the keys
+        let expected = "account: nano_1\n\
+            block:\n  balance: 10\n\
+            blocks:\n  hash: AB\n\n  hash: CD\n\
+            empty: (none)\n\
+            hashes:\n  AB\n  CD\n\
+            valid: true\n";
+        assert!(super::human_readable(&value) == expected);
+    }
+}
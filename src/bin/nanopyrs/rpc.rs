@@ -0,0 +1,227 @@
+use crate::block::{block_json, BlockInput};
+use crate::keys::{account_arg, hex_arg};
+use crate::CliResult;
+use clap::{Args, Subcommand};
+use json::{json, Value as JsonValue};
+use nanopyrs::rpc::util::to_uppercase_hex;
+use nanopyrs::rpc::{BlockInfo, ConfirmationOptions, Rpc};
+use nanopyrs::Account;
+use serde_json as json;
+
+#[derive(Debug, Args)]
+pub(crate) struct RpcArgs {
+    /// URL of the node's RPC
+    #[arg(long, env = "NANOPYRS_RPC", default_value = "http://127.0.0.1:7076")]
+    url: String,
+    /// Proxy to connect through
+    #[arg(long)]
+    proxy: Option<String>,
+    #[command(subcommand)]
+    query: Query,
+}
+
+#[derive(Debug, Subcommand)]
+enum Query {
+    /// Get the account's balance
+    AccountBalance {
+        #[arg(value_parser = account_arg)]
+        account: Account,
+    },
+    /// List the account's most recent blocks
+    AccountHistory {
+        #[arg(value_parser = account_arg)]
+        account: Account,
+        /// Maximum number of blocks
+        #[arg(long, default_value_t = 10)]
+        count: usize,
+    },
+    /// Get general info about the account
+    AccountInfo {
+        #[arg(value_parser = account_arg)]
+        account: Account,
+    },
+    /// Get the account's representative
+    AccountRepresentative {
+        #[arg(value_parser = account_arg)]
+        account: Account,
+    },
+    /// Get the number of blocks in the ledger
+    BlockCount,
+    /// Get info about a block
+    BlockInfo {
+        #[arg(value_parser = hex_arg::<32>)]
+        hash: [u8; 32],
+    },
+    /// Publish a signed block
+    Process {
+        #[command(flatten)]
+        input: BlockInput,
+        /// Wait for the block to be confirmed
+        #[arg(long)]
+        wait: bool,
+    },
+    /// List the account's receivable transactions
+    Receivable {
+        #[arg(value_parser = account_arg)]
+        account: Account,
+        /// Maximum number of transactions
+        #[arg(long, default_value_t = 10)]
+        count: usize,
+        /// Minimum amount, in raw
+        #[arg(long, default_value_t = 0)]
+        threshold: u128,
+    },
+    /// List the online representatives and their voting weight
+    RepresentativesOnline,
+    /// Get the node's telemetry
+    Telemetry,
+    /// Get the node's version
+    Version,
+    /// Generate work on the node
+    WorkGenerate {
+        #[arg(value_parser = hex_arg::<32>)]
+        hash: [u8; 32],
+        /// Work difficulty, as hex
+        #[arg(long, value_parser = hex_arg::<8>)]
+        difficulty: Option<[u8; 8]>,
+    },
+}
+
+pub(crate) async fn rpc(args: RpcArgs) -> CliResult {
+    let rpc = Rpc::new(&args.url, args.proxy)?;
+    let value = match args.query {
+        Query::AccountBalance { account } => {
+            json!({ "balance": rpc.account_balance(&account).await?.to_string() })
+        }
+        Query::AccountHistory { account, count } => {
+            let history = rpc.account_history(&account, count, None, None).await?;
+            history
+                .iter()
+                .map(|block| {
+                    json!({
+                        "hash": to_uppercase_hex(&block.hash()),
+                        "block": block_json(block)
+                    })
+                })
+                .collect()
+        }
+        Query::AccountInfo { account } => match rpc.account_info(&account).await? {
+            Some(info) => json!({
+                "frontier": to_uppercase_hex(&info.frontier),
+                "open_block": to_uppercase_hex(&info.open_block),
+                "balance": info.balance.to_string(),
+                "modified_timestamp": info.modified_timestamp,
+                "block_count": info.block_count,
+                "version": info.version,
+                "representative": info.representative.to_string(),
+                "weight": info.weight.to_string(),
+                "receivable": info.receivable
+            }),
+            None => json!({ "opened": false }),
+        },
+        Query::AccountRepresentative { account } => {
+            let representative = rpc.account_representative(&account).await?;
+            json!({ "representative": representative.map(|account| account.to_string()) })
+        }
+        Query::BlockCount => {
+            let count = rpc.block_count().await?;
+            json!({
+                "count": count.count,
+                "unchecked": count.unchecked,
+                "cemented": count.cemented
+            })
+        }
+        Query::BlockInfo { hash } => match rpc.block_info(hash).await? {
+            Some(info) => block_info_json(&info),
+            None => json!({ "found": false }),
+        },
+        Query::Process { input, wait } => {
+            let block = input.read()?;
+            match wait {
+                true => {
+                    let info = rpc
+                        .process_and_wait(&block, ConfirmationOptions::default())
+                        .await?;
+                    block_info_json(&info)
+                }
+                false => json!({ "hash": to_uppercase_hex(&rpc.process(&block).await?) }),
+            }
+        }
+        Query::Receivable {
+            account,
+            count,
+            threshold,
+        } => {
+            let receivable = rpc
+                .accounts_receivable(&[account], count, threshold)
+                .await?;
+            receivable
+                .into_iter()
+                .flatten()
+                .map(|receivable| {
+                    json!({
+                        "hash": to_uppercase_hex(&receivable.block_hash),
+                        "amount": receivable.amount.to_string()
+                    })
+                })
+                .collect()
+        }
+        Query::RepresentativesOnline => rpc
+            .representatives_online()
+            .await?
+            .into_iter()
+            .map(|(representative, weight)| {
+                json!({
+                    "representative": representative.to_string(),
+                    "weight": weight.to_string()
+                })
+            })
+            .collect(),
+        Query::Telemetry => {
+            let telemetry = rpc.telemetry().await?;
+            json!({
+                "block_count": telemetry.block_count,
+                "cemented_count": telemetry.cemented_count,
+                "unchecked_count": telemetry.unchecked_count,
+                "account_count": telemetry.account_count,
+                "peer_count": telemetry.peer_count,
+                "protocol_version": telemetry.protocol_version,
+                "uptime": telemetry.uptime,
+                "genesis_block": to_uppercase_hex(&telemetry.genesis_block),
+                "version": format!(
+                    "{}.{}.{}",
+                    telemetry.major_version, telemetry.minor_version, telemetry.patch_version
+                ),
+                "timestamp": telemetry.timestamp,
+                "active_difficulty": hex::encode(telemetry.active_difficulty)
+            })
+        }
+        Query::Version => {
+            let version = rpc.version().await?;
+            json!({
+                "rpc_version": version.rpc_version,
+                "store_version": version.store_version,
+                "protocol_version": version.protocol_version,
+                "node_vendor": version.node_vendor,
+                "store_vendor": version.store_vendor,
+                "network": version.network,
+                "network_identifier": to_uppercase_hex(&version.network_identifier),
+                "build_info": version.build_info
+            })
+        }
+        Query::WorkGenerate { hash, difficulty } => {
+            json!({ "work": hex::encode(rpc.work_generate(hash, difficulty).await?) })
+        }
+    };
+    Ok(value)
+}
+
+fn block_info_json(info: &BlockInfo) -> JsonValue {
+    json!({
+        "hash": to_uppercase_hex(&info.block.hash()),
+        "height": info.height,
+        "timestamp": info.timestamp,
+        "confirmed": info.confirmed,
+        "block": block_json(&info.block)
+    })
+}
@@ -1,13 +1,14 @@
-use super::constants::{epoch_signers::*, get_genesis_account};
+use super::constants::{EPOCH_V1_LINK, EPOCH_V2_LINK, STATE_BLOCK_LENGTH};
 use super::nanopy::{hash_block, sign_message};
-use super::{Account, Key, NanoError, Signature};
+use super::{Account, Key, NanoError, Network, Signature};
+use std::cmp::Ordering;
 use std::fmt::Display;
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-pub use super::nanopy::{check_work, get_local_work};
+pub use super::nanopy::{check_work, get_local_work, get_work_difficulty};
 
 /// The type of a Nano block
 ///
@@ -80,6 +81,64 @@ impl Display for BlockType {
     }
 }
 
+/// An epoch upgrade, applied to an account by an `epoch` block.
+///
+/// Accounts which have never been upgraded are at epoch 0, which has no variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Epoch {
+    V1,
+    V2,
+}
+impl Epoch {
+    /// Identify the epoch from the `link` field of an `epoch` block
+    pub fn from_link(link: &[u8; 32]) -> Option<Epoch> {
+        match *link {
+            EPOCH_V1_LINK => Some(Epoch::V1),
+            EPOCH_V2_LINK => Some(Epoch::V2),
+            _ => None,
+        }
+    }
+
+    /// The `link` field of this epoch's blocks
+    pub fn link(&self) -> [u8; 32] {
+        match self {
+            Epoch::V1 => EPOCH_V1_LINK,
+            Epoch::V2 => EPOCH_V2_LINK,
+        }
+    }
+
+    /// The account which signs this epoch's blocks on the live network.
+    /// See `Network::epoch_signer` for other networks.
+    pub fn signer(&self) -> Account {
+        Network::live().epoch_signer(*self).clone()
+    }
+
+    /// The highest epoch of the `epoch` blocks in an account's history (in any order),
+    /// or `None` if the account has not been upgraded by an `epoch` block.
+    ///
+    /// Note that the node also upgrades accounts when they receive from an upgraded account,
+    /// without an `epoch` block. `AccountInfo::version` reflects this.
+    pub fn from_history(history: &[Block]) -> Option<Epoch> {
+        history.iter().filter_map(Block::epoch).max()
+    }
+
+    /// Create an (unsigned, without work) `epoch` block, upgrading the account whose frontier is `frontier`.
+    /// It must be signed by this epoch's signer.
+    pub fn upgrade_block(&self, frontier: &Block) -> Block {
+        Block {
+            block_type: BlockType::Epoch,
+            account: frontier.account.clone(),
+            previous: frontier.hash(),
+            representative: frontier.representative.clone(),
+            balance: frontier.balance,
+            link: self.link(),
+            signature: Signature::default(),
+            work: [0; 8],
+        }
+    }
+}
+
 /// A Nano block. See the official [Nano documentation](https://docs.nano.org/protocol-design/blocks/) for details.
 #[derive(Debug, Clone, Zeroize, ZeroizeOnDrop, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -97,7 +156,7 @@ pub struct Block {
 impl Block {
     /// Check whether this block follows the rules for an `epoch` block
     pub fn follows_epoch_rules(&self, previous: &Block) -> bool {
-        self.block_type == BlockType::Epoch
+        self.epoch().is_some()
             && self.balance == previous.balance
             && self.representative == previous.representative
             && self.previous == previous.hash()
@@ -107,6 +166,70 @@ impl Block {
         hash_block(self)
     }
 
+    /// The epoch this block upgrades to, if it is an `epoch` block with a known `link`
+    pub fn epoch(&self) -> Option<Epoch> {
+        match self.block_type {
+            BlockType::Epoch => Epoch::from_link(&self.link),
+            _ => None,
+        }
+    }
+
+    /// Encode this block in the node's binary format for `state` blocks, as used in peer-to-peer messages
+    pub fn to_bytes(&self) -> [u8; STATE_BLOCK_LENGTH] {
+        [
+            self.account.compressed.as_bytes().as_slice(),
+            &self.previous,
+            self.representative.compressed.as_bytes(),
+            &self.balance.to_be_bytes(),
+            &self.link,
+            &self.signature.to_bytes(),
+            &self.work,
+        ]
+        .concat()
+        .try_into()
+        .unwrap()
+    }
+
+    /// Decode a block from the node's binary format for `state` blocks.
+    ///
+    /// The binary format does not include the subtype, which is guessed: `Epoch` if `link` is a known epoch,
+    /// `Receive` for blocks which open an account, `Change` if `link` is zero, and `Send` otherwise
+    /// (since it has the highest work threshold). Use `set_subtype` to correct it.
+    pub fn from_bytes(bytes: &[u8; STATE_BLOCK_LENGTH]) -> Result<Block, NanoError> {
+        let array = |start: usize| -> [u8; 32] { bytes[start..start + 32].try_into().unwrap() };
+        let link = array(112);
+        let previous = array(32);
+        let block_type = match Epoch::from_link(&link) {
+            Some(_) => BlockType::Epoch,
+            None if previous == [0; 32] => BlockType::Receive,
+            None if link == [0; 32] => BlockType::Change,
+            None => BlockType::Send,
+        };
+        let signature: [u8; 64] = bytes[144..208].try_into().unwrap();
+        Ok(Block {
+            block_type,
+            account: Account::try_from(array(0))?,
+            previous,
+            representative: Account::try_from(array(64))?,
+            balance: u128::from_be_bytes(bytes[96..112].try_into().unwrap()),
+            link,
+            signature: Signature::try_from(signature)?,
+            work: bytes[208..216].try_into().unwrap(),
+        })
+    }
+
+    /// Set `block_type` (the subtype), given the balance of the previous block (`0` if this block opens the account)
+    pub fn set_subtype(&mut self, previous_balance: u128) {
+        self.block_type = match self.balance.cmp(&previous_balance) {
+            Ordering::Less => BlockType::Send,
+            Ordering::Greater => BlockType::Receive,
+            Ordering::Equal if Epoch::from_link(&self.link).is_some() => BlockType::Epoch,
+            Ordering::Equal if self.link == [0; 32] => BlockType::Change,
+            // this would be a receive of nothing, which the node rejects
+            Ordering::Equal => BlockType::Receive,
+        }
+    }
+
     /// Get the hash for which this block must include valid work for
     pub fn work_hash(&self) -> [u8; 32] {
         if self.previous == [0; 32] {
@@ -136,20 +259,19 @@ impl Block {
         self.set_signature(self.get_signature(private_key))
     }
 
-    /// Check if the signature for this block is valid
+    /// Check if the signature for this block is valid on the live network
     pub fn has_valid_signature(&self) -> bool {
-        if self.block_type != BlockType::Epoch {
-            // "normal" block
-            self.account.clone()
-        } else if self.link[7] == 49 {
-            // epoch v1
-            get_v1_epoch_signer()
-        } else if self.link[7] == 50 {
-            // epoch v2
-            get_v2_epoch_signer()
-        } else {
+        self.has_valid_signature_on(&Network::live())
+    }
+
+    /// Check if the signature for this block is valid on the given network
+    pub fn has_valid_signature_on(&self, network: &Network) -> bool {
+        match (&self.block_type, self.epoch()) {
+            (BlockType::Epoch, Some(epoch)) => network.epoch_signer(epoch),
             // "uhhh let's try genesis I guess"
-            get_genesis_account()
+            (BlockType::Epoch, None) => &network.genesis_account,
+            // "normal" block
+            _ => &self.account,
         }
         .is_valid_signature(&self.hash(), &self.signature)
     }
@@ -176,6 +298,11 @@ impl Block {
         }
         check_work(self.work_hash(), difficulty, self.work)
     }
+
+    /// Check if the work for this block is valid, given the network's threshold for this block's type
+    pub fn has_valid_work_on(&self, network: &Network) -> bool {
+        self.has_valid_work(network.work_difficulty(&self.block_type))
+    }
 }
 
 #[cfg(test)]
@@ -215,6 +342,24 @@ mod tests {
         assert!(block.has_valid_work(TEST_WORK_DIFFICULTY));
     }
 
+    #[test]
+    fn work_difficulty() {
+        let work_hash = [
+            0x71, 0x8c, 0xc2, 0x12, 0x1c, 0x3e, 0x64, 0x10, 0x59, 0xbc, 0x1c, 0x2c, 0xfc, 0x45,
+            0x66, 0x6c, 0x99, 0xe8, 0xae, 0x92, 0x2f, 0x7a, 0x80, 0x7b, 0x7d, 0x07, 0xb6, 0x2c,
+            0x99, 0x5d, 0x79, 0xe2,
+        ];
+        let work = [0x2b, 0x3d, 0x68, 0x9b, 0xbc, 0xb2, 0x1d, 0xca];
+        let difficulty = get_work_difficulty(work_hash, work);
+        assert!(difficulty == [0xff, 0xff, 0xff, 0xf9, 0x3c, 0x41, 0xec, 0x94]);
+        assert!(check_work(work_hash, difficulty, work));
+        assert!(!check_work(
+            work_hash,
+            [0xff, 0xff, 0xff, 0xf9, 0x3c, 0x41, 0xec, 0x95],
+            work
+        ));
+    }
+
     #[test]
     fn create_signature() {
         let seed = SecretBytes::from([0; 32]);
@@ -226,6 +371,41 @@ mod tests {
         assert!(block.has_valid_signature());
     }
 
+    #[test]
+    fn epoch_upgrade() {
+        let frontier = create_test_block();
+        let v1 = Epoch::V1.upgrade_block(&frontier);
+        let v2 = Epoch::V2.upgrade_block(&v1);
+        assert!(v1.follows_epoch_rules(&frontier) && v2.follows_epoch_rules(&v1));
+        assert!(!v2.follows_epoch_rules(&frontier));
+        assert!(v2.epoch() == Some(Epoch::V2) && frontier.epoch().is_none());
+
+        assert!(Epoch::from_history(std::slice::from_ref(&frontier)).is_none());
+        assert!(Epoch::from_history(&[frontier.clone(), v1.clone()]) == Some(Epoch::V1));
+        assert!(Epoch::from_history(&[v2, v1, frontier]) == Some(Epoch::V2));
+    }
+
+    #[test]
+    fn binary_format() {
+        let mut block = create_test_block();
+        block.sign(&Key::from_seed(&SecretBytes::from([0; 32]), 0));
+        block.work = [1, 2, 3, 4, 5, 6, 7, 8];
+
+        let bytes = block.to_bytes();
+        assert!(bytes[..32] == block.account.compressed.to_bytes());
+        assert!(bytes[96..112] == ONE_NANO.to_be_bytes());
+        assert!(bytes[208..] == [1, 2, 3, 4, 5, 6, 7, 8]);
+        assert!(Block::from_bytes(&bytes).unwrap() == block);
+
+        let mut decoded = Block::from_bytes(&bytes).unwrap();
+        decoded.set_subtype(0);
+        assert!(decoded.block_type == BlockType::Receive);
+        decoded.set_subtype(ONE_NANO);
+        assert!(decoded.block_type == BlockType::Receive);
+        decoded.set_subtype(ONE_NANO + 1);
+        assert!(decoded.block_type == BlockType::Send);
+    }
+
     #[test]
     fn check_receive_block() {
         let block = Block {
@@ -326,6 +506,7 @@ mod tests {
         };
         assert!(block.has_valid_work(INFINITE_WORK_DIFFICULTY));
         assert!(block.has_valid_signature());
+        assert!(block.epoch() == Some(Epoch::V1));
     }
 
     #[test]
@@ -360,6 +541,7 @@ mod tests {
         };
         assert!(block.has_valid_work(INFINITE_WORK_DIFFICULTY));
         assert!(block.has_valid_signature());
+        assert!(block.epoch() == Some(Epoch::V2));
     }
 }
 
@@ -367,7 +549,8 @@ mod tests {
 #[cfg(feature = "serde")]
 mod serde_tests {
     use super::*;
-    use crate::{constants::ONE_NANO, serde_test};
+    use crate::constants::{epoch_signers::get_v2_epoch_signer, get_genesis_account, ONE_NANO};
+    use crate::serde_test;
 
     serde_test!(block_type: BlockType::Receive => 4);
     serde_test!(block: Block {
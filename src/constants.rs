@@ -13,6 +13,31 @@ pub const ONE_MILLI_NANO: u128 = ONE_RAW * 1_000_000_000_000_000_000_000_000_000
 /// 1 Nano
 pub const ONE_NANO: u128 = ONE_RAW * 1_000_000_000_000_000_000_000_000_000_000;
 
+/// Minimum work difficulty of every block on the live network, before the account was upgraded to epoch v2
+pub const EPOCH_1_WORK_DIFFICULTY: [u8; 8] = [0xff, 0xff, 0xff, 0xc0, 0, 0, 0, 0];
+/// Minimum work difficulty of `send` and `change` blocks on the live network
+pub const SEND_WORK_DIFFICULTY: [u8; 8] = [0xff, 0xff, 0xff, 0xf8, 0, 0, 0, 0];
+/// Minimum work difficulty of `receive` and `epoch` blocks on the live network
+pub const RECEIVE_WORK_DIFFICULTY: [u8; 8] = [0xff, 0xff, 0xfe, 0, 0, 0, 0, 0];
+
+/// The `link` field of epoch v1 blocks: `"epoch v1 block"`, padded with zeros
+pub const EPOCH_V1_LINK: [u8; 32] = epoch_link(b"epoch v1 block");
+/// The `link` field of epoch v2 blocks: `"epoch v2 block"`, padded with zeros
+pub const EPOCH_V2_LINK: [u8; 32] = epoch_link(b"epoch v2 block");
+
+/// Length of a `state` block in the node's binary format
+pub const STATE_BLOCK_LENGTH: usize = 216;
+
+const fn epoch_link(message: &[u8]) -> [u8; 32] {
+    let mut link = [0; 32];
+    let mut i = 0;
+    while i < message.len() {
+        link[i] = message[i];
+        i += 1;
+    }
+    link
+}
+
 pub fn get_genesis_account() -> Account {
     Account::try_from("nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3").unwrap()
 }
@@ -0,0 +1,195 @@
+//! Encryption of messages to a Nano account, which only the account's private key can decrypt.
+//!
+//! Messages are encrypted ECIES-style: a random ephemeral key is combined with the recipient's account (through X25519),
+//! and the shared secret is used to encrypt the message with ChaCha20-Poly1305.
+//!
+//! Version 1 ciphertexts are laid out as follows:
+//!
+//! | Bytes  | Contents                                                             |
+//! |--------|----------------------------------------------------------------------|
+//! | 1      | Version (`1`)                                                        |
+//! | 32     | The ephemeral X25519 public key                                      |
+//! | n + 16 | The ChaCha20-Poly1305 ciphertext of the n-byte message, and its tag |
+//!
+//! The encryption key is `blake2b256("nanopyrs encryption v1" || shared_secret || ephemeral_key || recipient_key)`,
+//! using X25519 public keys. Each key is only used once, so the nonce is zero.
+//! The version and ephemeral key are authenticated as associated data.
+//! Ephemeral keys outside the prime-order subgroup are rejected.
+
+use crate::hashes::blake2b256;
+use crate::secrets::random_bytes;
+use crate::{Account, Key, NanoError, Scalar, SecretBytes};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Nonce};
+use curve25519_dalek::MontgomeryPoint;
+
+/// The current version of the ciphertext format
+pub const VERSION: u8 = 1;
+/// Length of the version and ephemeral key, which precede the encrypted message
+pub const HEADER_LENGTH: usize = 1 + 32;
+/// Length of the authentication tag, which follows the encrypted message
+pub const TAG_LENGTH: usize = 16;
+
+const KEY_DOMAIN: &[u8] = b"nanopyrs encryption v1";
+
+/// Encrypt the message to the recipient, who can decrypt it with `decrypt`.
+///
+/// The ciphertext is `HEADER_LENGTH + TAG_LENGTH` bytes longer than the message.
+/// Note that the sender is not authenticated: anybody can encrypt a message to any account.
+///
+/// Returns `NanoError::InvalidCurvePoint` if the recipient's point has a small order.
+pub fn encrypt(recipient: &Account, message: &[u8]) -> Result<Vec<u8>, NanoError> {
+    let ephemeral = Key::from(Scalar::from(random_bytes::<64>()));
+    encrypt_with_ephemeral(recipient, message, &ephemeral)
+}
+
+/// Decrypt a message which was encrypted to this key's account
+pub fn decrypt(key: &Key, ciphertext: &[u8]) -> Result<Vec<u8>, NanoError> {
+    if ciphertext.len() < HEADER_LENGTH + TAG_LENGTH {
+        return Err(NanoError::DecryptionFailed);
+    }
+    if ciphertext[0] != VERSION {
+        return Err(NanoError::UnsupportedEncryptionVersion);
+    }
+    let (header, encrypted) = ciphertext.split_at(HEADER_LENGTH);
+    let ephemeral_public: [u8; 32] = header[1..].try_into().unwrap();
+    // reject points outside the prime-order subgroup, whose shared secret would leak the key modulo 8
+    let is_torsion_free = MontgomeryPoint(ephemeral_public)
+        .to_edwards(0)
+        .is_some_and(|point| point.is_torsion_free());
+    if !is_torsion_free {
+        return Err(NanoError::DecryptionFailed);
+    }
+
+    let shared = key.x25519(&ephemeral_public);
+    let cipher = cipher(&shared, &ephemeral_public, &key.to_account().to_x25519())
+        .or(Err(NanoError::DecryptionFailed))?;
+    let payload = Payload {
+        msg: encrypted,
+        aad: header,
+    };
+    cipher
+        .decrypt(&Nonce::default(), payload)
+        .or(Err(NanoError::DecryptionFailed))
+}
+
+fn encrypt_with_ephemeral(
+    recipient: &Account,
+    message: &[u8],
+    ephemeral: &Key,
+) -> Result<Vec<u8>, NanoError> {
+    let ephemeral_public = ephemeral.to_account().to_x25519();
+    let recipient_public = recipient.to_x25519();
+    let shared = ephemeral.x25519(&recipient_public);
+
+    let mut ciphertext = Vec::with_capacity(HEADER_LENGTH + message.len() + TAG_LENGTH);
+    ciphertext.push(VERSION);
+    ciphertext.extend_from_slice(&ephemeral_public);
+    let payload = Payload {
+        msg: message,
+        aad: &ciphertext,
+    };
+    let encrypted = cipher(&shared, &ephemeral_public, &recipient_public)?
+        .encrypt(&Nonce::default(), payload)
+        .expect("message is too long to encrypt");
+    ciphertext.extend(encrypted);
+    Ok(ciphertext)
+}
+
+fn cipher(
+    shared: &SecretBytes<32>,
+    ephemeral_public: &[u8; 32],
+    recipient_public: &[u8; 32],
+) -> Result<ChaCha20Poly1305, NanoError> {
+    // reject points of small order, which would make the shared secret predictable
+    if shared.as_bytes() == &[0; 32] {
+        return Err(NanoError::InvalidCurvePoint);
+    }
+    let key = blake2b256(
+        &[
+            KEY_DOMAIN,
+            shared.as_slice(),
+            ephemeral_public,
+            recipient_public,
+        ]
+        .concat(),
+    );
+    Ok(ChaCha20Poly1305::new(key.as_bytes().into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use curve25519_dalek::constants::EIGHT_TORSION;
+
+    fn key(i: u32) -> Key {
+        Key::from_seed(&SecretBytes::from([14; 32]), i)
+    }
+
+    #[test]
+    fn roundtrip() {
+        let message = b"memo: invoice #42";
+        let ciphertext = encrypt(&key(0).to_account(), message).unwrap();
+        assert!(ciphertext.len() == HEADER_LENGTH + message.len() + TAG_LENGTH);
+        assert!(ciphertext[0] == VERSION);
+        assert!(decrypt(&key(0), &ciphertext).unwrap() == message);
+        assert!(decrypt(&key(1), &ciphertext) == Err(NanoError::DecryptionFailed));
+
+        // random ephemeral keys
+        assert!(encrypt(&key(0).to_account(), message).unwrap() != ciphertext);
+        let empty = encrypt(&key(0).to_account(), b"").unwrap();
+        assert!(decrypt(&key(0), &empty).unwrap().is_empty());
+    }
+
+    #[test]
+    fn format() {
+        let ciphertext = encrypt_with_ephemeral(&key(0).to_account(), b"hello", &key(2)).unwrap();
+        assert!(ciphertext[1..HEADER_LENGTH] == key(2).to_account().to_x25519());
+        assert!(
+            ciphertext == encrypt_with_ephemeral(&key(0).to_account(), b"hello", &key(2)).unwrap()
+        );
+
+        for i in [0, 1, HEADER_LENGTH, ciphertext.len() - 1] {
+            let mut tampered = ciphertext.clone();
+            tampered[i] ^= 1;
+            assert!(decrypt(&key(0), &tampered).is_err());
+        }
+        let mut tampered = ciphertext.clone();
+        tampered[0] = 2;
+        assert!(decrypt(&key(0), &tampered) == Err(NanoError::UnsupportedEncryptionVersion));
+        assert!(
+            decrypt(&key(0), &ciphertext[..HEADER_LENGTH + TAG_LENGTH - 1])
+                == Err(NanoError::DecryptionFailed)
+        );
+
+        // a small-order ephemeral key
+        let mut small_order = ciphertext.clone();
+        small_order[1..HEADER_LENGTH].copy_from_slice(&[0; 32]);
+        assert!(decrypt(&key(0), &small_order) == Err(NanoError::DecryptionFailed));
+        // non-zero torsion points, alone and mixed with a valid point
+        for torsion in &EIGHT_TORSION[1..] {
+            for point in [*torsion, key(2).to_account().point + torsion] {
+                let mut tampered = ciphertext.clone();
+                tampered[1..HEADER_LENGTH].copy_from_slice(&point.to_montgomery().to_bytes());
+                assert!(decrypt(&key(0), &tampered) == Err(NanoError::DecryptionFailed));
+            }
+        }
+
+        // even if the attacker guesses the shared secret correctly, so that the tag is valid
+        let ephemeral_public = (key(2).to_account().point + EIGHT_TORSION[1])
+            .to_montgomery()
+            .to_bytes();
+        let shared = key(0).x25519(&ephemeral_public);
+        let header = [[VERSION].as_slice(), &ephemeral_public].concat();
+        let payload = Payload {
+            msg: b"hello",
+            aad: &header,
+        };
+        let encrypted = cipher(&shared, &ephemeral_public, &key(0).to_account().to_x25519())
+            .unwrap()
+            .encrypt(&Nonce::default(), payload)
+            .unwrap();
+        let forged = [header, encrypted].concat();
+        assert!(decrypt(&key(0), &forged) == Err(NanoError::DecryptionFailed));
+    }
+}
@@ -13,9 +13,27 @@ pub enum NanoError {
     InvalidCurvePoint,
     /// Invalid base32 encoding
     InvalidBase32,
+    /// Invalid set of keys or shares: empty, too few, or with duplicates
+    InvalidKeySet,
+    /// The key or account is not one of the multi-signature's signers
+    UnknownSigner,
+    /// Invalid threshold: zero, or more than the number of participants
+    InvalidThreshold,
+    /// The participant with this identifier sent an invalid share
+    InvalidShare(u16),
+    /// Invalid share text: wrong length, prefix or checksum
+    InvalidShareText,
+    /// The signature was not completed from the adaptor pre-signature
+    MismatchedSignature,
     /// incompatible camo protocol versions
     #[cfg(feature = "camo")]
     IncompatibleCamoVersions,
+    /// unsupported encrypted message version
+    #[cfg(feature = "encryption")]
+    UnsupportedEncryptionVersion,
+    /// could not decrypt message
+    #[cfg(feature = "encryption")]
+    DecryptionFailed,
 }
 impl Display for NanoError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -25,8 +43,20 @@ impl Display for NanoError {
             NanoError::InvalidBase32 => "invalid base 32 encoding",
             NanoError::InvalidAddressChecksum => "invalid checksum",
             NanoError::InvalidCurvePoint => "invalid ed25519 point",
+            NanoError::InvalidKeySet => "invalid set of keys",
+            NanoError::UnknownSigner => "unknown signer",
+            NanoError::InvalidThreshold => "invalid threshold",
+            NanoError::InvalidShareText => "invalid share text",
+            NanoError::MismatchedSignature => "signature does not match the pre-signature",
+            NanoError::InvalidShare(identifier) => {
+                return write!(f, "invalid share from participant {identifier}")
+            }
             #[cfg(feature = "camo")]
             NanoError::IncompatibleCamoVersions => "incompatible camo protocol versions",
+            #[cfg(feature = "encryption")]
+            NanoError::UnsupportedEncryptionVersion => "unsupported encrypted message version",
+            #[cfg(feature = "encryption")]
+            NanoError::DecryptionFailed => "could not decrypt message",
         }
         .into();
         write!(f, "{string}")
@@ -0,0 +1,704 @@
+//! Threshold (t-of-n) signing, using FROST.
+//!
+//! A key is split into `n` shares, any `t` of which can sign for the group's account.
+//! The resulting signatures are normal ed25519-blake2b signatures, accepted by the node and `is_valid_signature`.
+//!
+//! Shares can be created by a trusted dealer, who splits an existing key (`split_key`),
+//! or through distributed key generation (`DkgParticipant`), so that the group's key never exists in one place.
+//!
+//! Signing takes two rounds of communication between at least `t` signers:
+//! 1. Each signer creates `SigningNonces`, and shares its `SigningCommitment`
+//! 2. Once the commitments are known, each signer creates a `SigningPackage` and shares its `SignatureShare`
+//!
+//! Any signer can then aggregate the signature shares into the final `Signature`.
+//!
+//! **`SigningNonces` must never be used more than once.** They are consumed when signing to help with this,
+//! but they must also not be copied, stored and restored, or otherwise reused.
+
+use crate::hashes::blake2b512;
+use crate::secrets::random_bytes;
+use crate::{scalar, try_point_from_slice, Account, Key, NanoError, Scalar, Signature};
+use curve25519_dalek::{
+    constants::ED25519_BASEPOINT_POINT as G, traits::Identity, EdwardsPoint, Scalar as RawScalar,
+};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Hash the data (with a domain separator) to a scalar
+fn hash_scalar(domain: &[u8], data: &[&[u8]]) -> RawScalar {
+    let data = [&[domain], data].concat().concat();
+    *scalar!(blake2b512(&data)).as_ref()
+}
+
+fn random_scalar() -> Scalar {
+    Scalar::from(random_bytes::<64>())
+}
+
+/// Evaluate the secret polynomial at `x`
+fn evaluate(coefficients: &[Scalar], x: u16) -> Scalar {
+    let x = RawScalar::from(x);
+    let mut result = Scalar::from(RawScalar::ZERO);
+    for coefficient in coefficients.iter().rev() {
+        result = Scalar::from(result.as_ref() * x + coefficient.as_ref());
+    }
+    result
+}
+
+/// Evaluate the committed polynomial at `x`, giving the public key of the share at `x`
+fn evaluate_commitments(commitments: &[EdwardsPoint], x: u16) -> EdwardsPoint {
+    let x = RawScalar::from(x);
+    let mut result = EdwardsPoint::identity();
+    for commitment in commitments.iter().rev() {
+        result = result * x + commitment;
+    }
+    result
+}
+
+/// The Lagrange coefficient of `identifier`, for interpolating at zero from the `identifiers`
+fn lagrange(identifier: u16, identifiers: &[u16]) -> RawScalar {
+    let x = RawScalar::from(identifier);
+    let mut numerator = RawScalar::ONE;
+    let mut denominator = RawScalar::ONE;
+    for other in identifiers.iter().filter(|other| **other != identifier) {
+        let other = RawScalar::from(*other);
+        numerator *= other;
+        denominator *= other - x;
+    }
+    numerator * denominator.invert()
+}
+
+fn check_threshold(threshold: u16, count: u16) -> Result<(), NanoError> {
+    if threshold == 0 || threshold > count {
+        return Err(NanoError::InvalidThreshold);
+    }
+    Ok(())
+}
+
+/// The public information of a group: the commitments to its polynomial, from which every share's public key follows
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(try_from = "Vec<EdwardsPoint>", into = "Vec<EdwardsPoint>")
+)]
+pub struct GroupKey {
+    commitments: Vec<EdwardsPoint>,
+}
+impl GroupKey {
+    /// Load a group from its commitments, e.g. from storage.
+    ///
+    /// Returns `NanoError::InvalidThreshold` if there are no commitments (or too many),
+    /// or `NanoError::InvalidCurvePoint` if the group's point has a small order.
+    pub fn new(commitments: Vec<EdwardsPoint>) -> Result<GroupKey, NanoError> {
+        if commitments.is_empty() || commitments.len() > u16::MAX as usize {
+            return Err(NanoError::InvalidThreshold);
+        }
+        if commitments[0].is_small_order() {
+            return Err(NanoError::InvalidCurvePoint);
+        }
+        Ok(GroupKey { commitments })
+    }
+
+    /// The commitments to the group's polynomial
+    pub fn commitments(&self) -> &[EdwardsPoint] {
+        &self.commitments
+    }
+
+    /// The group's account, which requires `threshold()` signers to sign
+    pub fn account(&self) -> Account {
+        Account::from(&self.commitments[0])
+    }
+
+    /// The number of signers needed to sign
+    pub fn threshold(&self) -> u16 {
+        self.commitments.len() as u16
+    }
+
+    /// The public key of the participant's share
+    pub fn share_account(&self, identifier: u16) -> Account {
+        Account::from(&evaluate_commitments(&self.commitments, identifier))
+    }
+}
+
+impl TryFrom<Vec<EdwardsPoint>> for GroupKey {
+    type Error = NanoError;
+
+    fn try_from(value: Vec<EdwardsPoint>) -> Result<Self, NanoError> {
+        GroupKey::new(value)
+    }
+}
+impl From<GroupKey> for Vec<EdwardsPoint> {
+    fn from(value: GroupKey) -> Self {
+        value.commitments
+    }
+}
+
+/// A participant's share of the group's key
+#[derive(Debug, Clone, Zeroize, ZeroizeOnDrop)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct KeyShare {
+    identifier: u16,
+    secret: Scalar,
+}
+impl KeyShare {
+    /// Load a share, e.g. from storage.
+    ///
+    /// Returns `NanoError::UnknownSigner` if the identifier is zero.
+    pub fn new(identifier: u16, secret: Scalar) -> Result<KeyShare, NanoError> {
+        if identifier == 0 {
+            return Err(NanoError::UnknownSigner);
+        }
+        Ok(KeyShare { identifier, secret })
+    }
+
+    /// The participant's identifier, from `1` to `n`
+    pub fn identifier(&self) -> u16 {
+        self.identifier
+    }
+
+    pub fn as_scalar(&self) -> &Scalar {
+        &self.secret
+    }
+
+    /// Check that the share belongs to the group
+    pub fn is_valid(&self, group: &GroupKey) -> bool {
+        self.identifier != 0
+            && self.secret.as_ref() * G == evaluate_commitments(&group.commitments, self.identifier)
+    }
+}
+
+/// Split the key into `count` shares, any `threshold` of which can sign for the key's account.
+/// The shares are identified from `1` to `count`.
+///
+/// Whoever runs this (the trusted dealer) knows the whole key. Use `DkgParticipant` to avoid this.
+pub fn split_key(
+    key: &Key,
+    threshold: u16,
+    count: u16,
+) -> Result<(GroupKey, Vec<KeyShare>), NanoError> {
+    check_threshold(threshold, count)?;
+    let mut coefficients = vec![key.as_scalar().clone()];
+    coefficients.extend((1..threshold).map(|_| random_scalar()));
+
+    let group = GroupKey::new(coefficients.iter().map(|c| c.as_ref() * G).collect())?;
+    let shares = (1..=count)
+        .map(|identifier| KeyShare::new(identifier, evaluate(&coefficients, identifier)))
+        .collect::<Result<_, _>>()?;
+    Ok((group, shares))
+}
+
+/// The public part of a participant's first round of distributed key generation, which must be sent to every participant
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DkgCommitment {
+    pub identifier: u16,
+    /// Commitments to the participant's polynomial
+    pub commitments: Vec<EdwardsPoint>,
+    /// Proof that the participant knows its secret, preventing rogue-key attacks.
+    /// It is bound to the session's context, so it cannot be replayed into another session.
+    pub proof: Signature,
+}
+impl DkgCommitment {
+    /// The message signed by the proof: the context, identifier and commitments
+    fn proof_message(context: &[u8], identifier: u16, commitments: &[EdwardsPoint]) -> Vec<u8> {
+        let mut message = b"nanopyrs frost dkg".to_vec();
+        message.extend_from_slice(&(context.len() as u64).to_be_bytes());
+        message.extend_from_slice(context);
+        message.extend_from_slice(&identifier.to_be_bytes());
+        for commitment in commitments {
+            message.extend_from_slice(commitment.compress().as_bytes());
+        }
+        message
+    }
+
+    fn is_valid(&self, context: &[u8], threshold: u16) -> bool {
+        let message = DkgCommitment::proof_message(context, self.identifier, &self.commitments);
+        self.commitments.len() == threshold as usize
+            && Account::from(&self.commitments[0]).is_valid_signature(&message, &self.proof)
+    }
+}
+
+/// A share from one participant to another in distributed key generation.
+/// **It must be sent privately** (e.g. with `encryption::encrypt()`), and only to the receiver.
+#[derive(Debug, Clone, Zeroize, ZeroizeOnDrop)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DkgShare {
+    pub sender: u16,
+    pub receiver: u16,
+    pub secret: Scalar,
+}
+
+/// A participant in distributed key generation.
+///
+/// 1. Each participant creates a `DkgParticipant`, and sends its `DkgCommitment` to every other participant
+/// 2. Each participant sends a `DkgShare` (`share_for()`) to every other participant, privately
+/// 3. Each participant calls `finish()`, with every commitment and the shares it received
+///
+/// Each participant ends up with the same `GroupKey`, and its own `KeyShare`.
+#[derive(Debug, Zeroize, ZeroizeOnDrop)]
+pub struct DkgParticipant {
+    identifier: u16,
+    count: u16,
+    context: Vec<u8>,
+    coefficients: Vec<Scalar>,
+}
+impl DkgParticipant {
+    /// Start distributed key generation, as the participant `identifier` (from `1` to `count`).
+    ///
+    /// Every participant must use the same `context`, which must be unique to this session (e.g. a random session ID).
+    pub fn new(
+        context: &[u8],
+        identifier: u16,
+        threshold: u16,
+        count: u16,
+    ) -> Result<(DkgParticipant, DkgCommitment), NanoError> {
+        check_threshold(threshold, count)?;
+        if identifier == 0 || identifier > count {
+            return Err(NanoError::UnknownSigner);
+        }
+        let coefficients: Vec<Scalar> = (0..threshold).map(|_| random_scalar()).collect();
+        let commitments: Vec<EdwardsPoint> = coefficients.iter().map(|c| c.as_ref() * G).collect();
+        let proof = Key::from(coefficients[0].clone()).sign_message(&DkgCommitment::proof_message(
+            context,
+            identifier,
+            &commitments,
+        ));
+        let commitment = DkgCommitment {
+            identifier,
+            commitments,
+            proof,
+        };
+        let participant = DkgParticipant {
+            identifier,
+            count,
+            context: context.to_vec(),
+            coefficients,
+        };
+        Ok((participant, commitment))
+    }
+
+    /// The share for another participant, which must be sent to it privately.
+    ///
+    /// Returns `NanoError::UnknownSigner` if the receiver is not a participant (from `1` to `count`).
+    pub fn share_for(&self, receiver: u16) -> Result<DkgShare, NanoError> {
+        if receiver == 0 || receiver > self.count {
+            return Err(NanoError::UnknownSigner);
+        }
+        Ok(DkgShare {
+            sender: self.identifier,
+            receiver,
+            secret: evaluate(&self.coefficients, receiver),
+        })
+    }
+
+    /// Finish distributed key generation, given every participant's commitment (including this one's),
+    /// and the shares sent to this participant by every other participant.
+    ///
+    /// Returns `NanoError::InvalidShare` if a participant's commitment or share is invalid,
+    /// or `NanoError::InvalidKeySet` if any are missing or duplicated.
+    pub fn finish(
+        self,
+        commitments: &[DkgCommitment],
+        shares: &[DkgShare],
+    ) -> Result<(GroupKey, KeyShare), NanoError> {
+        let threshold = self.coefficients.len() as u16;
+        let mut group = vec![EdwardsPoint::identity(); threshold as usize];
+        let mut secret = evaluate(&self.coefficients, self.identifier);
+
+        for identifier in 1..=self.count {
+            let mut matching = commitments.iter().filter(|c| c.identifier == identifier);
+            let commitment = match (matching.next(), matching.next()) {
+                (Some(commitment), None) => commitment,
+                _ => return Err(NanoError::InvalidKeySet),
+            };
+            if !commitment.is_valid(&self.context, threshold) {
+                return Err(NanoError::InvalidShare(identifier));
+            }
+            for (sum, point) in group.iter_mut().zip(&commitment.commitments) {
+                *sum += point;
+            }
+            if identifier == self.identifier {
+                continue;
+            }
+
+            let mut matching = shares
+                .iter()
+                .filter(|s| s.sender == identifier && s.receiver == self.identifier);
+            let share = match (matching.next(), matching.next()) {
+                (Some(share), None) => share,
+                _ => return Err(NanoError::InvalidKeySet),
+            };
+            if share.secret.as_ref() * G
+                != evaluate_commitments(&commitment.commitments, self.identifier)
+            {
+                return Err(NanoError::InvalidShare(identifier));
+            }
+            secret = Scalar::from(secret.as_ref() + share.secret.as_ref());
+        }
+        if commitments.len() != self.count as usize {
+            return Err(NanoError::InvalidKeySet);
+        }
+
+        let group = GroupKey::new(group)?;
+        Ok((group, KeyShare::new(self.identifier, secret)?))
+    }
+}
+
+/// A signer's secret nonces for a single signature. They must never be reused.
+#[derive(Zeroize, ZeroizeOnDrop)]
+pub struct SigningNonces {
+    identifier: u16,
+    hiding: Scalar,
+    binding: Scalar,
+}
+impl SigningNonces {
+    /// Create random nonces for the share.
+    ///
+    /// The share is mixed in, in case the operating system's randomness is weak.
+    pub fn new(share: &KeyShare) -> SigningNonces {
+        let random = random_bytes::<32>();
+        let nonce = |i: u8| {
+            let data = [random.as_slice(), share.secret.as_slice(), &[i]].concat();
+            scalar!(blake2b512(&data))
+        };
+        SigningNonces {
+            identifier: share.identifier,
+            hiding: nonce(1),
+            binding: nonce(2),
+        }
+    }
+
+    /// The commitment to the nonces, which must be shared with the other signers
+    pub fn commitment(&self) -> SigningCommitment {
+        SigningCommitment {
+            identifier: self.identifier,
+            hiding: self.hiding.as_ref() * G,
+            binding: self.binding.as_ref() * G,
+        }
+    }
+}
+
+/// A signer's commitment to its nonces, which is shared with the other signers in the first round
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SigningCommitment {
+    pub identifier: u16,
+    pub hiding: EdwardsPoint,
+    pub binding: EdwardsPoint,
+}
+impl SigningCommitment {
+    pub fn to_bytes(&self) -> [u8; 66] {
+        [
+            self.identifier.to_be_bytes().as_slice(),
+            self.hiding.compress().as_bytes(),
+            self.binding.compress().as_bytes(),
+        ]
+        .concat()
+        .try_into()
+        .unwrap()
+    }
+
+    pub fn from_bytes(bytes: &[u8; 66]) -> Result<SigningCommitment, NanoError> {
+        Ok(SigningCommitment {
+            identifier: u16::from_be_bytes([bytes[0], bytes[1]]),
+            hiding: try_point_from_slice(&bytes[2..34])?,
+            binding: try_point_from_slice(&bytes[34..])?,
+        })
+    }
+}
+
+/// A signer's share of the signature, which is shared with the other signers in the second round
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SignatureShare {
+    pub identifier: u16,
+    pub share: RawScalar,
+}
+
+#[derive(Debug, Clone)]
+struct Signer {
+    commitment: SigningCommitment,
+    binding_factor: RawScalar,
+    lagrange: RawScalar,
+}
+
+/// The second round of signing a message, once the signers' commitments are known
+#[derive(Debug, Clone)]
+pub struct SigningPackage {
+    group: GroupKey,
+    signers: Vec<Signer>,
+    /// The nonce of the final signature
+    r: EdwardsPoint,
+    /// The challenge of the final signature
+    challenge: RawScalar,
+}
+impl SigningPackage {
+    /// Start signing the message, given the commitments of every signer (in any order).
+    ///
+    /// Returns `NanoError::InvalidKeySet` if there are fewer than `threshold` signers, or any are duplicated.
+    pub fn new(
+        group: &GroupKey,
+        commitments: &[SigningCommitment],
+        message: &[u8],
+    ) -> Result<SigningPackage, NanoError> {
+        let mut commitments = commitments.to_vec();
+        commitments.sort_by_key(|commitment| commitment.identifier);
+        let identifiers: Vec<u16> = commitments.iter().map(|c| c.identifier).collect();
+        if identifiers.len() < group.threshold() as usize
+            || identifiers.contains(&0)
+            || identifiers.windows(2).any(|pair| pair[0] == pair[1])
+        {
+            return Err(NanoError::InvalidKeySet);
+        }
+
+        let account = group.account().compressed.to_bytes();
+        let encoded: Vec<u8> = commitments.iter().flat_map(|c| c.to_bytes()).collect();
+        let mut r = EdwardsPoint::identity();
+        let signers: Vec<Signer> = commitments
+            .into_iter()
+            .map(|commitment| {
+                let binding_factor = hash_scalar(
+                    b"nanopyrs frost binding",
+                    &[
+                        &account,
+                        &commitment.identifier.to_be_bytes(),
+                        &encoded,
+                        message,
+                    ],
+                );
+                r += commitment.hiding + binding_factor * commitment.binding;
+                Signer {
+                    commitment,
+                    binding_factor,
+                    lagrange: lagrange(commitment.identifier, &identifiers),
+                }
+            })
+            .collect();
+        // the same challenge as a normal signature
+        let challenge = hash_scalar(b"", &[r.compress().as_bytes(), &account, message]);
+
+        Ok(SigningPackage {
+            group: group.clone(),
+            signers,
+            r,
+            challenge,
+        })
+    }
+
+    fn signer(&self, identifier: u16) -> Result<&Signer, NanoError> {
+        self.signers
+            .iter()
+            .find(|signer| signer.commitment.identifier == identifier)
+            .ok_or(NanoError::UnknownSigner)
+    }
+
+    /// Create this signer's signature share, using (and consuming) its nonces.
+    ///
+    /// Returns `NanoError::UnknownSigner` if the share, or its nonces, are not part of this signing package.
+    pub fn sign(
+        &self,
+        share: &KeyShare,
+        nonces: SigningNonces,
+    ) -> Result<SignatureShare, NanoError> {
+        let signer = self.signer(share.identifier)?;
+        if nonces.commitment() != signer.commitment {
+            return Err(NanoError::UnknownSigner);
+        }
+        let s = nonces.hiding.as_ref()
+            + signer.binding_factor * nonces.binding.as_ref()
+            + self.challenge * signer.lagrange * share.secret.as_ref();
+        Ok(SignatureShare {
+            identifier: share.identifier,
+            share: s,
+        })
+    }
+
+    /// Check another signer's signature share
+    pub fn is_valid_share(&self, share: &SignatureShare) -> bool {
+        let signer = match self.signer(share.identifier) {
+            Ok(signer) => signer,
+            Err(_) => return false,
+        };
+        let public = evaluate_commitments(&self.group.commitments, share.identifier);
+        share.share * G
+            == signer.commitment.hiding
+                + signer.binding_factor * signer.commitment.binding
+                + (self.challenge * signer.lagrange) * public
+    }
+
+    /// Aggregate every signer's signature share into the group's signature.
+    ///
+    /// Returns `NanoError::InvalidShare` if a share is invalid,
+    /// or `NanoError::InvalidKeySet` if any are missing or duplicated.
+    pub fn aggregate(&self, shares: &[SignatureShare]) -> Result<Signature, NanoError> {
+        let mut s = RawScalar::ZERO;
+        for signer in &self.signers {
+            let identifier = signer.commitment.identifier;
+            let mut matching = shares.iter().filter(|s| s.identifier == identifier);
+            let share = match (matching.next(), matching.next()) {
+                (Some(share), None) => share,
+                _ => return Err(NanoError::InvalidKeySet),
+            };
+            if !self.is_valid_share(share) {
+                return Err(NanoError::InvalidShare(identifier));
+            }
+            s += share.share;
+        }
+        if shares.len() != self.signers.len() {
+            return Err(NanoError::InvalidKeySet);
+        }
+        Ok(Signature { r: self.r, s })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SecretBytes;
+
+    fn key() -> Key {
+        Key::from_seed(&SecretBytes::from([16; 32]), 0)
+    }
+
+    /// Run both rounds of signing with the shares
+    fn sign(
+        group: &GroupKey,
+        shares: &[&KeyShare],
+        message: &[u8],
+    ) -> Result<Signature, NanoError> {
+        let nonces: Vec<SigningNonces> = shares.iter().map(|s| SigningNonces::new(s)).collect();
+        let commitments: Vec<SigningCommitment> = nonces.iter().map(|n| n.commitment()).collect();
+        let package = SigningPackage::new(group, &commitments, message)?;
+        let signature_shares: Vec<SignatureShare> = shares
+            .iter()
+            .zip(nonces)
+            .map(|(share, nonces)| package.sign(share, nonces).unwrap())
+            .collect();
+        package.aggregate(&signature_shares)
+    }
+
+    #[test]
+    fn trusted_dealer() {
+        let (group, shares) = split_key(&key(), 2, 3).unwrap();
+        assert!(group.account() == key().to_account());
+        assert!(group.threshold() == 2);
+        assert!(shares.iter().all(|share| share.is_valid(&group)));
+        assert!(group.share_account(1) == Key::from(shares[0].as_scalar().clone()).to_account());
+
+        for pair in [[0, 1], [0, 2], [2, 1]] {
+            let signers = [&shares[pair[0]], &shares[pair[1]]];
+            let signature = sign(&group, &signers, b"custody").unwrap();
+            assert!(key()
+                .to_account()
+                .is_valid_signature(b"custody", &signature));
+        }
+        let all: Vec<&KeyShare> = shares.iter().collect();
+        let signature = sign(&group, &all, b"custody").unwrap();
+        assert!(key()
+            .to_account()
+            .is_valid_signature(b"custody", &signature));
+
+        assert!(sign(&group, &[&shares[0]], b"custody") == Err(NanoError::InvalidKeySet));
+        assert!(split_key(&key(), 0, 3).is_err());
+        assert!(split_key(&key(), 4, 3).unwrap_err() == NanoError::InvalidThreshold);
+        assert!(KeyShare::new(0, shares[0].as_scalar().clone()).is_err());
+        assert!(GroupKey::new(vec![]).unwrap_err() == NanoError::InvalidThreshold);
+        assert!(GroupKey::new(group.commitments().to_vec()).unwrap() == group);
+    }
+
+    #[test]
+    fn distributed_key_generation() {
+        let (participants, commitments): (Vec<_>, Vec<_>) = (1..=3)
+            .map(|i| DkgParticipant::new(b"session", i, 2, 3).unwrap())
+            .unzip();
+        let shares: Vec<DkgShare> = participants
+            .iter()
+            .flat_map(|p| (1..=3).map(|i| p.share_for(i).unwrap()))
+            .collect();
+
+        // a forged share is detected
+        let mut forged = shares.clone();
+        forged[1].secret = Scalar::from(RawScalar::ONE);
+        let (participant, _) = DkgParticipant::new(b"session", 2, 2, 3).unwrap();
+        assert!(
+            participant.finish(&commitments, &forged).unwrap_err() == NanoError::InvalidShare(1)
+        );
+        // and so is a commitment without a valid proof
+        let mut forged = commitments.clone();
+        forged[2].proof = forged[1].proof;
+        let (participant, _) = DkgParticipant::new(b"session", 2, 2, 3).unwrap();
+        let result = participant.finish(&forged, &shares);
+        assert!(result.unwrap_err() == NanoError::InvalidShare(3));
+        // including a proof replayed from another session
+        let (_, replayed) = DkgParticipant::new(b"other session", 3, 2, 3).unwrap();
+        let mut forged = commitments.clone();
+        forged[2] = replayed;
+        let (participant, _) = DkgParticipant::new(b"session", 2, 2, 3).unwrap();
+        let result = participant.finish(&forged, &shares);
+        assert!(result.unwrap_err() == NanoError::InvalidShare(3));
+
+        // shares can only be made for participants, never for zero (the participant's secret)
+        assert!(participants[0].share_for(0).unwrap_err() == NanoError::UnknownSigner);
+        assert!(participants[0].share_for(4).unwrap_err() == NanoError::UnknownSigner);
+
+        let results: Vec<(GroupKey, KeyShare)> = participants
+            .into_iter()
+            .map(|p| p.finish(&commitments, &shares).unwrap())
+            .collect();
+        let group = results[0].0.clone();
+        assert!(results
+            .iter()
+            .all(|(g, share)| g == &group && share.is_valid(&group)));
+
+        let signature = sign(&group, &[&results[2].1, &results[0].1], b"block").unwrap();
+        assert!(group.account().is_valid_signature(b"block", &signature));
+    }
+
+    #[test]
+    fn invalid_shares() {
+        let (group, shares) = split_key(&key(), 2, 3).unwrap();
+        let nonces = [
+            SigningNonces::new(&shares[0]),
+            SigningNonces::new(&shares[1]),
+        ];
+        let commitments = [nonces[0].commitment(), nonces[1].commitment()];
+        let package = SigningPackage::new(&group, &commitments, b"message").unwrap();
+
+        let [nonces_0, nonces_1] = nonces;
+        // nonces which do not match the share
+        assert!(package.sign(&shares[1], nonces_0).unwrap_err() == NanoError::UnknownSigner);
+        let share_1 = package.sign(&shares[1], nonces_1).unwrap();
+        assert!(package.is_valid_share(&share_1));
+
+        let forged = SignatureShare {
+            identifier: 1,
+            share: share_1.share,
+        };
+        assert!(!package.is_valid_share(&forged));
+        assert!(package.aggregate(&[forged, share_1]) == Err(NanoError::InvalidShare(1)));
+        assert!(package.aggregate(&[share_1]) == Err(NanoError::InvalidKeySet));
+
+        let bytes = commitments[0].to_bytes();
+        assert!(SigningCommitment::from_bytes(&bytes).unwrap() == commitments[0]);
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "serde")]
+mod serde_tests {
+    use super::*;
+    use crate::SecretBytes;
+
+    #[test]
+    fn group_key() {
+        let key = Key::from_seed(&SecretBytes::from([16; 32]), 0);
+        let (group, _) = split_key(&key, 2, 3).unwrap();
+        let bytes = bincode::serialize(&group).unwrap();
+        assert!(bincode::deserialize::<GroupKey>(&bytes).unwrap() == group);
+
+        let empty = bincode::serialize(&Vec::<EdwardsPoint>::new()).unwrap();
+        assert!(bincode::deserialize::<GroupKey>(&empty).is_err());
+    }
+}
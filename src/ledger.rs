@@ -0,0 +1,659 @@
+//! An in-memory ledger, which applies blocks according to the node's validation rules.
+//!
+//! This is intended for testing wallet logic without a node. Blocks are applied immediately,
+//! without voting or confirmation, and only `state` blocks are supported.
+
+use crate::{Account, Block, BlockType, Epoch, Network};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::Display;
+
+/// Why a block was rejected by `Ledger::process`. These mirror the node's process results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessError {
+    /// The block has already been processed
+    Old,
+    /// The block's signature is invalid
+    BadSignature,
+    /// The block's work is below the network's threshold
+    InsufficientWork,
+    /// The `previous` block is unknown
+    GapPrevious,
+    /// The `source` block (the `link` field of a `receive` block) is unknown
+    GapSource,
+    /// An `epoch` block tried to open an account which has nothing to receive
+    GapEpochOpenPending,
+    /// Another block already follows the `previous` block, or the account has already been opened
+    Fork,
+    /// A `send` block does not decrease the balance
+    NegativeSpend,
+    /// The source is not receivable by this account, or has already been received
+    Unreceivable,
+    /// The change in balance does not match the block type (or the amount being received)
+    BalanceMismatch,
+    /// An `epoch` block changed the account's representative, or opened an account with a non-zero representative
+    RepresentativeMismatch,
+    /// The block cannot be at this position in the account's blockchain,
+    /// such as an `epoch` block upgrading to the wrong epoch, or a `send` block opening an account
+    BlockPosition,
+    /// Legacy blocks are not supported
+    LegacyBlock,
+}
+impl Display for ProcessError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let string = match &self {
+            ProcessError::Old => "old block",
+            ProcessError::BadSignature => "bad signature",
+            ProcessError::InsufficientWork => "insufficient work",
+            ProcessError::GapPrevious => "gap previous block",
+            ProcessError::GapSource => "gap source block",
+            ProcessError::GapEpochOpenPending => "gap pending for open epoch block",
+            ProcessError::Fork => "fork",
+            ProcessError::NegativeSpend => "negative spend",
+            ProcessError::Unreceivable => "unreceivable",
+            ProcessError::BalanceMismatch => "balance and amount mismatch",
+            ProcessError::RepresentativeMismatch => "representative mismatch",
+            ProcessError::BlockPosition => "block position",
+            ProcessError::LegacyBlock => "legacy blocks are not supported",
+        };
+        write!(f, "{string}")
+    }
+}
+impl Error for ProcessError {}
+
+/// General info about an account in the `Ledger`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountInfo {
+    /// Hash of the frontier block of this account
+    pub frontier: [u8; 32],
+    /// Hash of the first block of this account
+    pub open_block: [u8; 32],
+    pub balance: u128,
+    /// Number of blocks in this account's history
+    pub block_count: usize,
+    pub representative: Account,
+    /// The voting weight delegated to this account
+    pub weight: u128,
+    /// The number of receivable transactions for this account
+    pub receivable: usize,
+    /// The account's epoch, or `None` if it has not been upgraded
+    pub epoch: Option<Epoch>,
+}
+
+/// A receivable (pending) transaction in the `Ledger`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pending {
+    /// Hash of the send block
+    pub hash: [u8; 32],
+    /// The sending account
+    pub source: Account,
+    pub amount: u128,
+    /// The sending account's epoch, at the time of the send
+    pub epoch: Option<Epoch>,
+}
+
+#[derive(Debug, Clone)]
+struct AccountState {
+    frontier: [u8; 32],
+    open_block: [u8; 32],
+    balance: u128,
+    block_count: usize,
+    representative: Account,
+    epoch: Option<Epoch>,
+}
+
+/// An in-memory ledger for a `Network`
+#[derive(Debug, Clone)]
+pub struct Ledger {
+    network: Network,
+    blocks: HashMap<[u8; 32], Block>,
+    accounts: HashMap<Account, AccountState>,
+    /// Receivable transactions, by destination public key and send hash
+    receivable: HashMap<[u8; 32], HashMap<[u8; 32], Pending>>,
+    weights: HashMap<Account, u128>,
+}
+impl Ledger {
+    /// Create a ledger in which the network's genesis account holds the entire supply (`u128::MAX`).
+    ///
+    /// The genesis account is opened by an unsigned `receive` block, which is not validated.
+    /// Its hash therefore differs from the real network's genesis block.
+    pub fn new(network: Network) -> Ledger {
+        let genesis = Block {
+            block_type: BlockType::Receive,
+            account: network.genesis_account.clone(),
+            previous: [0; 32],
+            representative: network.genesis_account.clone(),
+            balance: u128::MAX,
+            link: network.genesis_account.compressed.to_bytes(),
+            signature: Default::default(),
+            work: [0; 8],
+        };
+        Ledger::with_genesis(network, genesis)
+    }
+
+    /// Create a ledger whose only block is `genesis`, which is not validated
+    pub fn with_genesis(network: Network, genesis: Block) -> Ledger {
+        let mut ledger = Ledger {
+            network,
+            blocks: HashMap::new(),
+            accounts: HashMap::new(),
+            receivable: HashMap::new(),
+            weights: HashMap::new(),
+        };
+        let hash = genesis.hash();
+        ledger.add_weight(&genesis.representative, genesis.balance);
+        ledger.accounts.insert(
+            genesis.account.clone(),
+            AccountState {
+                frontier: hash,
+                open_block: hash,
+                balance: genesis.balance,
+                block_count: 1,
+                representative: genesis.representative.clone(),
+                epoch: None,
+            },
+        );
+        ledger.blocks.insert(hash, genesis);
+        ledger
+    }
+
+    pub fn network(&self) -> &Network {
+        &self.network
+    }
+
+    /// Validate and apply the block, returning its hash
+    pub fn process(&mut self, block: &Block) -> Result<[u8; 32], ProcessError> {
+        let hash = block.hash();
+        if self.blocks.contains_key(&hash) {
+            return Err(ProcessError::Old);
+        }
+        if block.block_type.is_legacy() {
+            return Err(ProcessError::LegacyBlock);
+        }
+        if !block.has_valid_work_on(&self.network) {
+            return Err(ProcessError::InsufficientWork);
+        }
+        if !block.has_valid_signature_on(&self.network) {
+            return Err(ProcessError::BadSignature);
+        }
+
+        let opening = block.previous == [0; 32];
+        let state = self.accounts.get(&block.account);
+        match (state, opening) {
+            (Some(_), true) => return Err(ProcessError::Fork),
+            (Some(state), false) if state.frontier != block.previous => {
+                return match self.blocks.contains_key(&block.previous) {
+                    true => Err(ProcessError::Fork),
+                    false => Err(ProcessError::GapPrevious),
+                };
+            }
+            (None, false) => return Err(ProcessError::GapPrevious),
+            _ => (),
+        }
+        let previous_balance = state.map(|state| state.balance).unwrap_or(0);
+        let mut epoch = state.and_then(|state| state.epoch);
+        let receivable = self.receivable.get(&block.account.compressed.to_bytes());
+
+        match block.block_type {
+            BlockType::Send => {
+                if opening {
+                    return Err(ProcessError::BlockPosition);
+                }
+                if block.balance >= previous_balance {
+                    return Err(ProcessError::NegativeSpend);
+                }
+            }
+            BlockType::Receive => {
+                if !self.blocks.contains_key(&block.link) {
+                    return Err(ProcessError::GapSource);
+                }
+                let pending = receivable
+                    .and_then(|receivable| receivable.get(&block.link))
+                    .ok_or(ProcessError::Unreceivable)?;
+                if block.balance.checked_sub(previous_balance) != Some(pending.amount) {
+                    return Err(ProcessError::BalanceMismatch);
+                }
+                epoch = epoch.max(pending.epoch);
+            }
+            BlockType::Change => {
+                if opening {
+                    return Err(ProcessError::BlockPosition);
+                }
+                // the node treats a link as receiving nothing
+                if block.balance != previous_balance || block.link != [0; 32] {
+                    return Err(ProcessError::BalanceMismatch);
+                }
+            }
+            BlockType::Epoch => {
+                let upgrade = block.epoch().ok_or(ProcessError::BlockPosition)?;
+                if block.balance != previous_balance {
+                    return Err(ProcessError::BalanceMismatch);
+                }
+                match state {
+                    Some(state) if state.representative != block.representative => {
+                        return Err(ProcessError::RepresentativeMismatch)
+                    }
+                    // epoch blocks which open an account must have a zero representative
+                    None if block.representative.compressed.to_bytes() != [0; 32] => {
+                        return Err(ProcessError::RepresentativeMismatch)
+                    }
+                    None if receivable.is_none() => return Err(ProcessError::GapEpochOpenPending),
+                    _ => (),
+                }
+                if next_epoch(epoch) != Some(upgrade) {
+                    return Err(ProcessError::BlockPosition);
+                }
+                epoch = Some(upgrade);
+            }
+            BlockType::Legacy(_) => unreachable!("legacy blocks were rejected above"),
+        }
+
+        let state = match self.accounts.remove(&block.account) {
+            Some(state) => {
+                self.subtract_weight(&state.representative, state.balance);
+                AccountState {
+                    frontier: hash,
+                    block_count: state.block_count + 1,
+                    balance: block.balance,
+                    representative: block.representative.clone(),
+                    epoch,
+                    ..state
+                }
+            }
+            None => AccountState {
+                frontier: hash,
+                open_block: hash,
+                balance: block.balance,
+                block_count: 1,
+                representative: block.representative.clone(),
+                epoch,
+            },
+        };
+        self.add_weight(&block.representative, block.balance);
+        self.accounts.insert(block.account.clone(), state);
+
+        match block.block_type {
+            BlockType::Send => {
+                let pending = Pending {
+                    hash,
+                    source: block.account.clone(),
+                    amount: previous_balance - block.balance,
+                    epoch,
+                };
+                self.receivable
+                    .entry(block.link)
+                    .or_default()
+                    .insert(hash, pending);
+            }
+            BlockType::Receive => {
+                let destination = block.account.compressed.to_bytes();
+                let receivable = self.receivable.get_mut(&destination).unwrap();
+                receivable.remove(&block.link);
+                if receivable.is_empty() {
+                    self.receivable.remove(&destination);
+                }
+            }
+            _ => (),
+        }
+        self.blocks.insert(hash, block.clone());
+        Ok(hash)
+    }
+
+    /// The number of blocks in the ledger, including the genesis block
+    pub fn block_count(&self) -> usize {
+        self.blocks.len()
+    }
+
+    pub fn block(&self, hash: [u8; 32]) -> Option<&Block> {
+        self.blocks.get(&hash)
+    }
+
+    /// Info about the account, or `None` if it has not been opened
+    pub fn account_info(&self, account: &Account) -> Option<AccountInfo> {
+        let state = self.accounts.get(account)?;
+        Some(AccountInfo {
+            frontier: state.frontier,
+            open_block: state.open_block,
+            balance: state.balance,
+            block_count: state.block_count,
+            representative: state.representative.clone(),
+            weight: self.account_weight(account),
+            receivable: self.receivable(account, usize::MAX, 0).len(),
+            epoch: state.epoch,
+        })
+    }
+
+    /// The account's balance, or `0` if it has not been opened
+    pub fn account_balance(&self, account: &Account) -> u128 {
+        self.accounts.get(account).map_or(0, |state| state.balance)
+    }
+
+    /// The total balance of the accounts which have chosen `account` as their representative
+    pub fn account_weight(&self, account: &Account) -> u128 {
+        self.weights.get(account).copied().unwrap_or(0)
+    }
+
+    /// Up to `count` of the account's blocks, starting from the frontier
+    pub fn account_history(&self, account: &Account, count: usize) -> Vec<Block> {
+        let mut hash = match self.accounts.get(account) {
+            Some(state) => state.frontier,
+            None => return vec![],
+        };
+        let mut history = vec![];
+        while let Some(block) = self.blocks.get(&hash) {
+            if history.len() == count {
+                break;
+            }
+            history.push(block.clone());
+            hash = block.previous;
+        }
+        history
+    }
+
+    /// Up to `count` of the account's receivable transactions of at least `threshold` raw, largest first
+    pub fn receivable(&self, account: &Account, count: usize, threshold: u128) -> Vec<Pending> {
+        let Some(receivable) = self.receivable.get(&account.compressed.to_bytes()) else {
+            return vec![];
+        };
+        let mut receivable: Vec<Pending> = receivable
+            .values()
+            .filter(|pending| pending.amount >= threshold)
+            .cloned()
+            .collect();
+        receivable.sort_by(|a, b| b.amount.cmp(&a.amount).then(a.hash.cmp(&b.hash)));
+        receivable.truncate(count);
+        receivable
+    }
+
+    /// Returns `true` if the send block exists, and has not been received yet
+    pub fn receivable_exists(&self, hash: [u8; 32]) -> bool {
+        self.receivable
+            .values()
+            .any(|receivable| receivable.contains_key(&hash))
+    }
+
+    fn add_weight(&mut self, representative: &Account, amount: u128) {
+        if amount != 0 {
+            *self.weights.entry(representative.clone()).or_default() += amount
+        }
+    }
+
+    fn subtract_weight(&mut self, representative: &Account, amount: u128) {
+        if amount == 0 {
+            return;
+        }
+        let weight = self.weights.get_mut(representative).unwrap();
+        *weight -= amount;
+        if *weight == 0 {
+            self.weights.remove(representative);
+        }
+    }
+}
+
+fn next_epoch(epoch: Option<Epoch>) -> Option<Epoch> {
+    match epoch {
+        None => Some(Epoch::V1),
+        Some(Epoch::V1) => Some(Epoch::V2),
+        Some(Epoch::V2) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Key, SecretBytes, Signature};
+
+    const AMOUNT: u128 = 1_000;
+
+    fn key(i: u32) -> Key {
+        Key::from_seed(&SecretBytes::from([8; 32]), i)
+    }
+
+    fn ledger() -> Ledger {
+        Ledger::new(Network::dev(key(0).to_account()))
+    }
+
+    /// Create a signed block with valid work, on top of the account's frontier
+    fn block(
+        ledger: &Ledger,
+        key: &Key,
+        block_type: BlockType,
+        balance: u128,
+        link: [u8; 32],
+    ) -> Block {
+        let account = key.to_account();
+        let (previous, representative) = match ledger.account_info(&account) {
+            Some(info) => (info.frontier, info.representative),
+            None => ([0; 32], account.clone()),
+        };
+        let mut block = Block {
+            block_type,
+            account,
+            previous,
+            representative,
+            balance,
+            link,
+            signature: Signature::default(),
+            work: [0; 8],
+        };
+        block.sign(key);
+        block.local_work(ledger.network().work_difficulty(&block.block_type));
+        block
+    }
+
+    fn send(ledger: &mut Ledger, from: &Key, to: &Key, amount: u128) -> [u8; 32] {
+        let balance = ledger.account_balance(&from.to_account()) - amount;
+        let link = to.to_account().compressed.to_bytes();
+        let block = block(ledger, from, BlockType::Send, balance, link);
+        ledger.process(&block).unwrap()
+    }
+
+    #[test]
+    fn send_and_receive() {
+        let mut ledger = ledger();
+        let genesis = key(0).to_account();
+        let hash = send(&mut ledger, &key(0), &key(1), AMOUNT);
+        assert!(ledger.receivable_exists(hash));
+        assert!(ledger
+            .receivable(&key(1).to_account(), 10, AMOUNT + 1)
+            .is_empty());
+
+        let pending = &ledger.receivable(&key(1).to_account(), 10, 0)[0];
+        assert!(pending.amount == AMOUNT && pending.source == genesis);
+
+        let open = block(&ledger, &key(1), BlockType::Receive, AMOUNT, hash);
+        let open_hash = ledger.process(&open).unwrap();
+        assert!(!ledger.receivable_exists(hash));
+        assert!(ledger.process(&open) == Err(ProcessError::Old));
+
+        let info = ledger.account_info(&key(1).to_account()).unwrap();
+        assert!(info.frontier == open_hash && info.open_block == open_hash);
+        assert!(info.balance == AMOUNT && info.receivable == 0 && info.weight == AMOUNT);
+        assert!(ledger.account_weight(&genesis) == u128::MAX - AMOUNT);
+        assert!(ledger.account_history(&genesis, 10).len() == 2);
+        assert!(ledger.block_count() == 3);
+    }
+
+    #[test]
+    fn representative_weights() {
+        let mut ledger = ledger();
+        let hash = send(&mut ledger, &key(0), &key(1), AMOUNT);
+        let open = block(&ledger, &key(1), BlockType::Receive, AMOUNT, hash);
+        ledger.process(&open).unwrap();
+
+        let mut change = block(&ledger, &key(1), BlockType::Change, AMOUNT, [0; 32]);
+        change.representative = key(2).to_account();
+        change.sign(&key(1));
+        ledger.process(&change).unwrap();
+        assert!(ledger.account_weight(&key(1).to_account()) == 0);
+        assert!(ledger.account_weight(&key(2).to_account()) == AMOUNT);
+
+        send(&mut ledger, &key(1), &key(0), 400);
+        assert!(ledger.account_weight(&key(2).to_account()) == 600);
+    }
+
+    #[test]
+    fn empty_accounts() {
+        let mut ledger = ledger();
+        // two accounts with the same representative, which both send everything
+        for i in [1, 2] {
+            let hash = send(&mut ledger, &key(0), &key(i), AMOUNT);
+            let mut open = block(&ledger, &key(i), BlockType::Receive, AMOUNT, hash);
+            open.representative = key(3).to_account();
+            open.sign(&key(i));
+            ledger.process(&open).unwrap();
+        }
+        for i in [1, 2] {
+            send(&mut ledger, &key(i), &key(0), AMOUNT);
+        }
+        assert!(ledger.account_weight(&key(3).to_account()) == 0);
+
+        for i in [1, 2] {
+            let change = block(&ledger, &key(i), BlockType::Change, 0, [0; 32]);
+            ledger.process(&change).unwrap();
+        }
+        assert!(ledger.account_weight(&key(3).to_account()) == 0);
+    }
+
+    #[test]
+    fn invalid_blocks() {
+        let mut ledger = ledger();
+        let hash = send(&mut ledger, &key(0), &key(1), AMOUNT);
+
+        // fork of the send
+        let mut fork = block(&ledger, &key(0), BlockType::Send, 5, [1; 32]);
+        fork.previous = ledger.block(hash).unwrap().previous;
+        fork.sign(&key(0));
+        fork.local_work(ledger.network().send_work_difficulty);
+        assert!(ledger.process(&fork) == Err(ProcessError::Fork));
+
+        let mut gap = block(&ledger, &key(0), BlockType::Change, 0, [0; 32]);
+        gap.previous = [9; 32];
+        gap.sign(&key(0));
+        gap.local_work(ledger.network().send_work_difficulty);
+        assert!(ledger.process(&gap) == Err(ProcessError::GapPrevious));
+
+        let mut unsigned = block(&ledger, &key(1), BlockType::Receive, AMOUNT, hash);
+        unsigned.signature = Signature::default();
+        assert!(ledger.process(&unsigned) == Err(ProcessError::BadSignature));
+
+        let mut no_work = block(&ledger, &key(1), BlockType::Receive, AMOUNT, hash);
+        no_work.work = [0; 8];
+        assert!(ledger.process(&no_work) == Err(ProcessError::InsufficientWork));
+
+        let cases = [
+            (
+                key(1),
+                BlockType::Receive,
+                AMOUNT,
+                [7; 32],
+                ProcessError::GapSource,
+            ),
+            (
+                key(2),
+                BlockType::Receive,
+                AMOUNT,
+                hash,
+                ProcessError::Unreceivable,
+            ),
+            (
+                key(1),
+                BlockType::Receive,
+                1,
+                hash,
+                ProcessError::BalanceMismatch,
+            ),
+            (
+                key(1),
+                BlockType::Send,
+                0,
+                [0; 32],
+                ProcessError::BlockPosition,
+            ),
+            (
+                key(0),
+                BlockType::Send,
+                u128::MAX,
+                [0; 32],
+                ProcessError::NegativeSpend,
+            ),
+            (
+                key(0),
+                BlockType::Change,
+                0,
+                [0; 32],
+                ProcessError::BalanceMismatch,
+            ),
+            // a change block cannot receive
+            (
+                key(0),
+                BlockType::Change,
+                u128::MAX - AMOUNT,
+                hash,
+                ProcessError::BalanceMismatch,
+            ),
+        ];
+        for (key, block_type, balance, link, error) in cases {
+            let block = block(&ledger, &key, block_type, balance, link);
+            assert!(ledger.process(&block) == Err(error));
+        }
+    }
+
+    #[test]
+    fn epochs() {
+        let mut ledger = ledger();
+        let signer = key(0);
+        let epoch_block = |ledger: &Ledger, account: &Key, epoch: Epoch| {
+            let frontier = ledger.account_info(&account.to_account()).unwrap().frontier;
+            let mut block = epoch.upgrade_block(ledger.block(frontier).unwrap());
+            block.sign(&signer);
+            block
+        };
+
+        let hash = send(&mut ledger, &key(0), &key(1), AMOUNT);
+        let open = block(&ledger, &key(1), BlockType::Receive, AMOUNT, hash);
+        ledger.process(&open).unwrap();
+
+        let v2 = epoch_block(&ledger, &key(1), Epoch::V2);
+        assert!(ledger.process(&v2) == Err(ProcessError::BlockPosition));
+        let mut changed = epoch_block(&ledger, &key(1), Epoch::V1);
+        changed.representative = key(2).to_account();
+        changed.sign(&signer);
+        assert!(ledger.process(&changed) == Err(ProcessError::RepresentativeMismatch));
+        let mut unsigned = epoch_block(&ledger, &key(1), Epoch::V1);
+        unsigned.sign(&key(1));
+        assert!(ledger.process(&unsigned) == Err(ProcessError::BadSignature));
+
+        ledger
+            .process(&epoch_block(&ledger, &key(1), Epoch::V1))
+            .unwrap();
+        ledger
+            .process(&epoch_block(&ledger, &key(1), Epoch::V2))
+            .unwrap();
+        let info = ledger.account_info(&key(1).to_account()).unwrap();
+        assert!(info.epoch == Some(Epoch::V2) && info.block_count == 3);
+
+        // receiving from an upgraded account upgrades the receiver
+        let hash = send(&mut ledger, &key(1), &key(3), 10);
+        let mut epoch_open = Block {
+            block_type: BlockType::Epoch,
+            account: key(4).to_account(),
+            previous: [0; 32],
+            representative: Account::try_from([0; 32]).unwrap(),
+            balance: 0,
+            link: Epoch::V1.link(),
+            signature: Signature::default(),
+            work: [0; 8],
+        };
+        epoch_open.sign(&signer);
+        assert!(ledger.process(&epoch_open) == Err(ProcessError::GapEpochOpenPending));
+        epoch_open.representative = key(4).to_account();
+        epoch_open.sign(&signer);
+        assert!(ledger.process(&epoch_open) == Err(ProcessError::RepresentativeMismatch));
+
+        let open = block(&ledger, &key(3), BlockType::Receive, 10, hash);
+        ledger.process(&open).unwrap();
+        let info = ledger.account_info(&key(3).to_account()).unwrap();
+        assert!(info.epoch == Some(Epoch::V2));
+    }
+}
@@ -3,29 +3,50 @@
 #[cfg(test)]
 #[cfg(not(feature = "serde"))]
 use bincode as _;
+#[cfg(test)]
+#[cfg(not(feature = "rpc"))]
+use tokio as _;
+// only used by the `nanopyrs` binary
+#[cfg(feature = "cli")]
+use clap as _;
 
 mod account;
 mod error;
 mod nanopy;
 mod secrets;
 
+pub mod adaptor;
 pub mod base32;
 pub mod block;
 /// Various Nano-related constants
 pub mod constants;
+pub mod frost;
 /// Various hash functions
 pub mod hashes;
+pub mod ledger;
+pub mod musig;
+pub mod network;
+pub mod p2p;
+pub mod shamir;
 pub mod signature;
+pub mod telemetry;
+pub mod vote;
 
 pub use account::{Account, Key};
-pub use block::{Block, BlockType};
+pub use block::{Block, BlockType, Epoch};
 pub use error::NanoError;
+pub use network::Network;
 pub use secrets::{Scalar, SecretBytes};
 pub use signature::Signature;
+pub use telemetry::Telemetry;
+pub use vote::Vote;
 
 #[cfg(feature = "camo")]
 pub mod camo;
 
+#[cfg(feature = "encryption")]
+pub mod encryption;
+
 #[cfg(feature = "rpc")]
 pub mod rpc;
 
@@ -0,0 +1,373 @@
+//! Multi-signature accounts, using MuSig2.
+//!
+//! Several keys are aggregated into a single Nano account, whose blocks can only be signed by all of the keys together.
+//! The resulting signatures are normal ed25519-blake2b signatures, accepted by the node and `is_valid_signature`.
+//!
+//! Signing takes two rounds of communication between the signers:
+//! 1. Each signer creates a `SecretNonce` for the message, and shares its `PublicNonce`
+//! 2. Once all public nonces are known, each signer creates a `SigningSession` and shares its `PartialSignature`
+//!
+//! Any signer can then combine the partial signatures into the final `Signature`.
+//! Each key is weighted by a coefficient derived from every signer's key, which prevents rogue-key attacks.
+//!
+//! **A `SecretNonce` must never be used more than once.** It is consumed when signing to help with this,
+//! but it must also not be copied, stored and restored, or otherwise reused.
+
+use crate::hashes::{blake2b256, blake2b512};
+use crate::secrets::random_bytes;
+use crate::{scalar, try_point_from_slice, Account, Key, NanoError, Scalar, Signature};
+use curve25519_dalek::{
+    constants::ED25519_BASEPOINT_POINT as G, EdwardsPoint, Scalar as RawScalar,
+};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// Hash the data (with a domain separator) to a scalar
+fn hash_scalar(domain: &[u8], data: &[&[u8]]) -> RawScalar {
+    let data = [&[domain], data].concat().concat();
+    *scalar!(blake2b512(&data)).as_ref()
+}
+
+/// The aggregation of several signers' keys into a single account
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyAggregation {
+    /// The signers' accounts, sorted
+    signers: Vec<Account>,
+    coefficients: Vec<RawScalar>,
+    account: Account,
+}
+impl KeyAggregation {
+    /// Aggregate the signers' accounts. The order of the accounts does not matter.
+    ///
+    /// Returns `NanoError::InvalidKeySet` if there are no accounts, if any are duplicated,
+    /// or if the aggregated key has a small order (which anybody could sign for).
+    pub fn new(accounts: &[Account]) -> Result<KeyAggregation, NanoError> {
+        let mut signers = accounts.to_vec();
+        signers.sort_by_key(|account| account.compressed.to_bytes());
+        signers.dedup();
+        if signers.is_empty() || signers.len() != accounts.len() {
+            return Err(NanoError::InvalidKeySet);
+        }
+
+        let list: Vec<u8> = signers
+            .iter()
+            .flat_map(|account| account.compressed.to_bytes())
+            .collect();
+        let list_hash = blake2b256(&list);
+        let coefficients: Vec<RawScalar> = signers
+            .iter()
+            .map(|account| {
+                hash_scalar(
+                    b"musig coefficient",
+                    &[list_hash.as_slice(), account.compressed.as_bytes()],
+                )
+            })
+            .collect();
+        let point = signers
+            .iter()
+            .zip(&coefficients)
+            .map(|(account, coefficient)| coefficient * account.point)
+            .sum::<EdwardsPoint>();
+        if point.is_small_order() {
+            return Err(NanoError::InvalidKeySet);
+        }
+
+        Ok(KeyAggregation {
+            account: Account::from(&point),
+            signers,
+            coefficients,
+        })
+    }
+
+    /// The aggregated account, which requires every signer to sign
+    pub fn account(&self) -> &Account {
+        &self.account
+    }
+
+    /// The signers' accounts, sorted
+    pub fn signers(&self) -> &[Account] {
+        &self.signers
+    }
+
+    fn coefficient(&self, account: &Account) -> Result<RawScalar, NanoError> {
+        self.signers
+            .iter()
+            .position(|signer| signer == account)
+            .map(|i| self.coefficients[i])
+            .ok_or(NanoError::UnknownSigner)
+    }
+}
+
+/// A signer's secret nonce for a single signature. It must never be reused.
+#[derive(Zeroize, ZeroizeOnDrop)]
+pub struct SecretNonce {
+    r_1: Scalar,
+    r_2: Scalar,
+}
+impl SecretNonce {
+    /// Create a random nonce, for signing the message with the key.
+    ///
+    /// The key and message are mixed in, in case the operating system's randomness is weak.
+    pub fn new(key: &Key, message: &[u8]) -> SecretNonce {
+        let random = random_bytes::<32>();
+        let nonce = |i: u8| {
+            let data = [random.as_slice(), key.as_bytes(), &[i], message].concat();
+            scalar!(blake2b512(&data))
+        };
+        SecretNonce {
+            r_1: nonce(1),
+            r_2: nonce(2),
+        }
+    }
+
+    /// The public nonce, which must be shared with the other signers
+    pub fn public(&self) -> PublicNonce {
+        PublicNonce {
+            r_1: self.r_1.as_ref() * G,
+            r_2: self.r_2.as_ref() * G,
+        }
+    }
+}
+
+/// A signer's public nonce, which is shared with the other signers in the first round
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PublicNonce {
+    pub r_1: EdwardsPoint,
+    pub r_2: EdwardsPoint,
+}
+impl PublicNonce {
+    pub fn to_bytes(&self) -> [u8; 64] {
+        [
+            self.r_1.compress().to_bytes(),
+            self.r_2.compress().to_bytes(),
+        ]
+        .concat()
+        .try_into()
+        .unwrap()
+    }
+
+    pub fn from_bytes(bytes: &[u8; 64]) -> Result<PublicNonce, NanoError> {
+        Ok(PublicNonce {
+            r_1: try_point_from_slice(&bytes[..32])?,
+            r_2: try_point_from_slice(&bytes[32..])?,
+        })
+    }
+}
+
+/// A signer's share of the signature, which is shared with the other signers in the second round
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PartialSignature(pub RawScalar);
+impl PartialSignature {
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.0.to_bytes()
+    }
+
+    pub fn from_bytes(bytes: &[u8; 32]) -> Result<PartialSignature, NanoError> {
+        Ok(PartialSignature(
+            *Scalar::from_canonical_bytes(*bytes)?.as_ref(),
+        ))
+    }
+}
+
+/// The second round of signing a message, once every signer's public nonce is known
+#[derive(Debug, Clone)]
+pub struct SigningSession {
+    aggregation: KeyAggregation,
+    /// `b`, which weighs each signer's second nonce
+    nonce_coefficient: RawScalar,
+    /// The nonce of the final signature
+    r: EdwardsPoint,
+    /// The challenge of the final signature
+    challenge: RawScalar,
+}
+impl SigningSession {
+    /// Start signing the message, given the public nonces of every signer (in any order).
+    ///
+    /// Returns `NanoError::InvalidKeySet` if there is not exactly one nonce per signer.
+    pub fn new(
+        aggregation: &KeyAggregation,
+        nonces: &[PublicNonce],
+        message: &[u8],
+    ) -> Result<SigningSession, NanoError> {
+        if nonces.len() != aggregation.signers.len() {
+            return Err(NanoError::InvalidKeySet);
+        }
+        let r_1: EdwardsPoint = nonces.iter().map(|nonce| nonce.r_1).sum();
+        let r_2: EdwardsPoint = nonces.iter().map(|nonce| nonce.r_2).sum();
+        let account = aggregation.account.compressed.to_bytes();
+        let nonce_coefficient = hash_scalar(
+            b"musig nonce",
+            &[
+                &account,
+                r_1.compress().as_bytes(),
+                r_2.compress().as_bytes(),
+                message,
+            ],
+        );
+        let r = r_1 + nonce_coefficient * r_2;
+        // the same challenge as a normal signature
+        let challenge = hash_scalar(b"", &[r.compress().as_bytes(), &account, message]);
+        Ok(SigningSession {
+            aggregation: aggregation.clone(),
+            nonce_coefficient,
+            r,
+            challenge,
+        })
+    }
+
+    /// Create this signer's partial signature, using (and consuming) its secret nonce
+    pub fn partial_sign(
+        &self,
+        key: &Key,
+        nonce: SecretNonce,
+    ) -> Result<PartialSignature, NanoError> {
+        let coefficient = self.aggregation.coefficient(&key.to_account())?;
+        let s = nonce.r_1.as_ref()
+            + self.nonce_coefficient * nonce.r_2.as_ref()
+            + self.challenge * coefficient * key.as_scalar().as_ref();
+        Ok(PartialSignature(s))
+    }
+
+    /// Check another signer's partial signature, given its account and public nonce
+    pub fn is_valid_partial(
+        &self,
+        account: &Account,
+        nonce: &PublicNonce,
+        partial: &PartialSignature,
+    ) -> bool {
+        let coefficient = match self.aggregation.coefficient(account) {
+            Ok(coefficient) => coefficient,
+            Err(_) => return false,
+        };
+        partial.0 * G
+            == nonce.r_1
+                + self.nonce_coefficient * nonce.r_2
+                + (self.challenge * coefficient) * account.point
+    }
+
+    /// Combine every signer's partial signature into the aggregated account's signature.
+    ///
+    /// Returns `NanoError::InvalidKeySet` if there is not exactly one partial signature per signer,
+    /// or if they do not combine into a valid signature (`is_valid_partial` shows which one is invalid).
+    pub fn combine(&self, partials: &[PartialSignature]) -> Result<Signature, NanoError> {
+        if partials.len() != self.aggregation.signers.len() {
+            return Err(NanoError::InvalidKeySet);
+        }
+        let s: RawScalar = partials.iter().map(|partial| partial.0).sum();
+        if s * G != self.r + self.challenge * self.aggregation.account.point {
+            return Err(NanoError::InvalidKeySet);
+        }
+        Ok(Signature { r: self.r, s })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SecretBytes;
+
+    fn key(i: u32) -> Key {
+        Key::from_seed(&SecretBytes::from([15; 32]), i)
+    }
+
+    /// Run both rounds of signing with all of the keys
+    fn sign(keys: &[Key], message: &[u8]) -> (KeyAggregation, Signature) {
+        let accounts: Vec<Account> = keys.iter().map(Key::to_account).collect();
+        let aggregation = KeyAggregation::new(&accounts).unwrap();
+
+        let secret_nonces: Vec<SecretNonce> = keys
+            .iter()
+            .map(|key| SecretNonce::new(key, message))
+            .collect();
+        let public_nonces: Vec<PublicNonce> =
+            secret_nonces.iter().map(SecretNonce::public).collect();
+
+        let session = SigningSession::new(&aggregation, &public_nonces, message).unwrap();
+        let partials: Vec<PartialSignature> = keys
+            .iter()
+            .zip(secret_nonces)
+            .map(|(key, nonce)| session.partial_sign(key, nonce).unwrap())
+            .collect();
+        for ((account, nonce), partial) in accounts.iter().zip(&public_nonces).zip(&partials) {
+            assert!(session.is_valid_partial(account, nonce, partial));
+        }
+        (aggregation, session.combine(&partials).unwrap())
+    }
+
+    #[test]
+    fn two_of_two() {
+        let (aggregation, signature) = sign(&[key(0), key(1)], b"treasury");
+        assert!(aggregation
+            .account()
+            .is_valid_signature(b"treasury", &signature));
+        assert!(!aggregation
+            .account()
+            .is_valid_signature(b"treasurx", &signature));
+        assert!(!key(0)
+            .to_account()
+            .is_valid_signature(b"treasury", &signature));
+
+        // the signature survives serialization
+        let signature = Signature::try_from(signature.to_bytes()).unwrap();
+        assert!(aggregation
+            .account()
+            .is_valid_signature(b"treasury", &signature));
+    }
+
+    #[test]
+    fn n_of_n() {
+        let keys: Vec<Key> = (0..5).map(key).collect();
+        let (aggregation, signature) = sign(&keys, b"message");
+        assert!(aggregation
+            .account()
+            .is_valid_signature(b"message", &signature));
+
+        // order does not matter
+        let mut accounts: Vec<Account> = keys.iter().map(Key::to_account).collect();
+        accounts.reverse();
+        assert!(KeyAggregation::new(&accounts).unwrap() == aggregation);
+    }
+
+    #[test]
+    fn key_aggregation() {
+        let accounts = [key(0).to_account(), key(1).to_account()];
+        let aggregation = KeyAggregation::new(&accounts).unwrap();
+        // coefficients prevent the aggregate from being a plain sum of keys
+        assert!(aggregation.account() != &(&accounts[0] + &accounts[1]));
+
+        let duplicated = [key(0).to_account(), key(0).to_account()];
+        assert!(KeyAggregation::new(&duplicated) == Err(NanoError::InvalidKeySet));
+        assert!(KeyAggregation::new(&[]) == Err(NanoError::InvalidKeySet));
+        let identity = Account::from(&EdwardsPoint::default());
+        assert!(KeyAggregation::new(&[identity]) == Err(NanoError::InvalidKeySet));
+    }
+
+    #[test]
+    fn invalid_partials() {
+        let message = b"message";
+        let accounts = [key(0).to_account(), key(1).to_account()];
+        let aggregation = KeyAggregation::new(&accounts).unwrap();
+        let nonces = [
+            SecretNonce::new(&key(0), message),
+            SecretNonce::new(&key(1), message),
+        ];
+        let public = [nonces[0].public(), nonces[1].public()];
+        let session = SigningSession::new(&aggregation, &public, message).unwrap();
+        let result = SigningSession::new(&aggregation, &public[..1], message);
+        assert!(result.unwrap_err() == NanoError::InvalidKeySet);
+
+        let [nonce_0, nonce_1] = nonces;
+        assert!(session.partial_sign(&key(2), nonce_1) == Err(NanoError::UnknownSigner));
+        let partial = session.partial_sign(&key(0), nonce_0).unwrap();
+        assert!(session.is_valid_partial(&accounts[0], &public[0], &partial));
+        assert!(!session.is_valid_partial(&accounts[1], &public[1], &partial));
+        assert!(!session.is_valid_partial(&key(2).to_account(), &public[0], &partial));
+
+        // missing or invalid partial signatures are not combined
+        assert!(session.combine(&[partial]) == Err(NanoError::InvalidKeySet));
+        assert!(session.combine(&[partial, partial]) == Err(NanoError::InvalidKeySet));
+
+        let bytes = PublicNonce::from_bytes(&public[0].to_bytes()).unwrap();
+        assert!(bytes == public[0]);
+        assert!(PartialSignature::from_bytes(&partial.to_bytes()).unwrap() == partial);
+    }
+}
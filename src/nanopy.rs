@@ -5,37 +5,53 @@
 
 // https://docs.nano.org/protocol-design/
 
+use super::constants::ACCOUNT_PREFIX;
 use super::error::NanoError;
 use super::hashes::*;
 use super::{
     base32, try_compressed_from_slice, Account, Block, Key, Scalar, SecretBytes, Signature,
 };
 use crate::scalar;
-use curve25519_dalek::{constants::ED25519_BASEPOINT_POINT as G, edwards::CompressedEdwardsY};
+use curve25519_dalek::{
+    constants::ED25519_BASEPOINT_POINT as G,
+    edwards::{CompressedEdwardsY, EdwardsPoint},
+};
+use std::sync::atomic::{AtomicBool, Ordering};
 
 pub(crate) fn account_encode(key: &CompressedEdwardsY) -> String {
+    account_encode_with_prefix(key, ACCOUNT_PREFIX)
+}
+
+pub(crate) fn account_encode_with_prefix(key: &CompressedEdwardsY, prefix: &str) -> String {
     let key = key.as_bytes();
 
     let mut checksum = blake2b_checksum(key);
     checksum.reverse();
 
-    let mut account = "nano_".to_string();
+    let mut account = prefix.to_string();
     let data = [[0, 0, 0].as_slice(), key, &checksum].concat();
     account.push_str(&base32::encode(&data)[4..]);
     account
 }
 
 pub(crate) fn account_decode(account: &str) -> Result<CompressedEdwardsY, NanoError> {
-    if account.len() != 65 {
+    account_decode_with_prefix(account, ACCOUNT_PREFIX)
+}
+
+pub(crate) fn account_decode_with_prefix(
+    account: &str,
+    prefix: &str,
+) -> Result<CompressedEdwardsY, NanoError> {
+    if account.len() != prefix.len() + 60 {
         return Err(NanoError::InvalidAddressLength);
     }
 
-    if &account[..5] != "nano_" {
+    if account.get(..prefix.len()) != Some(prefix) {
         return Err(NanoError::InvalidAddressPrefix);
     }
 
     let mut data = "1111".to_string();
-    data.push_str(&account[5..]);
+    data.push_str(&account[prefix.len()..]);
 
     let data = base32::decode(&data).ok_or(NanoError::InvalidBase32)?;
 
@@ -62,6 +78,15 @@ pub fn get_account_scalar(master_seed: &SecretBytes<32>, i: u32) -> Scalar {
 
 /// Get work using the local CPU (likely very slow)
 pub fn get_local_work(block_hash: [u8; 32], difficulty: [u8; 8]) -> [u8; 8] {
+    get_local_work_cancellable(block_hash, difficulty, &AtomicBool::new(false)).unwrap()
+}
+
+/// Same as `get_local_work`, but gives up (returning `None`) once `cancel` is set
+pub(crate) fn get_local_work_cancellable(
+    block_hash: [u8; 32],
+    difficulty: [u8; 8],
+    cancel: &AtomicBool,
+) -> Option<[u8; 8]> {
     let mut data: [u8; 40] = [[0; 8].as_slice(), &block_hash]
         .concat()
         .try_into()
@@ -76,7 +101,7 @@ pub fn get_local_work(block_hash: [u8; 32], difficulty: [u8; 8]) -> [u8; 8] {
         if bytes >= difficulty {
             let mut work: [u8; 8] = data[..8].try_into().unwrap();
             work.reverse();
-            return work;
+            return Some(work);
         }
         i = 0;
         loop {
@@ -86,27 +111,45 @@ pub fn get_local_work(block_hash: [u8; 32], difficulty: [u8; 8]) -> [u8; 8] {
             }
             i += 1;
         }
+        // only check every 256 attempts
+        if i > 0 && cancel.load(Ordering::Relaxed) {
+            return None;
+        }
     }
 }
 
-/// Check if the given work is valid, given a difficulty target
-pub fn check_work(work_hash: [u8; 32], difficulty: [u8; 8], work: [u8; 8]) -> bool {
+/// Get the difficulty which the given work achieves
+pub fn get_work_difficulty(work_hash: [u8; 32], work: [u8; 8]) -> [u8; 8] {
     let mut work = work;
     work.reverse();
 
     let mut bytes = blake2b_work(&[work.as_slice(), &work_hash].concat());
     bytes.reverse();
+    bytes
+}
 
-    bytes >= difficulty
+/// Check if the given work is valid, given a difficulty target
+pub fn check_work(work_hash: [u8; 32], difficulty: [u8; 8], work: [u8; 8]) -> bool {
+    get_work_difficulty(work_hash, work) >= difficulty
 }
 
 /// Given a specific `r` value, sign the `message` with the `Key`, returning a `Signature`.
 ///
 /// **DANGEROUS! Don't use unless you know what you're doing.**
 pub fn sign_message_with_r(message: &[u8], private_key: &Key, r: &Scalar) -> Signature {
+    sign_message_with_r_point(message, private_key, r, &(r * G))
+}
+
+/// Sign with the nonce `r`, but commit to `r_point` (which need not be `rG`) in the signature.
+/// Used for adaptor signatures, where `r_point` is `rG + T`.
+pub(crate) fn sign_message_with_r_point(
+    message: &[u8],
+    private_key: &Key,
+    r: &Scalar,
+    r_point: &EdwardsPoint,
+) -> Signature {
     let public_key = private_key.to_account().compressed.to_bytes();
 
-    let r_point = r * G;
     let r_point_bytes = r_point.compress().to_bytes();
 
     let message = scalar!(blake2b512(&[&r_point_bytes, &public_key, message].concat()));
@@ -115,7 +158,7 @@ pub fn sign_message_with_r(message: &[u8], private_key: &Key, r: &Scalar) -> Sig
     let s = r + (message * private_key.as_scalar());
 
     Signature {
-        r: r_point,
+        r: *r_point,
         s: s.into(),
     }
 }
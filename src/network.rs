@@ -0,0 +1,216 @@
+//! Parameters which differ between Nano networks.
+//!
+//! Most of this library assumes the live network. Where that matters (signatures of `epoch` blocks, work thresholds,
+//! address prefixes), a `Network` can be used to target the beta or test networks, or a private dev network.
+
+use crate::constants::{
+    epoch_signers::*, get_genesis_account, ACCOUNT_PREFIX, EPOCH_1_WORK_DIFFICULTY,
+    RECEIVE_WORK_DIFFICULTY, SEND_WORK_DIFFICULTY,
+};
+use crate::nanopy::{account_decode_with_prefix, account_encode_with_prefix};
+use crate::{Account, BlockType, Epoch, NanoError};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A Nano network's genesis account, epoch signers, address prefix, work thresholds and peer-to-peer network ID
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Network {
+    /// Name of the network, as reported by the node's `version` RPC action (e.g. `"live"`)
+    pub name: String,
+    /// Identifies the network in the header of peer-to-peer messages (e.g. `*b"RC"`)
+    pub network_id: [u8; 2],
+    pub genesis_account: Account,
+    /// The account which signs epoch v1 blocks
+    pub epoch_v1_signer: Account,
+    /// The account which signs epoch v2 blocks
+    pub epoch_v2_signer: Account,
+    /// Prefix of addresses on this network (e.g. `"nano_"`)
+    pub account_prefix: String,
+    /// Minimum work difficulty of `send` and `change` blocks
+    pub send_work_difficulty: [u8; 8],
+    /// Minimum work difficulty of `receive` and `epoch` blocks
+    pub receive_work_difficulty: [u8; 8],
+    /// Minimum work difficulty of every block, before the account was upgraded to epoch v2
+    pub epoch_1_work_difficulty: [u8; 8],
+}
+impl Network {
+    /// The live network
+    pub fn live() -> Network {
+        Network {
+            name: "live".into(),
+            network_id: *b"RC",
+            genesis_account: get_genesis_account(),
+            epoch_v1_signer: get_v1_epoch_signer(),
+            epoch_v2_signer: get_v2_epoch_signer(),
+            account_prefix: ACCOUNT_PREFIX.into(),
+            send_work_difficulty: SEND_WORK_DIFFICULTY,
+            receive_work_difficulty: RECEIVE_WORK_DIFFICULTY,
+            epoch_1_work_difficulty: EPOCH_1_WORK_DIFFICULTY,
+        }
+    }
+
+    /// The public beta network
+    pub fn beta() -> Network {
+        let genesis =
+            Account::try_from("nano_1betag7az9wk6rbis38s1d35hdsycz1bi95xg4g4j148p6afjk7embcurda4")
+                .unwrap();
+        Network {
+            name: "beta".into(),
+            network_id: *b"RB",
+            send_work_difficulty: 0xfffff00000000000_u64.to_be_bytes(),
+            receive_work_difficulty: 0xffffe00000000000_u64.to_be_bytes(),
+            epoch_1_work_difficulty: 0xfffff00000000000_u64.to_be_bytes(),
+            ..Network::dev(genesis)
+        }
+    }
+
+    /// The public test network, which uses the live network's work thresholds
+    pub fn test() -> Network {
+        let genesis =
+            Account::try_from("nano_1jg8zygjg3pp5w644emqcbmjqpnzmubfni3kfe1s8pooeuxsw49fdq1mco9j")
+                .unwrap();
+        Network {
+            name: "test".into(),
+            network_id: *b"RX",
+            send_work_difficulty: SEND_WORK_DIFFICULTY,
+            receive_work_difficulty: RECEIVE_WORK_DIFFICULTY,
+            epoch_1_work_difficulty: EPOCH_1_WORK_DIFFICULTY,
+            ..Network::dev(genesis)
+        }
+    }
+
+    /// A private dev network, with the given genesis account.
+    ///
+    /// As on the node's dev network, the genesis account also signs epoch blocks,
+    /// and the work thresholds are low enough to generate work quickly on a CPU.
+    /// Any field can be changed afterwards, to match a differently-configured network.
+    pub fn dev(genesis_account: Account) -> Network {
+        Network {
+            name: "dev".into(),
+            network_id: *b"RA",
+            epoch_v1_signer: genesis_account.clone(),
+            epoch_v2_signer: genesis_account.clone(),
+            genesis_account,
+            account_prefix: ACCOUNT_PREFIX.into(),
+            send_work_difficulty: 0xffc0000000000000_u64.to_be_bytes(),
+            receive_work_difficulty: 0xf000000000000000_u64.to_be_bytes(),
+            epoch_1_work_difficulty: 0xfe00000000000000_u64.to_be_bytes(),
+        }
+    }
+
+    /// The account which signs the given epoch's blocks
+    pub fn epoch_signer(&self, epoch: Epoch) -> &Account {
+        match epoch {
+            Epoch::V1 => &self.epoch_v1_signer,
+            Epoch::V2 => &self.epoch_v2_signer,
+        }
+    }
+
+    /// The minimum work difficulty for a block of the given type
+    pub fn work_difficulty(&self, block_type: &BlockType) -> [u8; 8] {
+        match block_type {
+            BlockType::Receive | BlockType::Epoch => self.receive_work_difficulty,
+            _ => self.send_work_difficulty,
+        }
+    }
+
+    /// The minimum work difficulty for a block of the given type, in an account at the given epoch
+    /// (including any upgrade by the block itself)
+    pub fn work_difficulty_at(&self, block_type: &BlockType, epoch: Option<Epoch>) -> [u8; 8] {
+        match epoch {
+            Some(Epoch::V2) => self.work_difficulty(block_type),
+            _ => self.epoch_1_work_difficulty,
+        }
+    }
+
+    /// Encode the account as an address, using this network's prefix
+    pub fn encode_account(&self, account: &Account) -> String {
+        account_encode_with_prefix(&account.compressed, &self.account_prefix)
+    }
+
+    /// Decode an address which uses this network's prefix
+    pub fn decode_account(&self, address: &str) -> Result<Account, NanoError> {
+        let compressed = account_decode_with_prefix(address, &self.account_prefix)?;
+        Account::try_from(compressed)
+    }
+}
+impl Default for Network {
+    fn default() -> Self {
+        Network::live()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Block, Key, SecretBytes, Signature};
+
+    #[test]
+    fn epoch_signers() {
+        let live = Network::live();
+        assert!(live.epoch_signer(Epoch::V1) == &Epoch::V1.signer());
+        assert!(live.epoch_signer(Epoch::V2) == &Epoch::V2.signer());
+
+        let beta = Network::beta();
+        assert!(beta.epoch_signer(Epoch::V2) == &beta.genesis_account);
+        assert!(Network::test().genesis_account != live.genesis_account);
+    }
+
+    #[test]
+    fn work_difficulty() {
+        let beta = Network::beta();
+        assert!(beta.work_difficulty(&BlockType::Receive) == beta.receive_work_difficulty);
+        assert!(beta.work_difficulty(&BlockType::Change) == beta.send_work_difficulty);
+        assert!(beta.work_difficulty(&BlockType::Epoch) == beta.receive_work_difficulty);
+        assert!(Network::test().work_difficulty(&BlockType::Send) == SEND_WORK_DIFFICULTY);
+
+        let live = Network::live();
+        let epoch_1 = live.work_difficulty_at(&BlockType::Send, Some(Epoch::V1));
+        assert!(epoch_1 == EPOCH_1_WORK_DIFFICULTY);
+        assert!(live.work_difficulty_at(&BlockType::Receive, None) == EPOCH_1_WORK_DIFFICULTY);
+        let epoch_2 = live.work_difficulty_at(&BlockType::Receive, Some(Epoch::V2));
+        assert!(epoch_2 == RECEIVE_WORK_DIFFICULTY);
+    }
+
+    #[test]
+    fn account_prefix() {
+        let account = Network::live().genesis_account;
+        let network = Network {
+            account_prefix: "xrb_".into(),
+            ..Network::live()
+        };
+
+        let address = network.encode_account(&account);
+        assert!(address.starts_with("xrb_") && address[4..] == account.to_string()[5..]);
+        assert!(network.decode_account(&address).unwrap() == account);
+        assert!(network.decode_account(&account.to_string()).is_err());
+        assert!(Network::live().encode_account(&account) == account.to_string());
+    }
+
+    #[test]
+    fn dev_epoch_block() {
+        let genesis = Key::from_seed(&SecretBytes::from([7; 32]), 0);
+        let network = Network::dev(genesis.to_account());
+        let frontier = Block {
+            block_type: BlockType::Send,
+            account: Key::from_seed(&SecretBytes::from([7; 32]), 1).to_account(),
+            previous: [1; 32],
+            representative: genesis.to_account(),
+            balance: 10,
+            link: [2; 32],
+            signature: Signature::default(),
+            work: [0; 8],
+        };
+
+        let mut upgrade = Epoch::V2.upgrade_block(&frontier);
+        upgrade.sign(&genesis);
+        assert!(upgrade.has_valid_signature_on(&network));
+        assert!(!upgrade.has_valid_signature());
+
+        let mut block = frontier.clone();
+        block.local_work(network.send_work_difficulty);
+        assert!(block.has_valid_work_on(&network));
+    }
+}
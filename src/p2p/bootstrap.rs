@@ -0,0 +1,369 @@
+//! An async client for the node's bootstrap protocol, which serves account frontiers (`frontier_req`)
+//! and ranges of blocks (`bulk_pull`).
+//!
+//! Pulled blocks are verified as they arrive, so account blockchains can be reconstructed without trusting the peer.
+
+use super::peer::{write_message, PeerError, PeerOptions};
+use super::{BulkPull, FrontierReq, Message, MessageError, NOT_A_BLOCK, STATE_BLOCK};
+use crate::constants::STATE_BLOCK_LENGTH;
+use crate::ledger::ProcessError;
+use crate::{Account, Block, Epoch, Network};
+use tokio::io::AsyncReadExt;
+use tokio::net::{TcpStream, ToSocketAddrs};
+use tokio::time::timeout;
+
+/// A bootstrap connection to a node.
+///
+/// Unlike `Peer`, no handshake is needed. Requests are answered one at a time.
+#[derive(Debug)]
+pub struct Bootstrap {
+    stream: TcpStream,
+    network: Network,
+}
+impl Bootstrap {
+    /// Connect to the node. Only `network` and `timeout` of the options are used.
+    pub async fn connect<A: ToSocketAddrs>(
+        address: A,
+        options: &PeerOptions,
+    ) -> Result<Bootstrap, PeerError> {
+        let stream = timeout(options.timeout, TcpStream::connect(address))
+            .await
+            .map_err(|_| PeerError::Timeout)??;
+        Ok(Bootstrap::from_stream(stream, &options.network))
+    }
+
+    /// Use an existing connection to a node
+    pub fn from_stream(stream: TcpStream, network: &Network) -> Bootstrap {
+        Bootstrap {
+            stream,
+            network: network.clone(),
+        }
+    }
+
+    /// Get the frontiers (hashes of the newest blocks) of at most `count` accounts,
+    /// starting from the account `start` (which may be zero), in order of account.
+    ///
+    /// Returns `MessageError::InvalidLength` if the peer sends more than `count` frontiers.
+    pub async fn frontiers(
+        &mut self,
+        start: [u8; 32],
+        count: u32,
+    ) -> Result<Vec<(Account, [u8; 32])>, PeerError> {
+        let request = FrontierReq {
+            start,
+            age: u32::MAX,
+            count,
+        };
+        write_message(
+            &mut self.stream,
+            &self.network,
+            &Message::FrontierReq(request),
+        )
+        .await?;
+
+        let mut frontiers = vec![];
+        loop {
+            let mut pair = [0; 64];
+            self.stream.read_exact(&mut pair).await?;
+            if pair == [0; 64] {
+                return Ok(frontiers);
+            }
+            // the response must be terminated after at most `count` frontiers
+            if frontiers.len() == count as usize {
+                return Err(MessageError::InvalidLength.into());
+            }
+            let account = <[u8; 32]>::try_from(&pair[..32]).unwrap();
+            let account = Account::try_from(account).map_err(MessageError::from)?;
+            frontiers.push((account, pair[32..].try_into().unwrap()));
+        }
+    }
+
+    /// Pull the account's blockchain, from its frontier back to (but not including) the already-known block `end`,
+    /// or back to the open block if `end` is `None`. Blocks are returned newest first.
+    ///
+    /// Each block is checked as it arrives: it must belong to the account, be linked to the next block by its hash,
+    /// and have a valid signature. The last block must follow `end`, or open the account.
+    /// The `block_type` (subtype) of each block is set, according to the balance of its previous block.
+    ///
+    /// The work of each block is then checked oldest first, against the threshold of the account's epoch at that block.
+    /// Receiving from an upgraded account also upgrades an account, which cannot be seen from its own blocks,
+    /// so blocks before the account's epoch v2 block (or `end`, if it is one) may meet either epoch's threshold.
+    ///
+    /// Only `state` blocks are supported. A peer could still leave out the newest blocks,
+    /// so compare the first block's hash against the account's frontier (from `frontiers()`, or another source).
+    pub async fn pull_account(
+        &mut self,
+        account: &Account,
+        end: Option<&Block>,
+    ) -> Result<Vec<Block>, PeerError> {
+        let (end_hash, end_balance) = match end {
+            Some(end) => (end.hash(), end.balance),
+            None => ([0; 32], 0),
+        };
+        let request = BulkPull {
+            start: account.compressed.to_bytes(),
+            end: end_hash,
+            count: None,
+        };
+        write_message(&mut self.stream, &self.network, &Message::BulkPull(request)).await?;
+
+        let mut blocks: Vec<Block> = vec![];
+        // the newest block whose subtype is not yet known, since its previous block has not arrived
+        let mut unchecked: Option<Block> = None;
+        while let Some(block) = self.read_block().await? {
+            if &block.account != account {
+                return Err(PeerError::InvalidBlock(
+                    block.hash(),
+                    ProcessError::BlockPosition,
+                ));
+            }
+            if let Some(newer) = unchecked.take() {
+                if newer.previous != block.hash() {
+                    return Err(PeerError::InvalidBlock(
+                        newer.hash(),
+                        ProcessError::GapPrevious,
+                    ));
+                }
+                blocks.push(self.check_block(newer, block.balance)?);
+            }
+            unchecked = Some(block);
+        }
+
+        if let Some(oldest) = unchecked {
+            if oldest.previous != end_hash {
+                return Err(PeerError::InvalidBlock(
+                    oldest.hash(),
+                    ProcessError::GapPrevious,
+                ));
+            }
+            blocks.push(self.check_block(oldest, end_balance)?);
+        }
+
+        let mut epoch_2 = end.is_some_and(|end| end.epoch() == Some(Epoch::V2));
+        for block in blocks.iter().rev() {
+            epoch_2 |= block.epoch() == Some(Epoch::V2);
+            let network = &self.network;
+            let valid = match epoch_2 {
+                true => block.has_valid_work_on(network),
+                false => {
+                    block.has_valid_work(network.work_difficulty_at(&block.block_type, None))
+                        || block.has_valid_work_on(network)
+                }
+            };
+            if !valid {
+                return Err(PeerError::InvalidBlock(
+                    block.hash(),
+                    ProcessError::InsufficientWork,
+                ));
+            }
+        }
+        Ok(blocks)
+    }
+
+    /// Set the block's subtype, and check its signature
+    fn check_block(&self, mut block: Block, previous_balance: u128) -> Result<Block, PeerError> {
+        block.set_subtype(previous_balance);
+        if !block.has_valid_signature_on(&self.network) {
+            return Err(PeerError::InvalidBlock(
+                block.hash(),
+                ProcessError::BadSignature,
+            ));
+        }
+        Ok(block)
+    }
+
+    /// Read the next block of a `bulk_pull` response, or `None` at the end of the response
+    async fn read_block(&mut self) -> Result<Option<Block>, PeerError> {
+        match self.stream.read_u8().await? {
+            NOT_A_BLOCK => Ok(None),
+            STATE_BLOCK => {
+                let mut bytes = [0; STATE_BLOCK_LENGTH];
+                self.stream.read_exact(&mut bytes).await?;
+                let block = Block::from_bytes(&bytes).map_err(MessageError::from)?;
+                Ok(Some(block))
+            }
+            block_type => Err(MessageError::UnsupportedBlockType(block_type).into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::peer::read_message;
+    use super::*;
+    use crate::block::check_work;
+    use crate::{BlockType, Key, SecretBytes, Signature};
+    use tokio::io::AsyncWriteExt;
+    use tokio::net::TcpListener;
+
+    fn key(i: u32) -> Key {
+        Key::from_seed(&SecretBytes::from([13; 32]), i)
+    }
+
+    fn network() -> Network {
+        Network::dev(key(0).to_account())
+    }
+
+    /// Open, send and change blocks for `key(1)`, newest first
+    fn chain() -> Vec<Block> {
+        let network = network();
+        let mut blocks: Vec<Block> = vec![];
+        for (block_type, balance, link) in [
+            (BlockType::Receive, 100, [1; 32]),
+            (BlockType::Send, 40, [2; 32]),
+            (BlockType::Change, 40, [0; 32]),
+        ] {
+            let mut block = Block {
+                block_type,
+                account: key(1).to_account(),
+                previous: blocks.last().map(|block| block.hash()).unwrap_or_default(),
+                representative: key(2).to_account(),
+                balance,
+                link,
+                signature: Signature::default(),
+                work: [0; 8],
+            };
+            block.sign(&key(1));
+            block.local_work(network.work_difficulty(&block.block_type));
+            blocks.push(block);
+        }
+        blocks.reverse();
+        blocks
+    }
+
+    /// Set work which only meets the epoch v1 threshold, which is lower than the epoch v2 `send` threshold
+    fn set_epoch_1_work(block: &mut Block) {
+        let network = network();
+        let work_hash = block.work_hash();
+        block.work = (0..u64::MAX)
+            .map(u64::to_be_bytes)
+            .find(|work| {
+                check_work(work_hash, network.epoch_1_work_difficulty, *work)
+                    && !check_work(work_hash, network.send_work_difficulty, *work)
+            })
+            .unwrap();
+    }
+
+    /// Stands in for a node, serving `frontier_req` and `bulk_pull` requests from the given blockchain
+    async fn stand_in_server(listener: TcpListener, blocks: Vec<Block>) -> Result<(), PeerError> {
+        let (mut stream, _) = listener.accept().await?;
+        loop {
+            let mut response = vec![];
+            match read_message(&mut stream, &network()).await? {
+                Message::FrontierReq(_) => {
+                    response.extend_from_slice(blocks[0].account.compressed.as_bytes());
+                    response.extend_from_slice(&blocks[0].hash());
+                    response.extend_from_slice(&[0; 64]);
+                }
+                Message::BulkPull(BulkPull { end, .. }) => {
+                    for block in blocks.iter().take_while(|block| block.hash() != end) {
+                        response.push(STATE_BLOCK);
+                        response.extend_from_slice(&block.to_bytes());
+                    }
+                    response.push(NOT_A_BLOCK);
+                }
+                _ => return Err(PeerError::InvalidHandshake),
+            }
+            stream.write_all(&response).await?;
+        }
+    }
+
+    async fn connect(blocks: Vec<Block>) -> Bootstrap {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(stand_in_server(listener, blocks));
+        let options = PeerOptions::for_network(&network(), [0; 32]);
+        Bootstrap::connect(address, &options).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn pull() {
+        let chain = chain();
+        let mut bootstrap = connect(chain.clone()).await;
+        let account = key(1).to_account();
+
+        let frontiers = bootstrap.frontiers([0; 32], u32::MAX).await.unwrap();
+        assert!(frontiers == vec![(account.clone(), chain[0].hash())]);
+
+        let blocks = bootstrap.pull_account(&account, None).await.unwrap();
+        assert!(blocks == chain);
+        let frontiers = bootstrap.frontiers([0; 32], 1).await.unwrap();
+        assert!(frontiers.len() == 1);
+        let blocks = bootstrap
+            .pull_account(&account, Some(&chain[2]))
+            .await
+            .unwrap();
+        assert!(blocks == chain[..2]);
+    }
+
+    #[tokio::test]
+    async fn epoch_1_work() {
+        let account = key(1).to_account();
+        let mut chain = chain();
+        set_epoch_1_work(&mut chain[1]);
+        let blocks = connect(chain.clone())
+            .await
+            .pull_account(&account, None)
+            .await
+            .unwrap();
+        assert!(blocks == chain);
+
+        // once the account is upgraded to epoch v2, the epoch v1 threshold is too low
+        let mut upgrade = Epoch::V2.upgrade_block(&chain[0]);
+        upgrade.sign(&key(0));
+        upgrade.local_work(network().receive_work_difficulty);
+        let mut change = chain[0].clone();
+        change.previous = upgrade.hash();
+        change.sign(&key(1));
+        set_epoch_1_work(&mut change);
+        let hash = change.hash();
+        chain.insert(0, upgrade);
+        chain.insert(0, change);
+        let result = connect(chain).await.pull_account(&account, None).await;
+        assert!(matches!(
+            result,
+            Err(PeerError::InvalidBlock(invalid, ProcessError::InsufficientWork)) if invalid == hash
+        ));
+    }
+
+    #[tokio::test]
+    async fn too_many_frontiers() {
+        let mut bootstrap = connect(chain()).await;
+        let result = bootstrap.frontiers([0; 32], 0).await;
+        assert!(matches!(
+            result,
+            Err(PeerError::MessageError(MessageError::InvalidLength))
+        ));
+    }
+
+    #[tokio::test]
+    async fn invalid_chains() {
+        let account = key(1).to_account();
+
+        let mut forged = chain();
+        forged[1].balance = 30;
+        let result = connect(forged.clone())
+            .await
+            .pull_account(&account, None)
+            .await;
+        assert!(matches!(
+            result,
+            Err(PeerError::InvalidBlock(_, ProcessError::GapPrevious))
+        ));
+        forged[0].previous = forged[1].hash();
+        let result = connect(forged).await.pull_account(&account, None).await;
+        assert!(matches!(
+            result,
+            Err(PeerError::InvalidBlock(_, ProcessError::BadSignature))
+        ));
+
+        // missing the open block
+        let mut truncated = chain();
+        truncated.pop();
+        let result = connect(truncated).await.pull_account(&account, None).await;
+        assert!(matches!(
+            result,
+            Err(PeerError::InvalidBlock(_, ProcessError::GapPrevious))
+        ));
+    }
+}
@@ -0,0 +1,237 @@
+use super::{MessageError, Reader};
+use crate::{Account, Key, Signature, Vote};
+
+pub(super) fn encode_vote(vote: &Vote, bytes: &mut Vec<u8>) {
+    bytes.extend_from_slice(vote.representative.compressed.as_bytes());
+    bytes.extend_from_slice(&vote.signature.to_bytes());
+    bytes.extend_from_slice(&vote.timestamp.to_le_bytes());
+    for hash in &vote.hashes {
+        bytes.extend_from_slice(hash);
+    }
+}
+
+pub(super) fn decode_vote(reader: &mut Reader, count: usize) -> Result<Vote, MessageError> {
+    Ok(Vote {
+        representative: reader.account()?,
+        signature: reader.signature()?,
+        timestamp: u64::from_le_bytes(reader.take()?),
+        hashes: (0..count)
+            .map(|_| reader.take())
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+/// A `node_id_handshake` message, which carries a query for the peer's node ID, a response to the peer's query, or both
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeIdHandshake {
+    /// Whether this is a version 2 handshake. Version 2 responses must include `HandshakeResponse::v2`.
+    pub v2: bool,
+    /// A random cookie, which the peer should sign with its node ID
+    pub query: Option<[u8; 32]>,
+    pub response: Option<HandshakeResponse>,
+}
+impl NodeIdHandshake {
+    pub(super) const QUERY_FLAG: u16 = 0x0001;
+    pub(super) const RESPONSE_FLAG: u16 = 0x0002;
+    pub(super) const V2_FLAG: u16 = 0x0004;
+
+    pub(super) fn extensions(&self) -> Result<u16, MessageError> {
+        if let Some(response) = &self.response {
+            if response.v2.is_some() != self.v2 {
+                return Err(MessageError::InvalidHandshake);
+            }
+        }
+        let flag = |set: bool, flag: u16| if set { flag } else { 0 };
+        Ok(flag(self.query.is_some(), Self::QUERY_FLAG)
+            | flag(self.response.is_some(), Self::RESPONSE_FLAG)
+            | flag(self.v2, Self::V2_FLAG))
+    }
+
+    pub(super) fn length(extensions: u16) -> usize {
+        let v2 = extensions & Self::V2_FLAG != 0;
+        let mut length = 0;
+        if extensions & Self::QUERY_FLAG != 0 {
+            length += 32;
+        }
+        if extensions & Self::RESPONSE_FLAG != 0 {
+            length += if v2 { 32 + 64 + 64 } else { 32 + 64 };
+        }
+        length
+    }
+
+    pub(super) fn encode(&self, bytes: &mut Vec<u8>) {
+        if let Some(cookie) = &self.query {
+            bytes.extend_from_slice(cookie);
+        }
+        if let Some(response) = &self.response {
+            bytes.extend_from_slice(response.node_id.compressed.as_bytes());
+            if let Some(v2) = &response.v2 {
+                bytes.extend_from_slice(&v2.salt);
+                bytes.extend_from_slice(&v2.genesis);
+            }
+            bytes.extend_from_slice(&response.signature.to_bytes());
+        }
+    }
+
+    pub(super) fn decode(
+        reader: &mut Reader,
+        extensions: u16,
+    ) -> Result<NodeIdHandshake, MessageError> {
+        let v2 = extensions & Self::V2_FLAG != 0;
+        let query = match extensions & Self::QUERY_FLAG {
+            0 => None,
+            _ => Some(reader.take()?),
+        };
+        let response = match extensions & Self::RESPONSE_FLAG {
+            0 => None,
+            _ => Some(HandshakeResponse {
+                node_id: reader.account()?,
+                v2: match v2 {
+                    true => Some(HandshakeV2 {
+                        salt: reader.take()?,
+                        genesis: reader.take()?,
+                    }),
+                    false => None,
+                },
+                signature: reader.signature()?,
+            }),
+        };
+        Ok(NodeIdHandshake {
+            v2,
+            query,
+            response,
+        })
+    }
+}
+
+/// A peer's response to a `node_id_handshake` query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HandshakeResponse {
+    /// The peer's node ID
+    pub node_id: Account,
+    pub v2: Option<HandshakeV2>,
+    /// Signature of the query's cookie, made with the node ID
+    pub signature: Signature,
+}
+
+impl HandshakeResponse {
+    /// Respond to the peer's query by signing its cookie (and any `v2` fields) with the node ID
+    pub fn new(node_id: &Key, cookie: &[u8; 32], v2: Option<HandshakeV2>) -> HandshakeResponse {
+        let mut response = HandshakeResponse {
+            node_id: node_id.to_account(),
+            v2,
+            signature: Signature::default(),
+        };
+        response.signature = node_id.sign_message(&response.signed_bytes(cookie));
+        response
+    }
+
+    /// The bytes which are signed: the cookie, followed by the salt and genesis hash for version 2 responses
+    pub fn signed_bytes(&self, cookie: &[u8; 32]) -> Vec<u8> {
+        let mut bytes = cookie.to_vec();
+        if let Some(v2) = &self.v2 {
+            bytes.extend_from_slice(&v2.salt);
+            bytes.extend_from_slice(&v2.genesis);
+        }
+        bytes
+    }
+
+    /// Check if this is a valid response to the query with the given cookie
+    pub fn has_valid_signature(&self, cookie: &[u8; 32]) -> bool {
+        self.node_id
+            .is_valid_signature(&self.signed_bytes(cookie), &self.signature)
+    }
+}
+
+/// Fields of a version 2 handshake response
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HandshakeV2 {
+    /// Random data chosen by the responding peer, which is also signed
+    pub salt: [u8; 32],
+    /// Hash of the responding peer's genesis block
+    pub genesis: [u8; 32],
+}
+
+/// A `bulk_pull` request, for a range of an account's blockchain.
+///
+/// The peer responds with blocks from `start` back to (but not including) `end`, newest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BulkPull {
+    /// The account (to start from its frontier), or the hash of the newest block to pull
+    pub start: [u8; 32],
+    /// Hash of the block to stop at, or zero to pull down to the open block
+    pub end: [u8; 32],
+    /// Maximum number of blocks to pull, if any
+    pub count: Option<u32>,
+}
+impl BulkPull {
+    pub(super) const COUNT_FLAG: u16 = 0x0001;
+
+    pub(super) fn extensions(&self) -> u16 {
+        match self.count {
+            Some(_) => Self::COUNT_FLAG,
+            None => 0,
+        }
+    }
+
+    pub(super) fn length(extensions: u16) -> usize {
+        match extensions & Self::COUNT_FLAG {
+            0 => 64,
+            _ => 64 + 8,
+        }
+    }
+
+    pub(super) fn encode(&self, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(&self.start);
+        bytes.extend_from_slice(&self.end);
+        if let Some(count) = self.count {
+            // a reserved byte, then the count, then 3 more reserved bytes
+            bytes.push(0);
+            bytes.extend_from_slice(&count.to_le_bytes());
+            bytes.extend_from_slice(&[0; 3]);
+        }
+    }
+
+    pub(super) fn decode(reader: &mut Reader, extensions: u16) -> Result<BulkPull, MessageError> {
+        let start = reader.take()?;
+        let end = reader.take()?;
+        let count = match extensions & Self::COUNT_FLAG {
+            0 => None,
+            _ => {
+                let [_, count @ .., _, _, _]: [u8; 8] = reader.take()?;
+                Some(u32::from_le_bytes(count))
+            }
+        };
+        Ok(BulkPull { start, end, count })
+    }
+}
+
+/// A `frontier_req` request, for the frontiers of accounts starting from `start`.
+///
+/// The peer responds with `(account, frontier)` pairs, in order of account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrontierReq {
+    /// The account to start from
+    pub start: [u8; 32],
+    /// Only include accounts which changed within this many seconds (`u32::MAX` for all accounts)
+    pub age: u32,
+    /// Maximum number of frontiers (`u32::MAX` for no limit)
+    pub count: u32,
+}
+impl FrontierReq {
+    pub(super) const LENGTH: usize = 32 + 4 + 4;
+
+    pub(super) fn encode(&self, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(&self.start);
+        bytes.extend_from_slice(&self.age.to_le_bytes());
+        bytes.extend_from_slice(&self.count.to_le_bytes());
+    }
+
+    pub(super) fn decode(reader: &mut Reader) -> Result<FrontierReq, MessageError> {
+        Ok(FrontierReq {
+            start: reader.take()?,
+            age: u32::from_le_bytes(reader.take()?),
+            count: u32::from_le_bytes(reader.take()?),
+        })
+    }
+}
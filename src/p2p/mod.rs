@@ -0,0 +1,575 @@
+//! Encoding and decoding of the node's peer-to-peer messages.
+//!
+//! Every message starts with an 8-byte `Header`, followed by a payload whose length depends on the header.
+//! See the official [Nano documentation](https://docs.nano.org/protocol-design/networking/) for details.
+
+#[cfg(feature = "peer")]
+pub mod bootstrap;
+mod messages;
+#[cfg(feature = "peer")]
+pub mod peer;
+
+pub use messages::{BulkPull, FrontierReq, HandshakeResponse, HandshakeV2, NodeIdHandshake};
+
+use crate::constants::STATE_BLOCK_LENGTH;
+use crate::{Account, Block, NanoError, Network, Signature, Telemetry, Vote};
+use std::error::Error;
+use std::fmt::Display;
+use std::net::{Ipv6Addr, SocketAddrV6};
+
+/// The highest protocol version supported by the node (as of V27)
+pub const PROTOCOL_VERSION: u8 = 0x15;
+/// The lowest protocol version the node will talk to (as of V27)
+pub const PROTOCOL_VERSION_MIN: u8 = 0x14;
+/// Length of a message header
+pub const HEADER_LENGTH: usize = 8;
+/// Maximum number of hashes in a `confirm_req` or `confirm_ack` message
+pub const MAX_HASHES: usize = 15;
+
+const BLOCK_TYPE_MASK: u16 = 0x0f00;
+const COUNT_MASK: u16 = 0xf000;
+const TELEMETRY_SIZE_MASK: u16 = 0x03ff;
+const NOT_A_BLOCK: u8 = 1;
+const STATE_BLOCK: u8 = 6;
+/// Account, minimum amount and flags
+const BULK_PULL_ACCOUNT_LENGTH: usize = 32 + 16 + 1;
+
+/// The length of a block of this type, including legacy blocks (which are only skipped)
+fn block_length(block_type: u8) -> Result<usize, MessageError> {
+    Ok(match block_type {
+        // send: previous, destination and balance
+        2 => 32 + 32 + 16 + 64 + 8,
+        // receive and change: previous, and source or representative
+        3 | 5 => 32 + 32 + 64 + 8,
+        // open: source, representative and account
+        4 => 32 + 32 + 32 + 64 + 8,
+        STATE_BLOCK => STATE_BLOCK_LENGTH,
+        block_type => return Err(MessageError::UnsupportedBlockType(block_type)),
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageError {
+    /// The header does not start with the magic byte (`R`)
+    InvalidMagic,
+    /// The message type is unknown
+    UnknownMessageType(u8),
+    /// The message type is known, but not supported by this library
+    UnsupportedMessageType(MessageType),
+    /// The message contains a block of an unsupported type (only `state` blocks are supported)
+    UnsupportedBlockType(u8),
+    /// The payload's length does not match the header
+    InvalidLength,
+    /// A `confirm_req` or `confirm_ack` message contains more than `MAX_HASHES` hashes
+    TooManyHashes,
+    /// The `v2` flag of a `node_id_handshake` does not match its response
+    InvalidHandshake,
+    /// The message contains an invalid account or signature
+    InvalidData(NanoError),
+}
+impl Display for MessageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self {
+            MessageError::InvalidMagic => write!(f, "invalid magic byte"),
+            MessageError::UnknownMessageType(value) => write!(f, "unknown message type: {value}"),
+            MessageError::UnsupportedMessageType(value) => {
+                write!(f, "unsupported message type: {value:?}")
+            }
+            MessageError::UnsupportedBlockType(value) => {
+                write!(f, "unsupported block type: {value}")
+            }
+            MessageError::InvalidLength => write!(f, "invalid payload length"),
+            MessageError::TooManyHashes => write!(f, "too many hashes"),
+            MessageError::InvalidHandshake => write!(f, "invalid node ID handshake"),
+            MessageError::InvalidData(err) => write!(f, "invalid data: {err}"),
+        }
+    }
+}
+impl Error for MessageError {}
+impl From<NanoError> for MessageError {
+    fn from(value: NanoError) -> Self {
+        MessageError::InvalidData(value)
+    }
+}
+
+/// The type of a message, as encoded in its header
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MessageType {
+    Keepalive = 0x02,
+    Publish = 0x03,
+    ConfirmReq = 0x04,
+    ConfirmAck = 0x05,
+    BulkPull = 0x06,
+    BulkPush = 0x07,
+    FrontierReq = 0x08,
+    NodeIdHandshake = 0x0a,
+    BulkPullAccount = 0x0b,
+    TelemetryReq = 0x0c,
+    TelemetryAck = 0x0d,
+    AscPullReq = 0x0e,
+    AscPullAck = 0x0f,
+}
+impl TryFrom<u8> for MessageType {
+    type Error = MessageError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Ok(match value {
+            0x02 => MessageType::Keepalive,
+            0x03 => MessageType::Publish,
+            0x04 => MessageType::ConfirmReq,
+            0x05 => MessageType::ConfirmAck,
+            0x06 => MessageType::BulkPull,
+            0x07 => MessageType::BulkPush,
+            0x08 => MessageType::FrontierReq,
+            0x0a => MessageType::NodeIdHandshake,
+            0x0b => MessageType::BulkPullAccount,
+            0x0c => MessageType::TelemetryReq,
+            0x0d => MessageType::TelemetryAck,
+            0x0e => MessageType::AscPullReq,
+            0x0f => MessageType::AscPullAck,
+            _ => return Err(MessageError::UnknownMessageType(value)),
+        })
+    }
+}
+
+/// The header of a message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Header {
+    /// See `Network::network_id`
+    pub network_id: [u8; 2],
+    pub version_max: u8,
+    pub version_using: u8,
+    pub version_min: u8,
+    pub message_type: MessageType,
+    /// Message-specific flags, such as the number of hashes or the type of block in the payload
+    pub extensions: u16,
+}
+impl Header {
+    pub fn to_bytes(&self) -> [u8; HEADER_LENGTH] {
+        let [extensions_low, extensions_high] = self.extensions.to_le_bytes();
+        [
+            self.network_id[0],
+            self.network_id[1],
+            self.version_max,
+            self.version_using,
+            self.version_min,
+            self.message_type as u8,
+            extensions_low,
+            extensions_high,
+        ]
+    }
+
+    pub fn from_bytes(bytes: &[u8; HEADER_LENGTH]) -> Result<Header, MessageError> {
+        if bytes[0] != b'R' {
+            return Err(MessageError::InvalidMagic);
+        }
+        Ok(Header {
+            network_id: [bytes[0], bytes[1]],
+            version_max: bytes[2],
+            version_using: bytes[3],
+            version_min: bytes[4],
+            message_type: MessageType::try_from(bytes[5])?,
+            extensions: u16::from_le_bytes([bytes[6], bytes[7]]),
+        })
+    }
+
+    /// The type of block in the payload, for `publish`, `confirm_req` and `confirm_ack` messages
+    pub fn block_type(&self) -> u8 {
+        ((self.extensions & BLOCK_TYPE_MASK) >> 8) as u8
+    }
+
+    /// The number of hashes in the payload, for `confirm_req` and `confirm_ack` messages
+    pub fn count(&self) -> usize {
+        ((self.extensions & COUNT_MASK) >> 12) as usize
+    }
+
+    /// The length of the payload which follows this header.
+    ///
+    /// This is also known for some messages which are not supported, so that they can be skipped.
+    /// The blocks which follow a `bulk_push` are not part of its payload.
+    pub fn payload_length(&self) -> Result<usize, MessageError> {
+        // either `count` hashes (of `hash_length` bytes each), or a single block
+        let hashes_or_block = |hash_length: usize| match self.block_type() {
+            NOT_A_BLOCK => Ok(self.count() * hash_length),
+            block_type => block_length(block_type),
+        };
+        Ok(match self.message_type {
+            MessageType::Keepalive => 8 * 18,
+            MessageType::Publish => block_length(self.block_type())?,
+            MessageType::ConfirmReq => hashes_or_block(64)?,
+            MessageType::ConfirmAck => 32 + 64 + 8 + hashes_or_block(32)?,
+            MessageType::BulkPull => BulkPull::length(self.extensions),
+            MessageType::BulkPush => 0,
+            MessageType::FrontierReq => FrontierReq::LENGTH,
+            MessageType::BulkPullAccount => BULK_PULL_ACCOUNT_LENGTH,
+            MessageType::NodeIdHandshake => NodeIdHandshake::length(self.extensions),
+            MessageType::TelemetryReq => 0,
+            MessageType::TelemetryAck => (self.extensions & TELEMETRY_SIZE_MASK) as usize,
+            // type and ID, followed by a payload whose length is in the extensions
+            MessageType::AscPullReq | MessageType::AscPullAck => 1 + 8 + self.extensions as usize,
+        })
+    }
+}
+
+/// A peer-to-peer message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Message {
+    /// Up to 8 peers of the sender. Unused slots are `[::]:0`.
+    Keepalive([SocketAddrV6; 8]),
+    /// A new block
+    Publish(Block),
+    /// A request for votes on blocks, as `(hash, root)` pairs.
+    /// The root is the `previous` field, or the account for blocks which open an account.
+    ConfirmReq(Vec<([u8; 32], [u8; 32])>),
+    /// A vote
+    ConfirmAck(Vote),
+    NodeIdHandshake(NodeIdHandshake),
+    TelemetryReq,
+    /// The peer's telemetry, or `None` if the peer declined to share it
+    TelemetryAck(Option<Telemetry>),
+    /// A bootstrap request for a range of blocks. The response is not a message (see `p2p::bootstrap`).
+    BulkPull(BulkPull),
+    /// A bootstrap request for account frontiers. The response is not a message (see `p2p::bootstrap`).
+    FrontierReq(FrontierReq),
+}
+impl Message {
+    pub fn message_type(&self) -> MessageType {
+        match self {
+            Message::Keepalive(_) => MessageType::Keepalive,
+            Message::Publish(_) => MessageType::Publish,
+            Message::ConfirmReq(_) => MessageType::ConfirmReq,
+            Message::ConfirmAck(_) => MessageType::ConfirmAck,
+            Message::NodeIdHandshake(_) => MessageType::NodeIdHandshake,
+            Message::TelemetryReq => MessageType::TelemetryReq,
+            Message::TelemetryAck(_) => MessageType::TelemetryAck,
+            Message::BulkPull(_) => MessageType::BulkPull,
+            Message::FrontierReq(_) => MessageType::FrontierReq,
+        }
+    }
+
+    /// Create this message's header, using the current protocol versions
+    pub fn header(&self, network: &Network) -> Result<Header, MessageError> {
+        let count = |count: usize| match count {
+            0..=MAX_HASHES => Ok((count as u16) << 12 | (NOT_A_BLOCK as u16) << 8),
+            _ => Err(MessageError::TooManyHashes),
+        };
+        let extensions = match self {
+            Message::Keepalive(_) | Message::TelemetryReq | Message::FrontierReq(_) => 0,
+            Message::Publish(_) => (STATE_BLOCK as u16) << 8,
+            Message::ConfirmReq(pairs) => count(pairs.len())?,
+            Message::ConfirmAck(vote) => count(vote.hashes.len())?,
+            Message::NodeIdHandshake(handshake) => handshake.extensions()?,
+            Message::BulkPull(bulk_pull) => bulk_pull.extensions(),
+            Message::TelemetryAck(None) => 0,
+            Message::TelemetryAck(Some(telemetry)) => {
+                let length = Telemetry::LENGTH + telemetry.unknown_data.len();
+                if length > TELEMETRY_SIZE_MASK as usize {
+                    return Err(MessageError::InvalidLength);
+                }
+                length as u16
+            }
+        };
+        Ok(Header {
+            network_id: network.network_id,
+            version_max: PROTOCOL_VERSION,
+            version_using: PROTOCOL_VERSION,
+            version_min: PROTOCOL_VERSION_MIN,
+            message_type: self.message_type(),
+            extensions,
+        })
+    }
+
+    /// Encode the payload of this message (without the header)
+    pub fn payload(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        match self {
+            Message::Keepalive(peers) => {
+                for peer in peers {
+                    bytes.extend_from_slice(&peer.ip().octets());
+                    bytes.extend_from_slice(&peer.port().to_le_bytes());
+                }
+            }
+            Message::Publish(block) => bytes.extend_from_slice(&block.to_bytes()),
+            Message::ConfirmReq(pairs) => {
+                for (hash, root) in pairs {
+                    bytes.extend_from_slice(hash);
+                    bytes.extend_from_slice(root);
+                }
+            }
+            Message::ConfirmAck(vote) => messages::encode_vote(vote, &mut bytes),
+            Message::NodeIdHandshake(handshake) => handshake.encode(&mut bytes),
+            Message::TelemetryReq | Message::TelemetryAck(None) => (),
+            Message::TelemetryAck(Some(telemetry)) => bytes.extend(telemetry.to_bytes()),
+            Message::BulkPull(bulk_pull) => bulk_pull.encode(&mut bytes),
+            Message::FrontierReq(frontier_req) => frontier_req.encode(&mut bytes),
+        }
+        bytes
+    }
+
+    /// Encode this message, including its header
+    pub fn to_bytes(&self, network: &Network) -> Result<Vec<u8>, MessageError> {
+        let header = self.header(network)?;
+        Ok([header.to_bytes().as_slice(), &self.payload()].concat())
+    }
+
+    /// Decode a message's payload, given its header
+    pub fn from_payload(header: &Header, payload: &[u8]) -> Result<Message, MessageError> {
+        let unsupported_block = match header.message_type {
+            MessageType::Publish => header.block_type() != STATE_BLOCK,
+            MessageType::ConfirmReq | MessageType::ConfirmAck => header.block_type() != NOT_A_BLOCK,
+            _ => false,
+        };
+        if unsupported_block {
+            return Err(MessageError::UnsupportedBlockType(header.block_type()));
+        }
+        if payload.len() != header.payload_length()? {
+            return Err(MessageError::InvalidLength);
+        }
+        let mut reader = Reader(payload);
+        let message = match header.message_type {
+            MessageType::Keepalive => Message::Keepalive(std::array::from_fn(|_| {
+                let ip: [u8; 16] = reader.take().unwrap();
+                let port: [u8; 2] = reader.take().unwrap();
+                SocketAddrV6::new(Ipv6Addr::from(ip), u16::from_le_bytes(port), 0, 0)
+            })),
+            MessageType::Publish => Message::Publish(Block::from_bytes(&reader.take()?)?),
+            MessageType::ConfirmReq => Message::ConfirmReq(
+                (0..header.count())
+                    .map(|_| Ok((reader.take()?, reader.take()?)))
+                    .collect::<Result<_, MessageError>>()?,
+            ),
+            MessageType::ConfirmAck => {
+                Message::ConfirmAck(messages::decode_vote(&mut reader, header.count())?)
+            }
+            MessageType::NodeIdHandshake => {
+                Message::NodeIdHandshake(NodeIdHandshake::decode(&mut reader, header.extensions)?)
+            }
+            MessageType::TelemetryReq => Message::TelemetryReq,
+            MessageType::TelemetryAck => match payload.len() {
+                0 => Message::TelemetryAck(None),
+                _ => Message::TelemetryAck(Some(Telemetry::from_bytes(reader.rest())?)),
+            },
+            MessageType::BulkPull => {
+                Message::BulkPull(BulkPull::decode(&mut reader, header.extensions)?)
+            }
+            MessageType::FrontierReq => Message::FrontierReq(FrontierReq::decode(&mut reader)?),
+            message_type => return Err(MessageError::UnsupportedMessageType(message_type)),
+        };
+        Ok(message)
+    }
+
+    /// Decode a message, including its header
+    pub fn from_bytes(bytes: &[u8]) -> Result<(Header, Message), MessageError> {
+        let header: &[u8; HEADER_LENGTH] = bytes
+            .get(..HEADER_LENGTH)
+            .and_then(|header| header.try_into().ok())
+            .ok_or(MessageError::InvalidLength)?;
+        let header = Header::from_bytes(header)?;
+        let message = Message::from_payload(&header, &bytes[HEADER_LENGTH..])?;
+        Ok((header, message))
+    }
+}
+
+/// Reads fixed-length fields from the front of a payload
+pub(crate) struct Reader<'a>(pub(crate) &'a [u8]);
+impl Reader<'_> {
+    pub(crate) fn take<const N: usize>(&mut self) -> Result<[u8; N], MessageError> {
+        if self.0.len() < N {
+            return Err(MessageError::InvalidLength);
+        }
+        let (field, rest) = self.0.split_at(N);
+        self.0 = rest;
+        Ok(field.try_into().unwrap())
+    }
+
+    pub(crate) fn account(&mut self) -> Result<Account, MessageError> {
+        Ok(Account::try_from(self.take::<32>()?)?)
+    }
+
+    pub(crate) fn signature(&mut self) -> Result<Signature, MessageError> {
+        Ok(Signature::try_from(self.take::<64>()?)?)
+    }
+
+    pub(crate) fn rest(&mut self) -> &[u8] {
+        std::mem::take(&mut self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telemetry::tests::signed_telemetry;
+    use crate::{BlockType, Key, SecretBytes};
+
+    fn key() -> Key {
+        Key::from_seed(&SecretBytes::from([9; 32]), 0)
+    }
+
+    fn roundtrip(message: Message) -> Vec<u8> {
+        let bytes = message.to_bytes(&Network::live()).unwrap();
+        let (header, decoded) = Message::from_bytes(&bytes).unwrap();
+        assert!(decoded == message);
+        assert!(header.payload_length().unwrap() == bytes.len() - HEADER_LENGTH);
+        bytes
+    }
+
+    fn from_hex(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn header() {
+        let bytes = from_hex("5243151514020000");
+        let header = Header::from_bytes(&bytes.clone().try_into().unwrap()).unwrap();
+        assert!(header.network_id == *b"RC" && header.message_type == MessageType::Keepalive);
+        assert!(header.version_max == 0x15 && header.version_min == 0x14);
+        assert!(header.to_bytes().as_slice() == bytes);
+
+        let confirm_ack = Header::from_bytes(&from_hex("5242151514050021").try_into().unwrap());
+        let confirm_ack = confirm_ack.unwrap();
+        assert!(confirm_ack.count() == 2 && confirm_ack.block_type() == NOT_A_BLOCK);
+        assert!(confirm_ack.payload_length().unwrap() == 32 + 64 + 8 + 64);
+
+        let invalid = Header::from_bytes(&from_hex("0043151514020000").try_into().unwrap());
+        assert!(invalid == Err(MessageError::InvalidMagic));
+        let unknown = Header::from_bytes(&from_hex("52431515141f0000").try_into().unwrap());
+        assert!(unknown == Err(MessageError::UnknownMessageType(0x1f)));
+    }
+
+    #[test]
+    fn keepalive() {
+        let mut peers = [SocketAddrV6::new(Ipv6Addr::UNSPECIFIED, 0, 0, 0); 8];
+        peers[0] = SocketAddrV6::new(Ipv6Addr::LOCALHOST, 7075, 0, 0);
+        let bytes = roundtrip(Message::Keepalive(peers));
+        assert!(bytes.len() == 8 + 144);
+        assert!(bytes[8..26] == from_hex("00000000000000000000000000000001a31b"));
+        assert!(bytes[26..] == [0; 126]);
+    }
+
+    #[test]
+    fn publish() {
+        let mut block = Block {
+            block_type: BlockType::Send,
+            account: key().to_account(),
+            previous: [1; 32],
+            representative: key().to_account(),
+            balance: 100,
+            link: [2; 32],
+            signature: Signature::default(),
+            work: [3; 8],
+        };
+        block.sign(&key());
+        let bytes = roundtrip(Message::Publish(block.clone()));
+        assert!(bytes[..8] == from_hex("5243151514030006"));
+        assert!(bytes[8..] == block.to_bytes());
+
+        let mut legacy = bytes.clone();
+        legacy[7] = 0x02;
+        assert!(Message::from_bytes(&legacy) == Err(MessageError::UnsupportedBlockType(2)));
+        assert!(Message::from_bytes(&bytes[..100]) == Err(MessageError::InvalidLength));
+    }
+
+    #[test]
+    fn confirm() {
+        let bytes = roundtrip(Message::ConfirmReq(vec![
+            ([1; 32], [2; 32]),
+            ([3; 32], [4; 32]),
+        ]));
+        assert!(bytes[..8] == from_hex("5243151514040021"));
+
+        let vote = Vote::new(&key(), 0x0102030405060708, 0, vec![[5; 32]]);
+        let bytes = roundtrip(Message::ConfirmAck(vote));
+        assert!(bytes[..8] == from_hex("5243151514050011"));
+        assert!(bytes[8 + 96..8 + 104] == from_hex("0007060504030201"));
+
+        // requests for votes on a whole block are only skipped
+        let mut with_block = from_hex("5243151514040006");
+        with_block.extend_from_slice(&[0; STATE_BLOCK_LENGTH]);
+        let header = Header::from_bytes(&with_block[..8].try_into().unwrap()).unwrap();
+        assert!(header.payload_length().unwrap() == STATE_BLOCK_LENGTH);
+        assert!(Message::from_bytes(&with_block) == Err(MessageError::UnsupportedBlockType(6)));
+
+        let too_many = Message::ConfirmReq(vec![([0; 32], [0; 32]); 16]);
+        assert!(too_many.to_bytes(&Network::live()) == Err(MessageError::TooManyHashes));
+    }
+
+    #[test]
+    fn node_id_handshake() {
+        let query = NodeIdHandshake {
+            v2: true,
+            query: Some([7; 32]),
+            response: None,
+        };
+        let bytes = roundtrip(Message::NodeIdHandshake(query));
+        assert!(bytes[..8] == from_hex("52431515140a0500") && bytes.len() == 8 + 32);
+
+        let response = HandshakeResponse {
+            node_id: key().to_account(),
+            v2: Some(HandshakeV2 {
+                salt: [8; 32],
+                genesis: [9; 32],
+            }),
+            signature: key().sign_message(&[7; 32]),
+        };
+        let both = NodeIdHandshake {
+            v2: true,
+            query: Some([6; 32]),
+            response: Some(response.clone()),
+        };
+        let bytes = roundtrip(Message::NodeIdHandshake(both));
+        assert!(bytes.len() == 8 + 32 + 32 + 64 + 64);
+        assert!(bytes[8 + 64..8 + 96] == [8; 32]);
+
+        let v1 = NodeIdHandshake {
+            v2: false,
+            query: None,
+            response: Some(response),
+        };
+        let message = Message::NodeIdHandshake(v1);
+        assert!(message.to_bytes(&Network::live()) == Err(MessageError::InvalidHandshake));
+    }
+
+    #[test]
+    fn bootstrap_requests() {
+        let bulk_pull = BulkPull {
+            start: [1; 32],
+            end: [2; 32],
+            count: None,
+        };
+        let bytes = roundtrip(Message::BulkPull(bulk_pull));
+        assert!(bytes.len() == HEADER_LENGTH + 64 && bytes[5] == 0x06);
+        let bytes = roundtrip(Message::BulkPull(BulkPull {
+            count: Some(0x01020304),
+            ..bulk_pull
+        }));
+        assert!(bytes[6..8] == [1, 0]);
+        assert!(bytes[HEADER_LENGTH + 64..] == from_hex("0004030201000000"));
+
+        let frontier_req = FrontierReq {
+            start: [0; 32],
+            age: u32::MAX,
+            count: 5,
+        };
+        let bytes = roundtrip(Message::FrontierReq(frontier_req));
+        assert!(bytes[HEADER_LENGTH + 32..] == from_hex("ffffffff05000000"));
+    }
+
+    #[test]
+    fn telemetry() {
+        let bytes = roundtrip(Message::TelemetryReq);
+        assert!(bytes == from_hex("52431515140c0000"));
+        roundtrip(Message::TelemetryAck(None));
+
+        let mut telemetry = signed_telemetry(&key());
+        telemetry.unknown_data = vec![11, 12];
+        telemetry.sign(&key());
+        let bytes = roundtrip(Message::TelemetryAck(Some(telemetry)));
+        // length of 204, in the extensions
+        assert!(bytes[..8] == from_hex("52431515140dcc00"));
+        assert!(bytes[8 + 96..8 + 104] == 1_u64.to_be_bytes());
+        assert!(bytes[bytes.len() - 2..] == [11, 12]);
+    }
+}
@@ -0,0 +1,414 @@
+//! An async TCP connection to a node, using the peer-to-peer protocol.
+//!
+//! This allows blocks to be broadcast (and keepalives received) directly, without depending on a trusted RPC.
+
+use super::{
+    block_length, HandshakeResponse, HandshakeV2, Header, Message, MessageError, MessageType,
+    NodeIdHandshake, HEADER_LENGTH, NOT_A_BLOCK,
+};
+use crate::ledger::ProcessError;
+use crate::secrets::random_bytes;
+use crate::{Account, Block, Key, Network};
+use std::error::Error;
+use std::fmt::Display;
+use std::io::Error as IoError;
+use std::net::SocketAddrV6;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpStream, ToSocketAddrs};
+use tokio::time::timeout;
+
+/// The hash of the live network's genesis block
+pub const LIVE_GENESIS_BLOCK: [u8; 32] = [
+    0x99, 0x1c, 0xf1, 0x90, 0x09, 0x4c, 0x00, 0xf0, 0xb6, 0x8e, 0x2e, 0x5f, 0x75, 0xf6, 0xbe, 0xe9,
+    0x5a, 0x2e, 0x0b, 0xd9, 0x3c, 0xea, 0xa4, 0xa6, 0x73, 0x4d, 0xb9, 0xf1, 0x9b, 0x72, 0x89, 0x48,
+];
+
+#[derive(Debug)]
+pub enum PeerError {
+    /// Error in the TCP connection
+    IoError(IoError),
+    /// The peer sent an invalid message
+    MessageError(MessageError),
+    /// The peer's messages are for a different network (its network ID is given)
+    WrongNetwork([u8; 2]),
+    /// The peer did not complete the handshake as expected
+    InvalidHandshake,
+    /// The peer's handshake response has an invalid signature
+    InvalidSignature,
+    /// The peer's genesis block differs from ours (its genesis hash is given)
+    WrongGenesis([u8; 32]),
+    /// A block pulled from the peer is invalid, or does not belong in the blockchain (its hash is given)
+    InvalidBlock([u8; 32], ProcessError),
+    /// Timed out while connecting or during the handshake
+    Timeout,
+}
+impl Display for PeerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self {
+            PeerError::IoError(err) => write!(f, "{err}"),
+            PeerError::MessageError(err) => write!(f, "{err}"),
+            PeerError::WrongNetwork(id) => {
+                write!(f, "peer is on a different network: {id:?}")
+            }
+            PeerError::InvalidHandshake => write!(f, "invalid handshake"),
+            PeerError::InvalidSignature => write!(f, "invalid handshake signature"),
+            PeerError::WrongGenesis(_) => write!(f, "peer has a different genesis block"),
+            PeerError::InvalidBlock(_, err) => write!(f, "invalid block: {err}"),
+            PeerError::Timeout => write!(f, "timed out"),
+        }
+    }
+}
+impl Error for PeerError {}
+impl From<IoError> for PeerError {
+    fn from(value: IoError) -> Self {
+        PeerError::IoError(value)
+    }
+}
+impl From<MessageError> for PeerError {
+    fn from(value: MessageError) -> Self {
+        PeerError::MessageError(value)
+    }
+}
+
+/// Options for `Peer::connect`
+#[derive(Debug, Clone)]
+pub struct PeerOptions {
+    /// The network which the peer is expected to be on (default: the live network)
+    pub network: Network,
+    /// Hash of the network's genesis block, which is exchanged during the handshake.
+    /// It can be found through the RPC's `version` action. (default: `LIVE_GENESIS_BLOCK`)
+    pub genesis_block: [u8; 32],
+    /// How long to wait for the connection and handshake (default: 10 seconds)
+    pub timeout: Duration,
+}
+impl PeerOptions {
+    /// The default options, using the given network and genesis block hash
+    pub fn for_network(network: &Network, genesis_block: [u8; 32]) -> PeerOptions {
+        PeerOptions {
+            network: network.clone(),
+            genesis_block,
+            timeout: Duration::from_secs(10),
+        }
+    }
+}
+impl Default for PeerOptions {
+    fn default() -> Self {
+        PeerOptions::for_network(&Network::live(), LIVE_GENESIS_BLOCK)
+    }
+}
+
+/// A connection to a node, whose node ID has been verified through the `node_id_handshake`
+#[derive(Debug)]
+pub struct Peer {
+    stream: TcpStream,
+    network: Network,
+    node_id: Account,
+}
+impl Peer {
+    /// Connect to the node, and perform the handshake using our node ID `key`
+    pub async fn connect<A: ToSocketAddrs>(
+        address: A,
+        key: &Key,
+        options: &PeerOptions,
+    ) -> Result<Peer, PeerError> {
+        let connect = async {
+            let stream = TcpStream::connect(address).await?;
+            Peer::handshake(stream, key, options).await
+        };
+        timeout(options.timeout, connect)
+            .await
+            .map_err(|_| PeerError::Timeout)?
+    }
+
+    /// Perform the handshake over an existing connection to a node, using our node ID `key`.
+    ///
+    /// Our cookie is sent first. The peer must sign it (along with a random salt and its genesis hash),
+    /// and we then sign the peer's cookie in return.
+    pub async fn handshake(
+        mut stream: TcpStream,
+        key: &Key,
+        options: &PeerOptions,
+    ) -> Result<Peer, PeerError> {
+        let network = &options.network;
+        let cookie: [u8; 32] = random_bytes().into();
+        let query = NodeIdHandshake {
+            v2: true,
+            query: Some(cookie),
+            response: None,
+        };
+        write_message(&mut stream, network, &Message::NodeIdHandshake(query)).await?;
+
+        let handshake = match read_message(&mut stream, network).await? {
+            Message::NodeIdHandshake(handshake) => handshake,
+            _ => return Err(PeerError::InvalidHandshake),
+        };
+        let (response, genesis) = match handshake.response {
+            Some(response @ HandshakeResponse { v2: Some(v2), .. }) => (response, v2.genesis),
+            _ => return Err(PeerError::InvalidHandshake),
+        };
+        if !response.has_valid_signature(&cookie) {
+            return Err(PeerError::InvalidSignature);
+        }
+        if genesis != options.genesis_block {
+            return Err(PeerError::WrongGenesis(genesis));
+        }
+
+        if let Some(peer_cookie) = handshake.query {
+            let v2 = HandshakeV2 {
+                salt: random_bytes().into(),
+                genesis: options.genesis_block,
+            };
+            let reply = NodeIdHandshake {
+                v2: true,
+                query: None,
+                response: Some(HandshakeResponse::new(key, &peer_cookie, Some(v2))),
+            };
+            write_message(&mut stream, network, &Message::NodeIdHandshake(reply)).await?;
+        }
+
+        Ok(Peer {
+            stream,
+            network: network.clone(),
+            node_id: response.node_id,
+        })
+    }
+
+    /// The peer's verified node ID
+    pub fn node_id(&self) -> &Account {
+        &self.node_id
+    }
+
+    /// Send a message to the peer
+    pub async fn send(&mut self, message: &Message) -> Result<(), PeerError> {
+        write_message(&mut self.stream, &self.network, message).await
+    }
+
+    /// Wait for the next message from the peer.
+    ///
+    /// Messages of types (or with blocks) which are not supported by this library are skipped,
+    /// along with the blocks which follow a `bulk_push`.
+    pub async fn receive(&mut self) -> Result<Message, PeerError> {
+        read_message(&mut self.stream, &self.network).await
+    }
+
+    /// Broadcast the block to the peer, through a `publish` message
+    pub async fn publish(&mut self, block: &Block) -> Result<(), PeerError> {
+        self.send(&Message::Publish(block.clone())).await
+    }
+
+    /// Wait for the next `keepalive` from the peer, skipping any other messages,
+    /// and return the peers it shared
+    pub async fn keepalive(&mut self) -> Result<[SocketAddrV6; 8], PeerError> {
+        loop {
+            if let Message::Keepalive(peers) = self.receive().await? {
+                return Ok(peers);
+            }
+        }
+    }
+}
+
+pub(super) async fn write_message(
+    stream: &mut TcpStream,
+    network: &Network,
+    message: &Message,
+) -> Result<(), PeerError> {
+    stream.write_all(&message.to_bytes(network)?).await?;
+    Ok(())
+}
+
+/// Read the next message of a supported type
+pub(super) async fn read_message(
+    stream: &mut TcpStream,
+    network: &Network,
+) -> Result<Message, PeerError> {
+    loop {
+        let mut header = [0; HEADER_LENGTH];
+        stream.read_exact(&mut header).await?;
+        let header = Header::from_bytes(&header)?;
+        if header.network_id != network.network_id {
+            return Err(PeerError::WrongNetwork(header.network_id));
+        }
+
+        let mut payload = vec![0; header.payload_length()?];
+        stream.read_exact(&mut payload).await?;
+        if header.message_type == MessageType::BulkPush {
+            skip_blocks(stream).await?;
+            continue;
+        }
+        match Message::from_payload(&header, &payload) {
+            Err(
+                MessageError::UnsupportedMessageType(_) | MessageError::UnsupportedBlockType(_),
+            ) => continue,
+            message => return Ok(message?),
+        }
+    }
+}
+
+/// Skip the blocks which follow a `bulk_push`, each prefixed by its type, up to the terminating `not_a_block`
+async fn skip_blocks(stream: &mut TcpStream) -> Result<(), PeerError> {
+    loop {
+        let length = match stream.read_u8().await? {
+            NOT_A_BLOCK => return Ok(()),
+            block_type => block_length(block_type)?,
+        };
+        let mut block = vec![0; length];
+        stream.read_exact(&mut block).await?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BlockType, SecretBytes, Signature};
+    use std::net::Ipv6Addr;
+    use tokio::net::TcpListener;
+
+    fn key(i: u32) -> Key {
+        Key::from_seed(&SecretBytes::from([12; 32]), i)
+    }
+
+    /// Stands in for a node: answers the handshake with `node_key`, sends a keepalive,
+    /// then returns the client's node ID and the first block it publishes
+    async fn stand_in_peer(
+        listener: TcpListener,
+        node_key: Key,
+        genesis: [u8; 32],
+    ) -> Result<(Account, Block), PeerError> {
+        let network = Network::live();
+        let (mut stream, _) = listener.accept().await?;
+        let cookie = match read_message(&mut stream, &network).await? {
+            Message::NodeIdHandshake(NodeIdHandshake {
+                query: Some(cookie),
+                ..
+            }) => cookie,
+            _ => return Err(PeerError::InvalidHandshake),
+        };
+
+        let our_cookie = [5; 32];
+        let v2 = HandshakeV2 {
+            salt: [6; 32],
+            genesis,
+        };
+        let reply = NodeIdHandshake {
+            v2: true,
+            query: Some(our_cookie),
+            response: Some(HandshakeResponse::new(&node_key, &cookie, Some(v2))),
+        };
+        write_message(&mut stream, &network, &Message::NodeIdHandshake(reply)).await?;
+
+        let response = match read_message(&mut stream, &network).await? {
+            Message::NodeIdHandshake(NodeIdHandshake {
+                response: Some(response),
+                ..
+            }) => response,
+            _ => return Err(PeerError::InvalidHandshake),
+        };
+        if !response.has_valid_signature(&our_cookie) {
+            return Err(PeerError::InvalidSignature);
+        }
+
+        // unsupported messages should be skipped by the client
+        let header = |message_type: u8, extensions: [u8; 2]| {
+            vec![
+                b'R',
+                b'C',
+                0x15,
+                0x15,
+                0x14,
+                message_type,
+                extensions[0],
+                extensions[1],
+            ]
+        };
+        let mut unsupported = header(0x0f, [2, 0]);
+        unsupported.extend_from_slice(&[0; 1 + 8 + 2]);
+        // bulk_pull_account
+        unsupported.extend(header(0x0b, [0, 0]));
+        unsupported.extend_from_slice(&[0; 32 + 16 + 1]);
+        // bulk_push, followed by a legacy send block, a state block and not_a_block
+        unsupported.extend(header(0x07, [0, 0]));
+        unsupported.push(2);
+        unsupported.extend_from_slice(&[0; 152]);
+        unsupported.push(6);
+        unsupported.extend_from_slice(&[0; 216]);
+        unsupported.push(1);
+        // confirm_req with a legacy receive block, and with a state block
+        unsupported.extend(header(0x04, [0, 3]));
+        unsupported.extend_from_slice(&[0; 136]);
+        unsupported.extend(header(0x04, [0, 6]));
+        unsupported.extend_from_slice(&[0; 216]);
+        stream.write_all(&unsupported).await?;
+        let peers = [SocketAddrV6::new(Ipv6Addr::LOCALHOST, 7075, 0, 0); 8];
+        write_message(&mut stream, &network, &Message::TelemetryReq).await?;
+        write_message(&mut stream, &network, &Message::Keepalive(peers)).await?;
+
+        loop {
+            if let Message::Publish(block) = read_message(&mut stream, &network).await? {
+                return Ok((response.node_id, block));
+            }
+        }
+    }
+
+    fn block() -> Block {
+        let mut block = Block {
+            block_type: BlockType::Send,
+            account: key(2).to_account(),
+            previous: [1; 32],
+            representative: key(2).to_account(),
+            balance: 10,
+            link: [2; 32],
+            signature: Signature::default(),
+            work: [3; 8],
+        };
+        block.sign(&key(2));
+        block
+    }
+
+    #[tokio::test]
+    async fn handshake() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let stand_in = tokio::spawn(stand_in_peer(listener, key(0), LIVE_GENESIS_BLOCK));
+
+        let mut peer = Peer::connect(address, &key(1), &PeerOptions::default())
+            .await
+            .unwrap();
+        assert!(peer.node_id() == &key(0).to_account());
+        let peers = peer.keepalive().await.unwrap();
+        assert!(peers[0].port() == 7075);
+        peer.publish(&block()).await.unwrap();
+
+        let (client_id, published) = stand_in.await.unwrap().unwrap();
+        assert!(client_id == key(1).to_account());
+        assert!(published == block());
+    }
+
+    #[tokio::test]
+    async fn wrong_genesis() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(stand_in_peer(listener, key(0), [9; 32]));
+
+        let result = Peer::connect(address, &key(1), &PeerOptions::default()).await;
+        assert!(matches!(result, Err(PeerError::WrongGenesis(genesis)) if genesis == [9; 32]));
+    }
+
+    #[test]
+    fn handshake_response() {
+        let v2 = HandshakeV2 {
+            salt: [1; 32],
+            genesis: [2; 32],
+        };
+        let response = HandshakeResponse::new(&key(0), &[3; 32], Some(v2));
+        assert!(response.has_valid_signature(&[3; 32]));
+        assert!(!response.has_valid_signature(&[4; 32]));
+
+        let mut forged = response.clone();
+        forged.v2 = Some(HandshakeV2 {
+            genesis: [5; 32],
+            ..v2
+        });
+        assert!(!forged.has_valid_signature(&[3; 32]));
+    }
+}
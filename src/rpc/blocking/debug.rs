@@ -0,0 +1,464 @@
+use super::super::{
+    debug::{map_response, Response},
+    encode,
+    error::RpcError,
+    parse, AccountInfo, BlockCount, BlockInfo, ConfirmationQuorum, NodeVersion, Receivable,
+    TelemetryInfo,
+};
+use crate::{Account, Block, Telemetry};
+
+use json::{Map, Value as JsonValue};
+use reqwest::blocking::{ClientBuilder, RequestBuilder};
+use serde_json as json;
+
+macro_rules! request {
+    ($rpc: expr, $json: expr) => {
+        $rpc._raw_request($json)
+    };
+}
+
+/// Blocking version of `rpc::debug::DebugRpc`.
+///
+/// See the official [Nano RPC documentation](https://docs.nano.org/commands/rpc-protocol/) for details.
+#[derive(Debug)]
+pub struct DebugRpc {
+    builder: RequestBuilder,
+    url: String,
+    proxy: Option<String>,
+}
+impl DebugRpc {
+    pub fn new(url: &str, proxy: impl Into<Option<String>>) -> Result<DebugRpc, RpcError> {
+        let rpc = DebugRpc {
+            builder: ClientBuilder::new().build()?.post(url),
+            url: url.into(),
+            proxy: proxy.into(),
+        };
+        if rpc.try_clone().is_none() {
+            return Err(RpcError::InvalidRPC);
+        }
+        Ok(rpc)
+    }
+
+    /// Get the url of this RPC
+    pub fn get_url(&self) -> &str {
+        &self.url
+    }
+
+    /// Get the proxy of this RPC, if set
+    pub fn get_proxy(&self) -> Option<&str> {
+        self.proxy.as_deref()
+    }
+
+    /// Same as `command`, but *everything* must be set manually
+    pub fn _raw_request(&self, json: JsonValue) -> Response<JsonValue> {
+        let result = self
+            .clone()
+            .builder
+            .json(&json)
+            .send()
+            .and_then(|response| response.json::<JsonValue>())
+            .map_err(RpcError::ReqwestError);
+
+        let raw_response = match &result {
+            Ok(json) => Some(json.clone()),
+            Err(_) => None,
+        };
+
+        Response {
+            raw_request: Some(json),
+            raw_response,
+            result,
+        }
+    }
+
+    /// Send a request to the node with `action` set to `[command]`, and setting the given `arguments`
+    pub fn command(
+        &self,
+        command: &str,
+        mut arguments: Map<String, JsonValue>,
+    ) -> Response<JsonValue> {
+        arguments.insert("action".into(), command.into());
+        self._raw_request(JsonValue::Object(arguments))
+    }
+
+    pub fn account_balance(&self, account: &Account) -> Response<u128> {
+        let response = request!(self, encode::account_balance(account));
+        let result = match response.result {
+            Ok(json) => parse::account_balance(json),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    pub fn account_block_count(&self, account: &Account) -> Response<usize> {
+        let response = request!(self, encode::account_block_count(account));
+        let result = match response.result {
+            Ok(json) => parse::account_block_count(json),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// Lists the account's blocks, starting at `head` (or the newest block if `head` is `None`), and going back at most `count` number of blocks.
+    /// Will stop at first legacy block.
+    pub fn account_history(
+        &self,
+        account: &Account,
+        count: usize,
+        head: Option<[u8; 32]>,
+        offset: Option<usize>,
+    ) -> Response<Vec<Block>> {
+        let response = request!(self, encode::account_history(account, count, head, offset));
+        let result = match response.result {
+            Ok(json) => parse::account_history(json, account),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// Gets general information about an account.
+    /// Returns `None` if the account has not been opened.
+    pub fn account_info(&self, account: &Account) -> Response<Option<AccountInfo>> {
+        let response = request!(self, encode::account_info(account));
+        let result = match response.result {
+            Ok(json) => parse::account_info(json),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// Returns the public key of the account
+    pub fn account_key(&self, account: &Account) -> Response<[u8; 32]> {
+        let response = request!(self, encode::account_key(account));
+        let result = match response.result {
+            Ok(json) => parse::account_key(json, account),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// Indirect, relies on `account_history`.
+    /// This allows the data to be verified to an extent.
+    ///
+    /// If an account is not yet opened, its representative will be returned as `None`.
+    pub fn account_representative(&self, account: &Account) -> Response<Option<Account>> {
+        let response = self.account_history(account, 1, None, None);
+        let result = match response.result {
+            Ok(history) => parse::account_representative(history),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// Returns the voting weight delegated to the account
+    pub fn account_weight(&self, account: &Account) -> Response<u128> {
+        let response = request!(self, encode::account_weight(account));
+        let result = match response.result {
+            Ok(json) => parse::account_weight(json),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    pub fn accounts_balances(&self, accounts: &[Account]) -> Response<Vec<u128>> {
+        if accounts.is_empty() {
+            return Response::no_request(Ok(vec![]));
+        }
+
+        let response = request!(self, encode::accounts_balances(accounts));
+        let result = match response.result {
+            Ok(json) => parse::accounts_balances(json, accounts),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// Returns the hash of the frontier (newest) block of the given accounts.
+    /// If an account is not yet opened, its frontier will be returned as `None`.
+    pub fn accounts_frontiers(&self, accounts: &[Account]) -> Response<Vec<Option<[u8; 32]>>> {
+        if accounts.is_empty() {
+            return Response::no_request(Ok(vec![]));
+        }
+
+        let response = request!(self, encode::accounts_frontiers(accounts));
+        let result = match response.result {
+            Ok(json) => parse::accounts_frontiers(json, accounts),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// For each account, returns the receivable transactions as `Vec<Receivable>`
+    pub fn accounts_receivable(
+        &self,
+        accounts: &[Account],
+        count: usize,
+        threshold: u128,
+    ) -> Response<Vec<Vec<Receivable>>> {
+        if accounts.is_empty() {
+            return Response::no_request(Ok(vec![]));
+        }
+
+        let response = request!(
+            self,
+            encode::accounts_receivable(accounts, count, threshold)
+        );
+        let result = match response.result {
+            Ok(json) => parse::accounts_receivable(json, accounts),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// If an account is not yet opened, its representative will be returned as `None`
+    pub fn accounts_representatives(&self, accounts: &[Account]) -> Response<Vec<Option<Account>>> {
+        if accounts.is_empty() {
+            return Response::no_request(Ok(vec![]));
+        }
+
+        let response = request!(self, encode::accounts_representatives(accounts));
+        let result = match response.result {
+            Ok(json) => parse::accounts_representatives(json, accounts),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// Returns the account which the block belongs to
+    pub fn block_account(&self, hash: [u8; 32]) -> Response<Account> {
+        let response = request!(self, encode::block_account(hash));
+        let result = match response.result {
+            Ok(json) => parse::block_account(json),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// Request confirmation for the block, if it is not already confirmed
+    pub fn block_confirm(&self, hash: [u8; 32]) -> Response<()> {
+        let response = request!(self, encode::block_confirm(hash));
+        let result = match response.result {
+            Ok(json) => parse::block_confirm(json),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    pub fn block_count(&self) -> Response<BlockCount> {
+        let response = request!(self, encode::block_count());
+        let result = match response.result {
+            Ok(json) => parse::block_count(json),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// Legacy blocks, and blocks that don't exist, will return `None`
+    pub fn block_info(&self, hash: [u8; 32]) -> Response<Option<BlockInfo>> {
+        let response = request!(self, encode::block_info(hash));
+        let result = match response.result {
+            Ok(json) => parse::block_info(json),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// Legacy blocks, and blocks that don't exist, will return `None`
+    pub fn blocks_info(&self, hashes: &[[u8; 32]]) -> Response<Vec<Option<BlockInfo>>> {
+        if hashes.is_empty() {
+            return Response::no_request(Ok(vec![]));
+        }
+
+        let response = request!(self, encode::blocks_info(hashes));
+        let result = match response.result {
+            Ok(json) => parse::blocks_info(json, hashes),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// Returns the hashes of `block` and its predecessors, going back at most `count` number of blocks
+    pub fn chain(&self, block: [u8; 32], count: usize) -> Response<Vec<[u8; 32]>> {
+        let response = request!(self, encode::chain(block, count));
+        let result = match response.result {
+            Ok(json) => parse::chain(json, block, count),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    pub fn confirmation_quorum(&self) -> Response<ConfirmationQuorum> {
+        let response = request!(self, encode::confirmation_quorum());
+        let result = match response.result {
+            Ok(json) => parse::confirmation_quorum(json),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// Returns the accounts which delegate to `account` with at least `threshold` balance, along with their balances.
+    /// If `start` is set, only delegators after it are returned.
+    pub fn delegators(
+        &self,
+        account: &Account,
+        count: usize,
+        threshold: u128,
+        start: Option<&Account>,
+    ) -> Response<Vec<(Account, u128)>> {
+        let response = request!(self, encode::delegators(account, count, threshold, start));
+        let result = match response.result {
+            Ok(json) => parse::delegators(json, count),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    pub fn delegators_count(&self, account: &Account) -> Response<usize> {
+        let response = request!(self, encode::delegators_count(account));
+        let result = match response.result {
+            Ok(json) => parse::delegators_count(json),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// Returns the hash of the block
+    pub fn process(&self, block: &Block) -> Response<[u8; 32]> {
+        if !block.block_type.is_state() {
+            return Response::no_request(Err(RpcError::LegacyBlockType));
+        }
+
+        let hash = block.hash();
+        let response = request!(self, encode::process(block));
+        let result = match response.result {
+            Ok(json) => parse::process(json, hash),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// Returns whether or not the send block is still receivable
+    pub fn receivable_exists(&self, hash: [u8; 32]) -> Response<bool> {
+        let response = request!(self, encode::receivable_exists(hash));
+        let result = match response.result {
+            Ok(json) => parse::receivable_exists(json),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// Returns the online representatives, along with their voting weight
+    pub fn representatives_online(&self) -> Response<Vec<(Account, u128)>> {
+        let response = request!(self, encode::representatives_online());
+        let result = match response.result {
+            Ok(json) => parse::representatives_online(json),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// Returns the hashes of `block` and its successors, going forward at most `count` number of blocks
+    pub fn successors(&self, block: [u8; 32], count: usize) -> Response<Vec<[u8; 32]>> {
+        let response = request!(self, encode::successors(block, count));
+        let result = match response.result {
+            Ok(json) => parse::successors(json, block, count),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// Returns telemetry metrics, averaged across the node's peers
+    pub fn telemetry(&self) -> Response<TelemetryInfo> {
+        let response = request!(self, encode::telemetry());
+        let result = match response.result {
+            Ok(json) => parse::telemetry(json),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// Returns the telemetry of each of the node's peers, as signed by the peers' node IDs
+    pub fn telemetry_raw(&self) -> Response<Vec<Telemetry>> {
+        let response = request!(self, encode::telemetry_raw());
+        let result = match response.result {
+            Ok(json) => parse::telemetry_raw(json),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// Returns the node's uptime, in seconds
+    pub fn uptime(&self) -> Response<u64> {
+        let response = request!(self, encode::uptime());
+        let result = match response.result {
+            Ok(json) => parse::uptime(json),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    pub fn version(&self) -> Response<NodeVersion> {
+        let response = request!(self, encode::version());
+        let result = match response.result {
+            Ok(json) => parse::version(json),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// Stop generating work for the given hash
+    pub fn work_cancel(&self, work_hash: [u8; 32]) -> Response<()> {
+        let response = request!(self, encode::work_cancel(work_hash));
+        let result = match response.result {
+            Ok(json) => parse::work_cancel(json),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// Returns the generated work, assuming no error is encountered
+    pub fn work_generate(
+        &self,
+        work_hash: [u8; 32],
+        custom_difficulty: Option<[u8; 8]>,
+    ) -> Response<[u8; 8]> {
+        let response = request!(self, encode::work_generate(work_hash, custom_difficulty));
+        let result = match response.result {
+            Ok(json) => parse::work_generate(json, work_hash, custom_difficulty),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// Check the work for the given hash.
+    /// If `custom_difficulty` is `None`, returns whether or not the work is valid for all blocks.
+    pub fn work_validate(
+        &self,
+        work_hash: [u8; 32],
+        work: [u8; 8],
+        custom_difficulty: Option<[u8; 8]>,
+    ) -> Response<bool> {
+        let response = request!(
+            self,
+            encode::work_validate(work_hash, work, custom_difficulty)
+        );
+        let result = match response.result {
+            Ok(json) => parse::work_validate(json, work_hash, work, custom_difficulty),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    fn try_clone(&self) -> Option<DebugRpc> {
+        Some(DebugRpc {
+            builder: self.builder.try_clone()?,
+            url: self.url.clone(),
+            proxy: self.proxy.clone(),
+        })
+    }
+}
+impl Clone for DebugRpc {
+    fn clone(&self) -> Self {
+        self.try_clone().unwrap()
+    }
+}
@@ -0,0 +1,331 @@
+//! Blocking (synchronous) versions of the RPC clients.
+//!
+//! These must **not** be used from within an async runtime.
+
+pub mod debug;
+
+use super::paging::{HistoryPager, ReceivablePager};
+use super::{
+    AccountInfo, BlockCount, BlockInfo, ConfirmationOptions, ConfirmationQuorum, HistoryDirection,
+    NodeVersion, Receivable, RpcError, TelemetryInfo,
+};
+use crate::{Account, Block, Telemetry};
+use debug::DebugRpc;
+use json::{Map, Value as JsonValue};
+use serde_json as json;
+use std::collections::VecDeque;
+use std::thread::sleep;
+use std::time::Instant;
+
+/// Blocking version of `rpc::Rpc`.
+///
+/// See the official [Nano RPC documentation](https://docs.nano.org/commands/rpc-protocol/) for details.
+#[derive(Debug, Clone)]
+pub struct Rpc(DebugRpc);
+impl Rpc {
+    pub fn new(url: &str, proxy: impl Into<Option<String>>) -> Result<Rpc, RpcError> {
+        Ok(Rpc(DebugRpc::new(url, proxy)?))
+    }
+
+    /// Get the URL of this RPC
+    pub fn get_url(&self) -> &str {
+        self.0.get_url()
+    }
+
+    /// Get the proxy of this RPC, if set
+    pub fn get_proxy(&self) -> Option<&str> {
+        self.0.get_proxy()
+    }
+
+    /// Same as `command`, but *everything* must be set manually
+    pub fn _raw_request(&self, json: JsonValue) -> Result<JsonValue, RpcError> {
+        self.0._raw_request(json).result
+    }
+
+    /// Send a request to the node with `action` set to `[command]`, and setting the given `arguments`
+    pub fn command(
+        &self,
+        command: &str,
+        arguments: Map<String, JsonValue>,
+    ) -> Result<JsonValue, RpcError> {
+        self.0.command(command, arguments).result
+    }
+
+    pub fn account_balance(&self, account: &Account) -> Result<u128, RpcError> {
+        self.0.account_balance(account).result
+    }
+
+    pub fn account_block_count(&self, account: &Account) -> Result<usize, RpcError> {
+        self.0.account_block_count(account).result
+    }
+
+    /// Lists the account's blocks, starting at `head` (or the newest block if `head` is `None`), and going back at most `count` number of blocks.
+    /// Will stop at first legacy block.
+    pub fn account_history(
+        &self,
+        account: &Account,
+        count: usize,
+        head: Option<[u8; 32]>,
+        offset: Option<usize>,
+    ) -> Result<Vec<Block>, RpcError> {
+        self.0.account_history(account, count, head, offset).result
+    }
+
+    /// Gets general information about an account.
+    /// Returns `None` if the account has not been opened.
+    pub fn account_info(&self, account: &Account) -> Result<Option<AccountInfo>, RpcError> {
+        self.0.account_info(account).result
+    }
+
+    /// Returns the public key of the account
+    pub fn account_key(&self, account: &Account) -> Result<[u8; 32], RpcError> {
+        self.0.account_key(account).result
+    }
+
+    /// Indirect, relies on `account_history`.
+    /// This allows the data to be verified to an extent.
+    ///
+    /// If an account is not yet opened, its representative will be returned as `None`.
+    pub fn account_representative(&self, account: &Account) -> Result<Option<Account>, RpcError> {
+        self.0.account_representative(account).result
+    }
+
+    /// Returns the voting weight delegated to the account
+    pub fn account_weight(&self, account: &Account) -> Result<u128, RpcError> {
+        self.0.account_weight(account).result
+    }
+
+    pub fn accounts_balances(&self, accounts: &[Account]) -> Result<Vec<u128>, RpcError> {
+        self.0.accounts_balances(accounts).result
+    }
+
+    /// Returns the hash of the frontier (newest) block of the given accounts.
+    /// If an account is not yet opened, its frontier will be returned as `None`.
+    pub fn accounts_frontiers(
+        &self,
+        accounts: &[Account],
+    ) -> Result<Vec<Option<[u8; 32]>>, RpcError> {
+        self.0.accounts_frontiers(accounts).result
+    }
+
+    /// For each account, returns the receivable transactions as `Vec<Receivable>`
+    pub fn accounts_receivable(
+        &self,
+        accounts: &[Account],
+        count: usize,
+        threshold: u128,
+    ) -> Result<Vec<Vec<Receivable>>, RpcError> {
+        self.0
+            .accounts_receivable(accounts, count, threshold)
+            .result
+    }
+
+    /// If an account is not yet opened, its representative will be returned as `None`
+    pub fn accounts_representatives(
+        &self,
+        accounts: &[Account],
+    ) -> Result<Vec<Option<Account>>, RpcError> {
+        self.0.accounts_representatives(accounts).result
+    }
+
+    /// Returns the account which the block belongs to
+    pub fn block_account(&self, hash: [u8; 32]) -> Result<Account, RpcError> {
+        self.0.block_account(hash).result
+    }
+
+    /// Request confirmation for the block, if it is not already confirmed
+    pub fn block_confirm(&self, hash: [u8; 32]) -> Result<(), RpcError> {
+        self.0.block_confirm(hash).result
+    }
+
+    pub fn block_count(&self) -> Result<BlockCount, RpcError> {
+        self.0.block_count().result
+    }
+
+    /// Legacy blocks, and blocks that don't exist, will return `None`
+    pub fn block_info(&self, hash: [u8; 32]) -> Result<Option<BlockInfo>, RpcError> {
+        self.0.block_info(hash).result
+    }
+
+    /// Legacy blocks, and blocks that don't exist, will return `None`
+    pub fn blocks_info(&self, hashes: &[[u8; 32]]) -> Result<Vec<Option<BlockInfo>>, RpcError> {
+        self.0.blocks_info(hashes).result
+    }
+
+    /// Returns the hashes of `block` and its predecessors, going back at most `count` number of blocks
+    pub fn chain(&self, block: [u8; 32], count: usize) -> Result<Vec<[u8; 32]>, RpcError> {
+        self.0.chain(block, count).result
+    }
+
+    pub fn confirmation_quorum(&self) -> Result<ConfirmationQuorum, RpcError> {
+        self.0.confirmation_quorum().result
+    }
+
+    /// Returns the accounts which delegate to `account` with at least `threshold` balance, along with their balances.
+    /// If `start` is set, only delegators after it are returned.
+    pub fn delegators(
+        &self,
+        account: &Account,
+        count: usize,
+        threshold: u128,
+        start: Option<&Account>,
+    ) -> Result<Vec<(Account, u128)>, RpcError> {
+        self.0.delegators(account, count, threshold, start).result
+    }
+
+    pub fn delegators_count(&self, account: &Account) -> Result<usize, RpcError> {
+        self.0.delegators_count(account).result
+    }
+
+    /// Returns the hash of the block
+    pub fn process(&self, block: &Block) -> Result<[u8; 32], RpcError> {
+        self.0.process(block).result
+    }
+
+    /// Returns whether or not the send block is still receivable
+    pub fn receivable_exists(&self, hash: [u8; 32]) -> Result<bool, RpcError> {
+        self.0.receivable_exists(hash).result
+    }
+
+    /// Returns the online representatives, along with their voting weight
+    pub fn representatives_online(&self) -> Result<Vec<(Account, u128)>, RpcError> {
+        self.0.representatives_online().result
+    }
+
+    /// Returns the hashes of `block` and its successors, going forward at most `count` number of blocks
+    pub fn successors(&self, block: [u8; 32], count: usize) -> Result<Vec<[u8; 32]>, RpcError> {
+        self.0.successors(block, count).result
+    }
+
+    /// Returns telemetry metrics, averaged across the node's peers
+    pub fn telemetry(&self) -> Result<TelemetryInfo, RpcError> {
+        self.0.telemetry().result
+    }
+
+    /// Returns the telemetry of each of the node's peers, as signed by the peers' node IDs.
+    /// Use `Telemetry::has_valid_signature` to reject forged telemetry.
+    pub fn telemetry_raw(&self) -> Result<Vec<Telemetry>, RpcError> {
+        self.0.telemetry_raw().result
+    }
+
+    /// Returns the node's uptime, in seconds
+    pub fn uptime(&self) -> Result<u64, RpcError> {
+        self.0.uptime().result
+    }
+
+    pub fn version(&self) -> Result<NodeVersion, RpcError> {
+        self.0.version().result
+    }
+
+    /// Stop generating work for the given hash
+    pub fn work_cancel(&self, work_hash: [u8; 32]) -> Result<(), RpcError> {
+        self.0.work_cancel(work_hash).result
+    }
+
+    /// Returns the generated work, assuming no error is encountered
+    pub fn work_generate(
+        &self,
+        work_hash: [u8; 32],
+        custom_difficulty: Option<[u8; 8]>,
+    ) -> Result<[u8; 8], RpcError> {
+        self.0.work_generate(work_hash, custom_difficulty).result
+    }
+
+    /// Check the work for the given hash.
+    /// If `custom_difficulty` is `None`, returns whether or not the work is valid for all blocks.
+    pub fn work_validate(
+        &self,
+        work_hash: [u8; 32],
+        work: [u8; 8],
+        custom_difficulty: Option<[u8; 8]>,
+    ) -> Result<bool, RpcError> {
+        self.0
+            .work_validate(work_hash, work, custom_difficulty)
+            .result
+    }
+
+    /// Blocking version of `rpc::Rpc::process_and_wait`
+    pub fn process_and_wait(
+        &self,
+        block: &Block,
+        options: ConfirmationOptions,
+    ) -> Result<BlockInfo, RpcError> {
+        let hash = self.process(block)?;
+        self.wait_for_confirmation(hash, options)
+    }
+
+    /// Blocking version of `rpc::Rpc::wait_for_confirmation`
+    pub fn wait_for_confirmation(
+        &self,
+        hash: [u8; 32],
+        options: ConfirmationOptions,
+    ) -> Result<BlockInfo, RpcError> {
+        let deadline = Instant::now() + options.timeout;
+        let mut block_confirm = options.block_confirm;
+        loop {
+            if let Some(info) = self.block_info(hash)? {
+                if info.block.hash() != hash {
+                    return Err(RpcError::InvalidData);
+                }
+                if info.confirmed {
+                    return Ok(info);
+                }
+                if block_confirm {
+                    self.block_confirm(hash)?;
+                    block_confirm = false;
+                }
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(RpcError::Timeout);
+            }
+            sleep(options.poll_interval.min(deadline - now));
+        }
+    }
+
+    /// Blocking version of `rpc::Rpc::account_history_stream`.
+    ///
+    /// Walks the account's entire history, requesting `page_size` blocks at a time.
+    pub fn account_history_iter<'a>(
+        &'a self,
+        account: &Account,
+        page_size: usize,
+        direction: HistoryDirection,
+    ) -> impl Iterator<Item = Result<Block, RpcError>> + 'a {
+        let mut pager = HistoryPager::new(account, page_size, direction);
+        let mut page = VecDeque::new();
+        std::iter::from_fn(move || loop {
+            if let Some(block) = page.pop_front() {
+                return Some(Ok(block));
+            }
+            let request = pager.next_request()?;
+            match pager.accept(self._raw_request(request)) {
+                Ok(blocks) => page.extend(blocks),
+                Err(err) => return Some(Err(err)),
+            }
+        })
+    }
+
+    /// Blocking version of `rpc::Rpc::receivable_stream`.
+    ///
+    /// Lists all receivable transactions for the account above `threshold`, requesting `page_size` at a time.
+    pub fn receivable_iter<'a>(
+        &'a self,
+        account: &Account,
+        page_size: usize,
+        threshold: u128,
+    ) -> impl Iterator<Item = Result<Receivable, RpcError>> + 'a {
+        let mut pager = ReceivablePager::new(account, page_size, threshold);
+        let mut page = VecDeque::new();
+        std::iter::from_fn(move || loop {
+            if let Some(receivable) = page.pop_front() {
+                return Some(Ok(receivable));
+            }
+            let request = pager.next_request()?;
+            match pager.accept(self._raw_request(request)) {
+                Ok(receivables) => page.extend(receivables),
+                Err(err) => return Some(Err(err)),
+            }
+        })
+    }
+}
@@ -0,0 +1,344 @@
+use super::{BlockInfo, Rpc, RpcError};
+use crate::Block;
+use std::time::Duration;
+use tokio::time::{sleep, timeout};
+
+#[cfg(feature = "websocket")]
+use super::websocket::{Confirmation, Event, Topic, WebSocket};
+#[cfg(feature = "websocket")]
+use crate::Account;
+
+/// How long to wait for the node to acknowledge restoring the websocket's subscription
+#[cfg(feature = "websocket")]
+const RESTORE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Options for waiting for a block to be confirmed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConfirmationOptions {
+    /// How long to wait before giving up with `RpcError::Timeout` (default: 60 seconds)
+    pub timeout: Duration,
+    /// How often to poll `block_info`, when polling (default: 1 second)
+    pub poll_interval: Duration,
+    /// Whether or not to call `block_confirm` once the node has the unconfirmed block (default: `false`)
+    pub block_confirm: bool,
+}
+impl Default for ConfirmationOptions {
+    fn default() -> Self {
+        ConfirmationOptions {
+            timeout: Duration::from_secs(60),
+            poll_interval: Duration::from_secs(1),
+            block_confirm: false,
+        }
+    }
+}
+
+impl Rpc {
+    /// Publish the block, and wait for it to be confirmed by polling `block_info`
+    pub async fn process_and_wait(
+        &self,
+        block: &Block,
+        options: ConfirmationOptions,
+    ) -> Result<BlockInfo, RpcError> {
+        let hash = self.process(block).await?;
+        self.wait_for_confirmation(hash, options).await
+    }
+
+    /// Poll `block_info` until the block is confirmed.
+    ///
+    /// Blocks which the node does not have yet (and legacy blocks) are treated as unconfirmed.
+    pub async fn wait_for_confirmation(
+        &self,
+        hash: [u8; 32],
+        options: ConfirmationOptions,
+    ) -> Result<BlockInfo, RpcError> {
+        let mut block_confirm = options.block_confirm;
+        let poll = self.poll_confirmation(hash, options.poll_interval, &mut block_confirm);
+        timeout(options.timeout, poll)
+            .await
+            .map_err(|_| RpcError::Timeout)?
+    }
+
+    async fn poll_confirmation(
+        &self,
+        hash: [u8; 32],
+        poll_interval: Duration,
+        block_confirm: &mut bool,
+    ) -> Result<BlockInfo, RpcError> {
+        loop {
+            if let Some(info) = self.confirmed_block_info(hash, block_confirm).await? {
+                return Ok(info);
+            }
+            sleep(poll_interval).await;
+        }
+    }
+
+    /// Returns the block info if the block is confirmed,
+    /// calling `block_confirm` (and unsetting `block_confirm`) if the node has the unconfirmed block
+    async fn confirmed_block_info(
+        &self,
+        hash: [u8; 32],
+        block_confirm: &mut bool,
+    ) -> Result<Option<BlockInfo>, RpcError> {
+        let Some(info) = self.block_info(hash).await? else {
+            return Ok(None);
+        };
+        if info.block.hash() != hash {
+            return Err(RpcError::InvalidData);
+        }
+        if info.confirmed {
+            return Ok(Some(info));
+        }
+        if *block_confirm {
+            self.block_confirm(hash).await?;
+            *block_confirm = false;
+        }
+        Ok(None)
+    }
+
+    /// Same as `process_and_wait`, but listens for the confirmation on the websocket
+    #[cfg(feature = "websocket")]
+    pub async fn process_and_wait_websocket(
+        &self,
+        block: &Block,
+        websocket: &mut WebSocket,
+        options: ConfirmationOptions,
+    ) -> Result<BlockInfo, RpcError> {
+        self.process(block).await?;
+        self.wait_for_confirmation_websocket(block, websocket, options)
+            .await
+    }
+
+    /// Same as `wait_for_confirmation`, but listens for the confirmation on the websocket instead of polling.
+    /// If the websocket connection is lost (and cannot be recovered), falls back to polling.
+    ///
+    /// The websocket's `confirmation` subscription is temporarily extended to include the block's account,
+    /// and restored afterwards (even on timeout). The node's acknowledgement of the restored subscription
+    /// is awaited for at most 5 seconds (or `options.timeout`, if shorter).
+    /// Other events received while waiting will still be returned by `WebSocket::next_event`.
+    #[cfg(feature = "websocket")]
+    pub async fn wait_for_confirmation_websocket(
+        &self,
+        block: &Block,
+        websocket: &mut WebSocket,
+        options: ConfirmationOptions,
+    ) -> Result<BlockInfo, RpcError> {
+        let previous = websocket
+            .get_subscriptions()
+            .iter()
+            .find(|topic| matches!(topic, Topic::Confirmation { .. }))
+            .cloned();
+        let extended = match &previous {
+            Some(Topic::Confirmation { accounts }) if accounts.is_empty() => None,
+            Some(Topic::Confirmation { accounts }) if accounts.contains(&block.account) => None,
+            Some(Topic::Confirmation { accounts }) => {
+                Some([accounts.as_slice(), std::slice::from_ref(&block.account)].concat())
+            }
+            _ => Some(vec![block.account.clone()]),
+        };
+
+        // shared by the websocket and polling, so that `block_confirm` is only called once
+        let mut block_confirm = options.block_confirm;
+        let mut others = vec![];
+        let result = timeout(options.timeout, async {
+            let subscribed = match &extended {
+                Some(accounts) => websocket
+                    .subscribe(Topic::Confirmation {
+                        accounts: accounts.clone(),
+                    })
+                    .await
+                    .is_ok(),
+                None => true,
+            };
+            if subscribed {
+                match self
+                    .listen_for_confirmation(
+                        block.hash(),
+                        websocket,
+                        &mut others,
+                        &mut block_confirm,
+                    )
+                    .await
+                {
+                    Err(RpcError::WebSocketError(_)) => (),
+                    result => return result,
+                }
+            }
+            self.poll_confirmation(block.hash(), options.poll_interval, &mut block_confirm)
+                .await
+        })
+        .await
+        .map_err(|_| RpcError::Timeout)
+        .and_then(|result| result);
+
+        // a timeout may have interrupted the subscription request, after the node received it
+        if extended.is_some() {
+            let restore = restore_subscription(websocket, previous, others);
+            // if this times out, the subscriptions will still be restored when reconnecting
+            let _ = timeout(RESTORE_TIMEOUT.min(options.timeout), restore).await;
+        } else {
+            websocket.requeue(others);
+        }
+        result
+    }
+
+    #[cfg(feature = "websocket")]
+    async fn listen_for_confirmation(
+        &self,
+        hash: [u8; 32],
+        websocket: &mut WebSocket,
+        others: &mut Vec<Event>,
+        block_confirm: &mut bool,
+    ) -> Result<BlockInfo, RpcError> {
+        // the block may have been confirmed before subscribing
+        if let Some(info) = self.confirmed_block_info(hash, block_confirm).await? {
+            return Ok(info);
+        }
+        loop {
+            match websocket.next_event().await? {
+                Event::Confirmation(confirmation)
+                    if confirmation.block_info.block.hash() == hash =>
+                {
+                    return Ok(confirmation.block_info.clone())
+                }
+                event => others.push(event),
+            }
+        }
+    }
+}
+
+/// Restore the `confirmation` subscription to `previous`,
+/// requeueing the other events except confirmations which were only received due to the extended subscription
+#[cfg(feature = "websocket")]
+async fn restore_subscription(
+    websocket: &mut WebSocket,
+    previous: Option<Topic>,
+    mut others: Vec<Event>,
+) {
+    others.retain(|event| match (event, &previous) {
+        (Event::Confirmation(confirmation), Some(Topic::Confirmation { accounts })) => {
+            is_for_accounts(confirmation, accounts)
+        }
+        (Event::Confirmation(_), _) => false,
+        _ => true,
+    });
+    websocket.requeue(others);
+
+    // if this fails, the subscriptions will still be restored when reconnecting
+    let _ = match previous {
+        Some(topic) => websocket.subscribe(topic).await,
+        None => {
+            websocket
+                .unsubscribe(&Topic::Confirmation { accounts: vec![] })
+                .await
+        }
+    };
+}
+
+/// Whether or not the node would send the confirmation to a subscription for `accounts`
+#[cfg(feature = "websocket")]
+fn is_for_accounts(confirmation: &Confirmation, accounts: &[Account]) -> bool {
+    let block = &confirmation.block_info.block;
+    accounts.contains(&block.account)
+        || block
+            .link_as_account()
+            .is_ok_and(|link| accounts.contains(&link))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::mock::mock_rpc;
+    use crate::rpc::util::{block_to_json, to_uppercase_hex};
+    use crate::{BlockType, Key, SecretBytes, Signature};
+    use serde_json::{json, Value as JsonValue};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    fn signed_block() -> Block {
+        let key = Key::from_seed(&SecretBytes::from([5; 32]), 0);
+        let mut block = Block {
+            block_type: BlockType::Change,
+            account: key.to_account(),
+            previous: [1; 32],
+            representative: key.to_account(),
+            balance: 10,
+            link: [0; 32],
+            signature: Signature::default(),
+            work: [0; 8],
+        };
+        block.sign(&key);
+        block
+    }
+
+    fn block_info_json(block: &Block, confirmed: bool) -> JsonValue {
+        json!({
+            "block_account": block.account.to_string(),
+            "amount": "0",
+            "balance": block.balance.to_string(),
+            "height": "2",
+            "local_timestamp": "1564935350",
+            "confirmed": confirmed.to_string(),
+            "contents": block_to_json(block),
+            "subtype": "change"
+        })
+    }
+
+    /// A node which confirms the block after it has been asked about it `confirm_after` times
+    async fn mock_node(block: &Block, confirm_after: usize) -> (Rpc, Arc<AtomicUsize>) {
+        let block = block.clone();
+        let block_confirm_calls = Arc::new(AtomicUsize::new(0));
+        let block_info_calls = AtomicUsize::new(0);
+        let calls = block_confirm_calls.clone();
+        let url = mock_rpc(move |request| match request["action"].as_str().unwrap() {
+            "process" => json!({"hash": to_uppercase_hex(&block.hash())}),
+            "block_confirm" => {
+                calls.fetch_add(1, Ordering::SeqCst);
+                json!({"started": "1"})
+            }
+            "block_info" => {
+                let count = block_info_calls.fetch_add(1, Ordering::SeqCst);
+                match count {
+                    0 => json!({"error": "Block not found"}),
+                    _ => block_info_json(&block, count >= confirm_after),
+                }
+            }
+            _ => json!({"error": "Unknown command"}),
+        })
+        .await;
+        (Rpc::new(&url, None).unwrap(), block_confirm_calls)
+    }
+
+    fn options() -> ConfirmationOptions {
+        ConfirmationOptions {
+            timeout: Duration::from_secs(5),
+            poll_interval: Duration::from_millis(10),
+            block_confirm: true,
+        }
+    }
+
+    #[tokio::test]
+    async fn process_and_wait() {
+        let block = signed_block();
+        let (rpc, block_confirm_calls) = mock_node(&block, 3).await;
+        let info = rpc.process_and_wait(&block, options()).await.unwrap();
+        assert!(info.confirmed);
+        assert!(info.block == block);
+        assert!(block_confirm_calls.load(Ordering::SeqCst) == 1);
+    }
+
+    #[tokio::test]
+    async fn timeout() {
+        let block = signed_block();
+        let (rpc, block_confirm_calls) = mock_node(&block, usize::MAX).await;
+        let options = ConfirmationOptions {
+            timeout: Duration::from_millis(100),
+            block_confirm: false,
+            ..options()
+        };
+        assert!(matches!(
+            rpc.wait_for_confirmation(block.hash(), options).await,
+            Err(RpcError::Timeout)
+        ));
+        assert!(block_confirm_calls.load(Ordering::SeqCst) == 0);
+    }
+}
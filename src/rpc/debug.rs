@@ -1,5 +1,8 @@
-use super::{encode, error::RpcError, parse, AccountInfo, BlockInfo, Receivable};
-use crate::{Account, Block};
+use super::{
+    encode, error::RpcError, parse, AccountInfo, BlockCount, BlockInfo, ConfirmationQuorum,
+    NodeVersion, Receivable, TelemetryInfo,
+};
+use crate::{Account, Block, Telemetry};
 
 use json::{Map, Value as JsonValue};
 use reqwest::{ClientBuilder, RequestBuilder};
@@ -20,6 +23,8 @@ macro_rules! map_response {
         }
     };
 }
+#[cfg(feature = "blocking")]
+pub(crate) use map_response;
 
 #[derive(Debug)]
 pub struct Response<T> {
@@ -28,7 +33,7 @@ pub struct Response<T> {
     pub result: Result<T, RpcError>,
 }
 impl<T> Response<T> {
-    fn no_request(result: Result<T, RpcError>) -> Response<T> {
+    pub(crate) fn no_request(result: Result<T, RpcError>) -> Response<T> {
         Response {
             raw_request: None,
             raw_response: None,
@@ -114,6 +119,15 @@ impl DebugRpc {
         map_response!(response, result)
     }
 
+    pub async fn account_block_count(&self, account: &Account) -> Response<usize> {
+        let response = request!(self, encode::account_block_count(account));
+        let result = match response.result {
+            Ok(json) => parse::account_block_count(json),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
     /// Lists the account's blocks, starting at `head` (or the newest block if `head` is `None`), and going back at most `count` number of blocks.
     /// Will stop at first legacy block.
     pub async fn account_history(
@@ -142,6 +156,16 @@ impl DebugRpc {
         map_response!(response, result)
     }
 
+    /// Returns the public key of the account
+    pub async fn account_key(&self, account: &Account) -> Response<[u8; 32]> {
+        let response = request!(self, encode::account_key(account));
+        let result = match response.result {
+            Ok(json) => parse::account_key(json, account),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
     /// Indirect, relies on `account_history`.
     /// This allows the data to be verified to an extent.
     ///
@@ -155,6 +179,16 @@ impl DebugRpc {
         map_response!(response, result)
     }
 
+    /// Returns the voting weight delegated to the account
+    pub async fn account_weight(&self, account: &Account) -> Response<u128> {
+        let response = request!(self, encode::account_weight(account));
+        let result = match response.result {
+            Ok(json) => parse::account_weight(json),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
     pub async fn accounts_balances(&self, accounts: &[Account]) -> Response<Vec<u128>> {
         if accounts.is_empty() {
             return Response::no_request(Ok(vec![]));
@@ -225,6 +259,35 @@ impl DebugRpc {
         map_response!(response, result)
     }
 
+    /// Returns the account which the block belongs to
+    pub async fn block_account(&self, hash: [u8; 32]) -> Response<Account> {
+        let response = request!(self, encode::block_account(hash));
+        let result = match response.result {
+            Ok(json) => parse::block_account(json),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// Request confirmation for the block, if it is not already confirmed
+    pub async fn block_confirm(&self, hash: [u8; 32]) -> Response<()> {
+        let response = request!(self, encode::block_confirm(hash));
+        let result = match response.result {
+            Ok(json) => parse::block_confirm(json),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    pub async fn block_count(&self) -> Response<BlockCount> {
+        let response = request!(self, encode::block_count());
+        let result = match response.result {
+            Ok(json) => parse::block_count(json),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
     /// Legacy blocks, and blocks that don't exist, will return `None`
     pub async fn block_info(&self, hash: [u8; 32]) -> Response<Option<BlockInfo>> {
         let response = request!(self, encode::block_info(hash));
@@ -249,6 +312,51 @@ impl DebugRpc {
         map_response!(response, result)
     }
 
+    /// Returns the hashes of `block` and its predecessors, going back at most `count` number of blocks
+    pub async fn chain(&self, block: [u8; 32], count: usize) -> Response<Vec<[u8; 32]>> {
+        let response = request!(self, encode::chain(block, count));
+        let result = match response.result {
+            Ok(json) => parse::chain(json, block, count),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    pub async fn confirmation_quorum(&self) -> Response<ConfirmationQuorum> {
+        let response = request!(self, encode::confirmation_quorum());
+        let result = match response.result {
+            Ok(json) => parse::confirmation_quorum(json),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// Returns the accounts which delegate to `account` with at least `threshold` balance, along with their balances.
+    /// If `start` is set, only delegators after it are returned.
+    pub async fn delegators(
+        &self,
+        account: &Account,
+        count: usize,
+        threshold: u128,
+        start: Option<&Account>,
+    ) -> Response<Vec<(Account, u128)>> {
+        let response = request!(self, encode::delegators(account, count, threshold, start));
+        let result = match response.result {
+            Ok(json) => parse::delegators(json, count),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    pub async fn delegators_count(&self, account: &Account) -> Response<usize> {
+        let response = request!(self, encode::delegators_count(account));
+        let result = match response.result {
+            Ok(json) => parse::delegators_count(json),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
     /// Returns the hash of the block
     pub async fn process(&self, block: &Block) -> Response<[u8; 32]> {
         if !block.block_type.is_state() {
@@ -264,6 +372,85 @@ impl DebugRpc {
         map_response!(response, result)
     }
 
+    /// Returns whether or not the send block is still receivable
+    pub async fn receivable_exists(&self, hash: [u8; 32]) -> Response<bool> {
+        let response = request!(self, encode::receivable_exists(hash));
+        let result = match response.result {
+            Ok(json) => parse::receivable_exists(json),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// Returns the online representatives, along with their voting weight
+    pub async fn representatives_online(&self) -> Response<Vec<(Account, u128)>> {
+        let response = request!(self, encode::representatives_online());
+        let result = match response.result {
+            Ok(json) => parse::representatives_online(json),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// Returns the hashes of `block` and its successors, going forward at most `count` number of blocks
+    pub async fn successors(&self, block: [u8; 32], count: usize) -> Response<Vec<[u8; 32]>> {
+        let response = request!(self, encode::successors(block, count));
+        let result = match response.result {
+            Ok(json) => parse::successors(json, block, count),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// Returns telemetry metrics, averaged across the node's peers
+    pub async fn telemetry(&self) -> Response<TelemetryInfo> {
+        let response = request!(self, encode::telemetry());
+        let result = match response.result {
+            Ok(json) => parse::telemetry(json),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// Returns the telemetry of each of the node's peers, as signed by the peers' node IDs
+    pub async fn telemetry_raw(&self) -> Response<Vec<Telemetry>> {
+        let response = request!(self, encode::telemetry_raw());
+        let result = match response.result {
+            Ok(json) => parse::telemetry_raw(json),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// Returns the node's uptime, in seconds
+    pub async fn uptime(&self) -> Response<u64> {
+        let response = request!(self, encode::uptime());
+        let result = match response.result {
+            Ok(json) => parse::uptime(json),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    pub async fn version(&self) -> Response<NodeVersion> {
+        let response = request!(self, encode::version());
+        let result = match response.result {
+            Ok(json) => parse::version(json),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
+    /// Stop generating work for the given hash
+    pub async fn work_cancel(&self, work_hash: [u8; 32]) -> Response<()> {
+        let response = request!(self, encode::work_cancel(work_hash));
+        let result = match response.result {
+            Ok(json) => parse::work_cancel(json),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
     /// Returns the generated work, assuming no error is encountered
     pub async fn work_generate(
         &self,
@@ -278,6 +465,25 @@ impl DebugRpc {
         map_response!(response, result)
     }
 
+    /// Check the work for the given hash.
+    /// If `custom_difficulty` is `None`, returns whether or not the work is valid for all blocks.
+    pub async fn work_validate(
+        &self,
+        work_hash: [u8; 32],
+        work: [u8; 8],
+        custom_difficulty: Option<[u8; 8]>,
+    ) -> Response<bool> {
+        let response = request!(
+            self,
+            encode::work_validate(work_hash, work, custom_difficulty)
+        );
+        let result = match response.result {
+            Ok(json) => parse::work_validate(json, work_hash, work, custom_difficulty),
+            Err(err) => Err(err),
+        };
+        map_response!(response, result)
+    }
+
     fn try_clone(&self) -> Option<DebugRpc> {
         Some(DebugRpc {
             builder: self.builder.try_clone()?,
@@ -1,4 +1,6 @@
 use super::util::{block_to_json, to_uppercase_hex};
+#[cfg(feature = "work-server")]
+use crate::{block::get_work_difficulty, Network};
 use crate::{Account, Block};
 use json::{Map, Value as JsonValue};
 use serde_json as json;
@@ -10,12 +12,40 @@ pub fn account_balance(account: &Account) -> JsonValue {
     JsonValue::Object(arguments)
 }
 
+pub fn account_block_count(account: &Account) -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "account_block_count".into());
+    arguments.insert("account".into(), account.into());
+    JsonValue::Object(arguments)
+}
+
 pub fn account_history(
     account: &Account,
     count: usize,
     head: Option<[u8; 32]>,
     offset: Option<usize>,
 ) -> JsonValue {
+    JsonValue::Object(account_history_arguments(account, count, head, offset))
+}
+
+/// Same as `account_history`, but with `reverse` set: lists the account's blocks, starting at `head` (or the `open` block if `head` is `None`), and going forward
+pub fn account_history_reverse(
+    account: &Account,
+    count: usize,
+    head: Option<[u8; 32]>,
+    offset: Option<usize>,
+) -> JsonValue {
+    let mut arguments = account_history_arguments(account, count, head, offset);
+    arguments.insert("reverse".into(), true.into());
+    JsonValue::Object(arguments)
+}
+
+fn account_history_arguments(
+    account: &Account,
+    count: usize,
+    head: Option<[u8; 32]>,
+    offset: Option<usize>,
+) -> Map<String, JsonValue> {
     let mut arguments = Map::new();
     arguments.insert("action".into(), "account_history".into());
     arguments.insert("raw".into(), true.into());
@@ -27,7 +57,7 @@ pub fn account_history(
     if let Some(offset) = offset {
         arguments.insert("offset".into(), offset.to_string().into());
     }
-    JsonValue::Object(arguments)
+    arguments
 }
 
 pub fn account_info(account: &Account) -> JsonValue {
@@ -40,6 +70,20 @@ pub fn account_info(account: &Account) -> JsonValue {
     JsonValue::Object(arguments)
 }
 
+pub fn account_key(account: &Account) -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "account_key".into());
+    arguments.insert("account".into(), account.into());
+    JsonValue::Object(arguments)
+}
+
+pub fn account_weight(account: &Account) -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "account_weight".into());
+    arguments.insert("account".into(), account.into());
+    JsonValue::Object(arguments)
+}
+
 pub fn accounts_balances(accounts: &[Account]) -> JsonValue {
     let accounts: Vec<String> = accounts.iter().map(|account| account.to_string()).collect();
 
@@ -79,6 +123,26 @@ pub fn accounts_representatives(accounts: &[Account]) -> JsonValue {
     JsonValue::Object(arguments)
 }
 
+pub fn block_account(hash: [u8; 32]) -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "block_account".into());
+    arguments.insert("hash".into(), to_uppercase_hex(&hash).into());
+    JsonValue::Object(arguments)
+}
+
+pub fn block_confirm(hash: [u8; 32]) -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "block_confirm".into());
+    arguments.insert("hash".into(), to_uppercase_hex(&hash).into());
+    JsonValue::Object(arguments)
+}
+
+pub fn block_count() -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "block_count".into());
+    JsonValue::Object(arguments)
+}
+
 pub fn block_info(hash: [u8; 32]) -> JsonValue {
     let mut arguments = Map::new();
     arguments.insert("action".into(), "block_info".into());
@@ -98,6 +162,44 @@ pub fn blocks_info(hashes: &[[u8; 32]]) -> JsonValue {
     JsonValue::Object(arguments)
 }
 
+pub fn chain(block: [u8; 32], count: usize) -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "chain".into());
+    arguments.insert("block".into(), to_uppercase_hex(&block).into());
+    arguments.insert("count".into(), count.to_string().into());
+    JsonValue::Object(arguments)
+}
+
+pub fn confirmation_quorum() -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "confirmation_quorum".into());
+    JsonValue::Object(arguments)
+}
+
+pub fn delegators(
+    account: &Account,
+    count: usize,
+    threshold: u128,
+    start: Option<&Account>,
+) -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "delegators".into());
+    arguments.insert("account".into(), account.into());
+    arguments.insert("count".into(), count.to_string().into());
+    arguments.insert("threshold".into(), threshold.to_string().into());
+    if let Some(start) = start {
+        arguments.insert("start".into(), start.into());
+    }
+    JsonValue::Object(arguments)
+}
+
+pub fn delegators_count(account: &Account) -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "delegators_count".into());
+    arguments.insert("account".into(), account.into());
+    JsonValue::Object(arguments)
+}
+
 pub fn process(block: &Block) -> JsonValue {
     let mut arguments = Map::new();
     arguments.insert("action".into(), "process".into());
@@ -107,6 +209,71 @@ pub fn process(block: &Block) -> JsonValue {
     JsonValue::Object(arguments)
 }
 
+pub fn receivable(account: &Account, count: usize, threshold: u128, offset: usize) -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "receivable".into());
+    arguments.insert("account".into(), account.into());
+    arguments.insert("sorting".into(), true.into());
+    arguments.insert("threshold".into(), threshold.to_string().into());
+    arguments.insert("count".into(), count.to_string().into());
+    arguments.insert("offset".into(), offset.to_string().into());
+    JsonValue::Object(arguments)
+}
+
+pub fn receivable_exists(hash: [u8; 32]) -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "receivable_exists".into());
+    arguments.insert("hash".into(), to_uppercase_hex(&hash).into());
+    JsonValue::Object(arguments)
+}
+
+pub fn representatives_online() -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "representatives_online".into());
+    arguments.insert("weight".into(), true.into());
+    JsonValue::Object(arguments)
+}
+
+pub fn successors(block: [u8; 32], count: usize) -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "successors".into());
+    arguments.insert("block".into(), to_uppercase_hex(&block).into());
+    arguments.insert("count".into(), count.to_string().into());
+    JsonValue::Object(arguments)
+}
+
+pub fn telemetry() -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "telemetry".into());
+    JsonValue::Object(arguments)
+}
+
+pub fn telemetry_raw() -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "telemetry".into());
+    arguments.insert("raw".into(), "true".into());
+    JsonValue::Object(arguments)
+}
+
+pub fn uptime() -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "uptime".into());
+    JsonValue::Object(arguments)
+}
+
+pub fn version() -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "version".into());
+    JsonValue::Object(arguments)
+}
+
+pub fn work_cancel(work_hash: [u8; 32]) -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "work_cancel".into());
+    arguments.insert("hash".into(), to_uppercase_hex(&work_hash).into());
+    JsonValue::Object(arguments)
+}
+
 pub fn work_generate(work_hash: [u8; 32], custom_difficulty: Option<[u8; 8]>) -> JsonValue {
     let mut arguments = Map::new();
     arguments.insert("action".into(), "work_generate".into());
@@ -118,6 +285,99 @@ pub fn work_generate(work_hash: [u8; 32], custom_difficulty: Option<[u8; 8]>) ->
     JsonValue::Object(arguments)
 }
 
+pub fn work_validate(
+    work_hash: [u8; 32],
+    work: [u8; 8],
+    custom_difficulty: Option<[u8; 8]>,
+) -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "work_validate".into());
+    arguments.insert("hash".into(), to_uppercase_hex(&work_hash).into());
+    arguments.insert("work".into(), hex::encode(work).into());
+    if let Some(difficulty) = custom_difficulty {
+        arguments.insert("difficulty".into(), hex::encode(difficulty).into());
+    }
+    JsonValue::Object(arguments)
+}
+
+/// The response to `work_generate`, as a work server. `base_difficulty` is the default difficulty.
+#[cfg(feature = "work-server")]
+pub fn work_generate_response(
+    work_hash: [u8; 32],
+    work: [u8; 8],
+    base_difficulty: [u8; 8],
+) -> JsonValue {
+    // the node returns the difficulty of the work itself
+    let achieved = get_work_difficulty(work_hash, work);
+    let mut response = Map::new();
+    response.insert("work".into(), hex::encode(work).into());
+    response.insert("difficulty".into(), hex::encode(achieved).into());
+    response.insert(
+        "multiplier".into(),
+        multiplier(achieved, base_difficulty).into(),
+    );
+    response.insert("hash".into(), to_uppercase_hex(&work_hash).into());
+    JsonValue::Object(response)
+}
+
+/// The response to `work_cancel`, as a work server
+#[cfg(feature = "work-server")]
+pub fn work_cancel_response() -> JsonValue {
+    let mut response = Map::new();
+    response.insert("success".into(), "".into());
+    JsonValue::Object(response)
+}
+
+/// The response to `work_validate`, as a work server, checking against the network's thresholds
+#[cfg(feature = "work-server")]
+pub fn work_validate_response(
+    work_hash: [u8; 32],
+    work: [u8; 8],
+    custom_difficulty: Option<[u8; 8]>,
+    network: &Network,
+) -> JsonValue {
+    let achieved = get_work_difficulty(work_hash, work);
+    let valid = |difficulty: [u8; 8]| bool_string(achieved >= difficulty).into();
+    let mut response = Map::new();
+    if let Some(difficulty) = custom_difficulty {
+        response.insert("valid".into(), valid(difficulty));
+    }
+    response.insert("valid_all".into(), valid(network.send_work_difficulty));
+    response.insert(
+        "valid_receive".into(),
+        valid(network.receive_work_difficulty),
+    );
+    response.insert("difficulty".into(), hex::encode(achieved).into());
+    response.insert(
+        "multiplier".into(),
+        multiplier(achieved, network.send_work_difficulty).into(),
+    );
+    JsonValue::Object(response)
+}
+
+/// An error response, as a work server
+#[cfg(feature = "work-server")]
+pub fn error_response(message: &str) -> JsonValue {
+    let mut response = Map::new();
+    response.insert("error".into(), message.into());
+    JsonValue::Object(response)
+}
+
+#[cfg(feature = "work-server")]
+fn bool_string(value: bool) -> &'static str {
+    match value {
+        true => "1",
+        false => "0",
+    }
+}
+
+/// How much harder `difficulty` is than `base`, formatted like the node does
+#[cfg(feature = "work-server")]
+fn multiplier(difficulty: [u8; 8], base: [u8; 8]) -> String {
+    let reverse = |difficulty: [u8; 8]| ((1 << 64) - u64::from_be_bytes(difficulty) as u128) as f64;
+    format!("{:.15}", reverse(base) / reverse(difficulty))
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{Block, BlockType};
@@ -137,6 +397,20 @@ mod tests {
         )
     }
 
+    #[test]
+    fn account_block_count() {
+        let account = "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3"
+            .try_into()
+            .unwrap();
+        let json = super::account_block_count(&account);
+        assert!(
+            json == json!({
+                "action": "account_block_count",
+                "account": "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3"
+            })
+        )
+    }
+
     #[test]
     fn account_history() {
         let account = "nano_1ipx847tk8o46pwxt5qjdbncjqcbwcc1rrmqnkztrfjy5k7z4imsrata9est"
@@ -166,6 +440,25 @@ mod tests {
         )
     }
 
+    #[test]
+    fn account_history_reverse() {
+        let account = "nano_1ipx847tk8o46pwxt5qjdbncjqcbwcc1rrmqnkztrfjy5k7z4imsrata9est"
+            .try_into()
+            .unwrap();
+
+        let json = super::account_history_reverse(&account, 5, Some([255; 32]), None);
+        assert!(
+            json == json!({
+                "action": "account_history",
+                "account": "nano_1ipx847tk8o46pwxt5qjdbncjqcbwcc1rrmqnkztrfjy5k7z4imsrata9est",
+                "head": "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+                "count": "5",
+                "raw": true,
+                "reverse": true
+            })
+        )
+    }
+
     #[test]
     fn account_info() {
         let account = "nano_1gyeqc6u5j3oaxbe5qy1hyz3q745a318kh8h9ocnpan7fuxnq85cxqboapu5"
@@ -184,6 +477,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn account_key() {
+        let account = "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3"
+            .try_into()
+            .unwrap();
+        let json = super::account_key(&account);
+        assert!(
+            json == json!({
+                "action": "account_key",
+                "account": "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3"
+            })
+        )
+    }
+
+    #[test]
+    fn account_weight() {
+        let account = "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3"
+            .try_into()
+            .unwrap();
+        let json = super::account_weight(&account);
+        assert!(
+            json == json!({
+                "action": "account_weight",
+                "account": "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3"
+            })
+        )
+    }
+
     #[test]
     fn accounts_balances() {
         let accounts = vec![
@@ -263,6 +584,37 @@ mod tests {
         )
     }
 
+    #[test]
+    fn block_account() {
+        let hash = hex::decode("87434F8041869A01C8F6F263B87972D7BA443A72E0A97D7A3FD0CCC2358FD6F9")
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let json = super::block_account(hash);
+        assert!(
+            json == json!({
+                "action": "block_account",
+                "hash": "87434F8041869A01C8F6F263B87972D7BA443A72E0A97D7A3FD0CCC2358FD6F9"
+            })
+        )
+    }
+
+    #[test]
+    fn block_confirm() {
+        let json = super::block_confirm([0xAB; 32]);
+        assert!(
+            json == json!({
+                "action": "block_confirm",
+                "hash": "AB".repeat(32)
+            })
+        )
+    }
+
+    #[test]
+    fn block_count() {
+        assert!(super::block_count() == json!({"action": "block_count"}))
+    }
+
     #[test]
     fn block_info() {
         let hash = hex::decode("87434F8041869A01C8F6F263B87972D7BA443A72E0A97D7A3FD0CCC2358FD6F9")
@@ -299,6 +651,72 @@ mod tests {
         )
     }
 
+    #[test]
+    fn chain() {
+        let hash = hex::decode("87434F8041869A01C8F6F263B87972D7BA443A72E0A97D7A3FD0CCC2358FD6F9")
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let json = super::chain(hash, 5);
+        assert!(
+            json == json!({
+                "action": "chain",
+                "block": "87434F8041869A01C8F6F263B87972D7BA443A72E0A97D7A3FD0CCC2358FD6F9",
+                "count": "5"
+            })
+        )
+    }
+
+    #[test]
+    fn confirmation_quorum() {
+        assert!(super::confirmation_quorum() == json!({"action": "confirmation_quorum"}))
+    }
+
+    #[test]
+    fn delegators() {
+        let account = "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3"
+            .try_into()
+            .unwrap();
+        let start = "nano_1ipx847tk8o46pwxt5qjdbncjqcbwcc1rrmqnkztrfjy5k7z4imsrata9est"
+            .try_into()
+            .unwrap();
+
+        let json = super::delegators(&account, 50, 1000000000000000000000000, None);
+        assert!(
+            json == json!({
+                "action": "delegators",
+                "account": "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3",
+                "count": "50",
+                "threshold": "1000000000000000000000000"
+            })
+        );
+
+        let json = super::delegators(&account, 50, 0, Some(&start));
+        assert!(
+            json == json!({
+                "action": "delegators",
+                "account": "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3",
+                "count": "50",
+                "threshold": "0",
+                "start": "nano_1ipx847tk8o46pwxt5qjdbncjqcbwcc1rrmqnkztrfjy5k7z4imsrata9est"
+            })
+        )
+    }
+
+    #[test]
+    fn delegators_count() {
+        let account = "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3"
+            .try_into()
+            .unwrap();
+        let json = super::delegators_count(&account);
+        assert!(
+            json == json!({
+                "action": "delegators_count",
+                "account": "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3"
+            })
+        )
+    }
+
     #[test]
     fn process() {
         let signature: [u8; 64] = hex::decode("A5DB164F6B81648F914E49CAB533900C389FAAD64FBB24F6902F9261312B29F730D07E9BCCD21D918301419B4E05B181637CF8419ED4DCBF8EF2539EB2467F07").unwrap().try_into().unwrap();
@@ -344,6 +762,97 @@ mod tests {
         )
     }
 
+    #[test]
+    fn receivable() {
+        let account = "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3"
+            .try_into()
+            .unwrap();
+        let json = super::receivable(&account, 10, 1000000000000000000000000, 20);
+        assert!(
+            json == json!({
+                "action": "receivable",
+                "account": "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3",
+                "count": "10",
+                "offset": "20",
+                "threshold": "1000000000000000000000000",
+                "sorting": true
+            })
+        )
+    }
+
+    #[test]
+    fn receivable_exists() {
+        let hash = hex::decode("87434F8041869A01C8F6F263B87972D7BA443A72E0A97D7A3FD0CCC2358FD6F9")
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let json = super::receivable_exists(hash);
+        assert!(
+            json == json!({
+                "action": "receivable_exists",
+                "hash": "87434F8041869A01C8F6F263B87972D7BA443A72E0A97D7A3FD0CCC2358FD6F9"
+            })
+        )
+    }
+
+    #[test]
+    fn representatives_online() {
+        assert!(
+            super::representatives_online()
+                == json!({
+                    "action": "representatives_online",
+                    "weight": true
+                })
+        )
+    }
+
+    #[test]
+    fn successors() {
+        let hash = hex::decode("87434F8041869A01C8F6F263B87972D7BA443A72E0A97D7A3FD0CCC2358FD6F9")
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let json = super::successors(hash, 5);
+        assert!(
+            json == json!({
+                "action": "successors",
+                "block": "87434F8041869A01C8F6F263B87972D7BA443A72E0A97D7A3FD0CCC2358FD6F9",
+                "count": "5"
+            })
+        )
+    }
+
+    #[test]
+    fn telemetry() {
+        assert!(super::telemetry() == json!({"action": "telemetry"}));
+        assert!(super::telemetry_raw() == json!({"action": "telemetry", "raw": "true"}))
+    }
+
+    #[test]
+    fn uptime() {
+        assert!(super::uptime() == json!({"action": "uptime"}))
+    }
+
+    #[test]
+    fn version() {
+        assert!(super::version() == json!({"action": "version"}))
+    }
+
+    #[test]
+    fn work_cancel() {
+        let hash = hex::decode("718CC2121C3E641059BC1C2CFC45666C99E8AE922F7A807B7D07B62C995D79E2")
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let json = super::work_cancel(hash);
+        assert!(
+            json == json!({
+                "action": "work_cancel",
+                "hash": "718CC2121C3E641059BC1C2CFC45666C99E8AE922F7A807B7D07B62C995D79E2"
+            })
+        )
+    }
+
     #[test]
     fn work_generate() {
         let hash = hex::decode("718CC2121C3E641059BC1C2CFC45666C99E8AE922F7A807B7D07B62C995D79E2")
@@ -373,4 +882,42 @@ mod tests {
             })
         )
     }
+
+    #[test]
+    fn work_validate() {
+        let hash = hex::decode("718CC2121C3E641059BC1C2CFC45666C99E8AE922F7A807B7D07B62C995D79E2")
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let json = super::work_validate(hash, [1; 8], None);
+        assert!(
+            json == json!({
+                "action": "work_validate",
+                "hash": "718CC2121C3E641059BC1C2CFC45666C99E8AE922F7A807B7D07B62C995D79E2",
+                "work": "0101010101010101"
+            })
+        );
+
+        let json = super::work_validate(hash, [1; 8], Some([255; 8]));
+        assert!(
+            json == json!({
+                "action": "work_validate",
+                "hash": "718CC2121C3E641059BC1C2CFC45666C99E8AE922F7A807B7D07B62C995D79E2",
+                "work": "0101010101010101",
+                "difficulty": "ffffffffffffffff"
+            })
+        )
+    }
+
+    #[test]
+    #[cfg(feature = "work-server")]
+    fn multiplier() {
+        use crate::constants::{RECEIVE_WORK_DIFFICULTY, SEND_WORK_DIFFICULTY};
+        assert!(
+            super::multiplier(SEND_WORK_DIFFICULTY, SEND_WORK_DIFFICULTY) == "1.000000000000000"
+        );
+        assert!(
+            super::multiplier(RECEIVE_WORK_DIFFICULTY, SEND_WORK_DIFFICULTY) == "0.015625000000000"
+        );
+    }
 }
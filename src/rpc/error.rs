@@ -4,6 +4,9 @@ use reqwest::Error as ReqwestError;
 use serde_json as json;
 use thiserror::Error;
 
+#[cfg(feature = "websocket")]
+use tokio_tungstenite::tungstenite::Error as WebSocketError;
+
 #[derive(Debug, Error)]
 pub enum RpcError {
     /// Could not create RPC: possibly invalid URL
@@ -32,4 +35,31 @@ pub enum RpcError {
     /// Cannot publish block of type `legacy`
     #[error("cannot publish block of type 'legacy'")]
     LegacyBlockType,
+    /// The account's blockchain changed while it was being iterated over
+    #[error("the account's blockchain changed while it was being iterated over")]
+    ChainChanged,
+    /// Timed out while waiting for the block to be confirmed
+    #[error("timed out while waiting for the block to be confirmed")]
+    Timeout,
+    /// Could not generate work
+    #[error("could not generate work")]
+    WorkUnavailable,
+    /// The account's balance is too low to send the requested amount
+    #[error("insufficient balance")]
+    InsufficientBalance,
+    /// Error in the WebSocket connection
+    #[cfg(feature = "websocket")]
+    #[error(transparent)]
+    WebSocketError(Box<WebSocketError>),
+    /// Error in the work server
+    #[cfg(feature = "work-server")]
+    #[error(transparent)]
+    ServerError(#[from] hyper::Error),
+}
+
+#[cfg(feature = "websocket")]
+impl From<WebSocketError> for RpcError {
+    fn from(value: WebSocketError) -> Self {
+        RpcError::WebSocketError(Box::new(value))
+    }
 }
@@ -0,0 +1,55 @@
+//! A minimal HTTP server which answers RPC requests, for testing
+
+use serde_json::Value as JsonValue;
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Start a mock node which answers each request with `handler(request)`, returning its URL
+pub(crate) async fn mock_rpc<F>(handler: F) -> String
+where
+    F: Fn(JsonValue) -> JsonValue + Send + Sync + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let handler = Arc::new(handler);
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            tokio::spawn(serve(stream, handler.clone()));
+        }
+    });
+    url
+}
+
+async fn serve<F>(stream: TcpStream, handler: Arc<F>)
+where
+    F: Fn(JsonValue) -> JsonValue,
+{
+    let mut stream = BufReader::new(stream);
+    loop {
+        let mut content_length = 0;
+        loop {
+            let mut line = String::new();
+            if stream.read_line(&mut line).await.unwrap_or(0) == 0 {
+                return;
+            }
+            let line = line.trim_end().to_lowercase();
+            if line.is_empty() {
+                break;
+            }
+            if let Some(length) = line.strip_prefix("content-length:") {
+                content_length = length.trim().parse().unwrap();
+            }
+        }
+
+        let mut body = vec![0; content_length];
+        stream.read_exact(&mut body).await.unwrap();
+        let response = handler(serde_json::from_slice(&body).unwrap()).to_string();
+        let response = format!(
+            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{}",
+            response.len(),
+            response
+        );
+        stream.write_all(response.as_bytes()).await.unwrap();
+    }
+}
@@ -1,11 +1,26 @@
+mod confirm;
 mod encode;
 mod error;
+mod paging;
 mod parse;
+mod stream;
+mod wallet;
+
+#[cfg(test)]
+mod mock;
 
 pub mod debug;
+pub mod representatives;
 pub mod util;
+pub mod work;
+
+#[cfg(feature = "blocking")]
+pub mod blocking;
 
-use crate::{Account, Block};
+#[cfg(feature = "websocket")]
+pub mod websocket;
+
+use crate::{Account, Block, Telemetry};
 use debug::DebugRpc;
 use json::{Map, Value as JsonValue};
 use serde_json as json;
@@ -14,7 +29,10 @@ use zeroize::{Zeroize, ZeroizeOnDrop};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
+pub use confirm::ConfirmationOptions;
 pub use error::RpcError;
+pub use paging::HistoryDirection;
+pub use wallet::{ReceiveOptions, ReceiveReport, Received, SendOptions};
 
 #[cfg(test)]
 #[cfg(feature = "serde")]
@@ -81,6 +99,76 @@ impl From<(Account, [u8; 32], u128)> for Receivable {
     }
 }
 
+/// The number of blocks in a node's ledger
+#[derive(Debug, Clone, Zeroize, ZeroizeOnDrop, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BlockCount {
+    /// Total number of blocks
+    pub count: usize,
+    /// Number of blocks which have not yet been checked
+    pub unchecked: usize,
+    /// Number of blocks which have been confirmed (cemented)
+    pub cemented: usize,
+}
+
+/// Info about the voting weight required to confirm blocks
+#[derive(Debug, Clone, Zeroize, ZeroizeOnDrop, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ConfirmationQuorum {
+    /// The voting weight required to confirm a block
+    pub quorum_delta: u128,
+    /// The percentage of online voting weight required to confirm a block
+    pub online_weight_quorum_percent: u8,
+    /// The minimum online voting weight assumed by the node
+    pub online_weight_minimum: u128,
+    /// The voting weight which is currently online
+    pub online_stake_total: u128,
+    /// The voting weight of the node's peers
+    pub peers_stake_total: u128,
+    /// The trended online voting weight
+    pub trended_stake_total: u128,
+}
+
+/// Telemetry metrics, averaged across the node's peers
+#[derive(Debug, Clone, Zeroize, ZeroizeOnDrop, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TelemetryInfo {
+    pub block_count: usize,
+    pub cemented_count: usize,
+    pub unchecked_count: usize,
+    pub account_count: usize,
+    pub bandwidth_cap: u64,
+    pub peer_count: usize,
+    pub protocol_version: u8,
+    /// Uptime, in seconds
+    pub uptime: u64,
+    pub genesis_block: [u8; 32],
+    pub major_version: u8,
+    pub minor_version: u8,
+    pub patch_version: u8,
+    pub pre_release_version: u8,
+    pub maker: u8,
+    /// Timestamp, in milliseconds
+    pub timestamp: u64,
+    pub active_difficulty: [u8; 8],
+}
+
+/// Version info of a node
+#[derive(Debug, Clone, Zeroize, ZeroizeOnDrop, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NodeVersion {
+    pub rpc_version: usize,
+    pub store_version: usize,
+    pub protocol_version: usize,
+    pub node_vendor: String,
+    pub store_vendor: String,
+    /// The name of the network, such as `live` or `beta`
+    pub network: String,
+    /// The hash of the network's genesis block
+    pub network_identifier: [u8; 32],
+    pub build_info: String,
+}
+
 /// See the official [Nano RPC documentation](https://docs.nano.org/commands/rpc-protocol/) for details.
 #[derive(Debug, Clone)]
 pub struct Rpc(DebugRpc);
@@ -117,6 +205,10 @@ impl Rpc {
         self.0.account_balance(account).await.result
     }
 
+    pub async fn account_block_count(&self, account: &Account) -> Result<usize, RpcError> {
+        self.0.account_block_count(account).await.result
+    }
+
     /// Lists the account's blocks, starting at `head` (or the newest block if `head` is `None`), and going back at most `count` number of blocks.
     /// Will stop at first legacy block.
     pub async fn account_history(
@@ -138,6 +230,11 @@ impl Rpc {
         self.0.account_info(account).await.result
     }
 
+    /// Returns the public key of the account
+    pub async fn account_key(&self, account: &Account) -> Result<[u8; 32], RpcError> {
+        self.0.account_key(account).await.result
+    }
+
     /// Indirect, relies on `account_history`.
     /// This allows the data to be verified to an extent.
     ///
@@ -149,6 +246,11 @@ impl Rpc {
         self.0.account_representative(account).await.result
     }
 
+    /// Returns the voting weight delegated to the account
+    pub async fn account_weight(&self, account: &Account) -> Result<u128, RpcError> {
+        self.0.account_weight(account).await.result
+    }
+
     pub async fn accounts_balances(&self, accounts: &[Account]) -> Result<Vec<u128>, RpcError> {
         self.0.accounts_balances(accounts).await.result
     }
@@ -183,6 +285,20 @@ impl Rpc {
         self.0.accounts_representatives(accounts).await.result
     }
 
+    /// Returns the account which the block belongs to
+    pub async fn block_account(&self, hash: [u8; 32]) -> Result<Account, RpcError> {
+        self.0.block_account(hash).await.result
+    }
+
+    /// Request confirmation for the block, if it is not already confirmed
+    pub async fn block_confirm(&self, hash: [u8; 32]) -> Result<(), RpcError> {
+        self.0.block_confirm(hash).await.result
+    }
+
+    pub async fn block_count(&self) -> Result<BlockCount, RpcError> {
+        self.0.block_count().await.result
+    }
+
     /// Legacy blocks, and blocks that don't exist, will return `None`
     pub async fn block_info(&self, hash: [u8; 32]) -> Result<Option<BlockInfo>, RpcError> {
         self.0.block_info(hash).await.result
@@ -196,11 +312,83 @@ impl Rpc {
         self.0.blocks_info(hashes).await.result
     }
 
+    /// Returns the hashes of `block` and its predecessors, going back at most `count` number of blocks
+    pub async fn chain(&self, block: [u8; 32], count: usize) -> Result<Vec<[u8; 32]>, RpcError> {
+        self.0.chain(block, count).await.result
+    }
+
+    pub async fn confirmation_quorum(&self) -> Result<ConfirmationQuorum, RpcError> {
+        self.0.confirmation_quorum().await.result
+    }
+
+    /// Returns the accounts which delegate to `account` with at least `threshold` balance, along with their balances.
+    /// If `start` is set, only delegators after it are returned.
+    pub async fn delegators(
+        &self,
+        account: &Account,
+        count: usize,
+        threshold: u128,
+        start: Option<&Account>,
+    ) -> Result<Vec<(Account, u128)>, RpcError> {
+        self.0
+            .delegators(account, count, threshold, start)
+            .await
+            .result
+    }
+
+    pub async fn delegators_count(&self, account: &Account) -> Result<usize, RpcError> {
+        self.0.delegators_count(account).await.result
+    }
+
     /// Returns the hash of the block
     pub async fn process(&self, block: &Block) -> Result<[u8; 32], RpcError> {
         self.0.process(block).await.result
     }
 
+    /// Returns whether or not the send block is still receivable
+    pub async fn receivable_exists(&self, hash: [u8; 32]) -> Result<bool, RpcError> {
+        self.0.receivable_exists(hash).await.result
+    }
+
+    /// Returns the online representatives, along with their voting weight
+    pub async fn representatives_online(&self) -> Result<Vec<(Account, u128)>, RpcError> {
+        self.0.representatives_online().await.result
+    }
+
+    /// Returns the hashes of `block` and its successors, going forward at most `count` number of blocks
+    pub async fn successors(
+        &self,
+        block: [u8; 32],
+        count: usize,
+    ) -> Result<Vec<[u8; 32]>, RpcError> {
+        self.0.successors(block, count).await.result
+    }
+
+    /// Returns telemetry metrics, averaged across the node's peers
+    pub async fn telemetry(&self) -> Result<TelemetryInfo, RpcError> {
+        self.0.telemetry().await.result
+    }
+
+    /// Returns the telemetry of each of the node's peers, as signed by the peers' node IDs.
+    /// Use `Telemetry::has_valid_signature` to reject forged telemetry.
+    pub async fn telemetry_raw(&self) -> Result<Vec<Telemetry>, RpcError> {
+        self.0.telemetry_raw().await.result
+    }
+
+    /// Returns the node's uptime, in seconds
+    pub async fn uptime(&self) -> Result<u64, RpcError> {
+        self.0.uptime().await.result
+    }
+
+    pub async fn version(&self) -> Result<NodeVersion, RpcError> {
+        self.0.version().await.result
+    }
+
+    /// Stop generating work for the given hash
+    pub async fn work_cancel(&self, work_hash: [u8; 32]) -> Result<(), RpcError> {
+        self.0.work_cancel(work_hash).await.result
+    }
+
     /// Returns the generated work, assuming no error is encountered
     pub async fn work_generate(
         &self,
@@ -212,6 +400,20 @@ impl Rpc {
             .await
             .result
     }
+
+    /// Check the work for the given hash.
+    /// If `custom_difficulty` is `None`, returns whether or not the work is valid for all blocks.
+    pub async fn work_validate(
+        &self,
+        work_hash: [u8; 32],
+        work: [u8; 8],
+        custom_difficulty: Option<[u8; 8]>,
+    ) -> Result<bool, RpcError> {
+        self.0
+            .work_validate(work_hash, work, custom_difficulty)
+            .await
+            .result
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,340 @@
+use super::{encode, parse, util::bytes_from_json, Receivable, RpcError};
+use crate::{Account, Block};
+use serde_json::Value as JsonValue;
+use std::collections::HashSet;
+
+/// The direction in which to walk an account's history
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryDirection {
+    /// Start at the frontier (newest) block, and walk back towards the `open` block
+    FromFrontier,
+    /// Start at the `open` block, and walk forward towards the frontier block
+    FromOpen,
+}
+
+/// Keeps track of where an `account_history` walk is, and verifies that each page links to the previous one.
+///
+/// Shared between the async and blocking RPC clients, which only need to send the requests.
+#[derive(Debug, Clone)]
+pub(crate) struct HistoryPager {
+    account: Account,
+    page_size: usize,
+    direction: HistoryDirection,
+    /// The `head` of the next request, if any
+    head: Option<[u8; 32]>,
+    /// The hash of the most recently returned block, if any
+    last_hash: Option<[u8; 32]>,
+    finished: bool,
+}
+impl HistoryPager {
+    pub(crate) fn new(account: &Account, page_size: usize, direction: HistoryDirection) -> Self {
+        HistoryPager {
+            account: account.clone(),
+            page_size: page_size.max(1),
+            direction,
+            head: None,
+            last_hash: None,
+            finished: false,
+        }
+    }
+
+    /// Returns `None` once the walk is finished
+    pub(crate) fn next_request(&self) -> Option<JsonValue> {
+        if self.finished {
+            return None;
+        }
+        Some(match self.direction {
+            HistoryDirection::FromFrontier => {
+                encode::account_history(&self.account, self.page_size, self.head, None)
+            }
+            HistoryDirection::FromOpen => {
+                encode::account_history_reverse(&self.account, self.page_size, self.head, None)
+            }
+        })
+    }
+
+    /// Handle the node's response to the last request, returning the blocks of this page
+    pub(crate) fn accept(
+        &mut self,
+        response: Result<JsonValue, RpcError>,
+    ) -> Result<Vec<Block>, RpcError> {
+        let result = self.try_accept(response);
+        if result.is_err() {
+            self.finished = true;
+        }
+        result
+    }
+
+    fn try_accept(
+        &mut self,
+        response: Result<JsonValue, RpcError>,
+    ) -> Result<Vec<Block>, RpcError> {
+        let raw_json = response?;
+        if self.head.is_some() && !raw_json["error"].is_null() {
+            // the block we are continuing from no longer exists
+            return Err(RpcError::ChainChanged);
+        }
+
+        let (continuation, page) = match self.direction {
+            HistoryDirection::FromFrontier => (
+                raw_json["previous"].clone(),
+                parse::account_history(raw_json, &self.account)?,
+            ),
+            HistoryDirection::FromOpen => (
+                raw_json["next"].clone(),
+                parse::account_history_reverse(raw_json, &self.account)?,
+            ),
+        };
+
+        if let Some(first) = page.first() {
+            let links = match self.direction {
+                HistoryDirection::FromFrontier => self.head.is_none_or(|head| first.hash() == head),
+                HistoryDirection::FromOpen => self
+                    .last_hash
+                    .is_none_or(|last_hash| first.previous == last_hash),
+            };
+            if !links {
+                return Err(RpcError::ChainChanged);
+            }
+        }
+
+        match self.direction {
+            // `account_history` stops at the first legacy block, so a short page means we're done
+            HistoryDirection::FromFrontier if page.len() < self.page_size => self.finished = true,
+            _ if continuation.is_null() => self.finished = true,
+            _ => {
+                let next: [u8; 32] = bytes_from_json(&continuation)?;
+                if let (HistoryDirection::FromFrontier, Some(last)) = (self.direction, page.last())
+                {
+                    if last.previous != next {
+                        return Err(RpcError::InvalidData);
+                    }
+                }
+                self.head = Some(next);
+            }
+        }
+        if let Some(last) = page.last() {
+            self.last_hash = Some(last.hash());
+        }
+        Ok(page)
+    }
+}
+
+/// Keeps track of where a `receivable` walk is, filtering out duplicates between pages.
+///
+/// Shared between the async and blocking RPC clients, which only need to send the requests.
+#[derive(Debug, Clone)]
+pub(crate) struct ReceivablePager {
+    account: Account,
+    page_size: usize,
+    threshold: u128,
+    offset: usize,
+    seen: HashSet<[u8; 32]>,
+    finished: bool,
+}
+impl ReceivablePager {
+    pub(crate) fn new(account: &Account, page_size: usize, threshold: u128) -> Self {
+        ReceivablePager {
+            account: account.clone(),
+            page_size: page_size.max(1),
+            threshold,
+            offset: 0,
+            seen: HashSet::new(),
+            finished: false,
+        }
+    }
+
+    /// Returns `None` once the walk is finished
+    pub(crate) fn next_request(&self) -> Option<JsonValue> {
+        if self.finished {
+            return None;
+        }
+        Some(encode::receivable(
+            &self.account,
+            self.page_size,
+            self.threshold,
+            self.offset,
+        ))
+    }
+
+    /// Handle the node's response to the last request, returning the new receivables of this page
+    pub(crate) fn accept(
+        &mut self,
+        response: Result<JsonValue, RpcError>,
+    ) -> Result<Vec<Receivable>, RpcError> {
+        let page = match response.and_then(|raw_json| parse::receivable(raw_json, &self.account)) {
+            Ok(page) => page,
+            Err(err) => {
+                self.finished = true;
+                return Err(err);
+            }
+        };
+
+        if page.len() < self.page_size {
+            self.finished = true;
+        }
+        self.offset += page.len();
+        Ok(page
+            .into_iter()
+            .filter(|receivable| self.seen.insert(receivable.block_hash))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::util::{block_to_json, to_uppercase_hex};
+    use crate::{constants::ONE_NANO, BlockType, Key, SecretBytes, Signature};
+    use serde_json::json;
+
+    fn create_chain(length: usize) -> Vec<Block> {
+        let key = Key::from_seed(&SecretBytes::from([7; 32]), 0);
+        let mut chain: Vec<Block> = vec![];
+        for i in 0..length {
+            let mut block = Block {
+                block_type: BlockType::Receive,
+                account: key.to_account(),
+                previous: chain.last().map_or([0; 32], |block| block.hash()),
+                representative: key.to_account(),
+                balance: ONE_NANO * (i as u128 + 1),
+                link: [i as u8; 32],
+                signature: Signature::default(),
+                work: [0; 8],
+            };
+            block.sign(&key);
+            chain.push(block)
+        }
+        chain
+    }
+
+    fn history_json(block: &Block) -> JsonValue {
+        let mut json = block_to_json(block);
+        json.insert("subtype".into(), block.block_type.to_string().into());
+        JsonValue::Object(json)
+    }
+
+    /// Answer an `account_history` request like the node would
+    fn simulate_node(chain: &[Block], request: &JsonValue) -> JsonValue {
+        let count: usize = request["count"].as_str().unwrap().parse().unwrap();
+        let head = request["head"].as_str().map(|head| {
+            let head: [u8; 32] = hex::decode(head).unwrap().try_into().unwrap();
+            chain.iter().position(|block| block.hash() == head)
+        });
+        let start = match head {
+            Some(None) => return json!({"error": "Block not found"}),
+            Some(Some(start)) => start,
+            None if request["reverse"] == true => 0,
+            None => chain.len() - 1,
+        };
+
+        if request["reverse"] == true {
+            let end = (start + count).min(chain.len());
+            let mut response = json!({
+                "history": chain[start..end].iter().map(history_json).collect::<Vec<_>>()
+            });
+            if end < chain.len() {
+                response["next"] = to_uppercase_hex(&chain[end].hash()).into();
+            }
+            response
+        } else {
+            let end = start.saturating_sub(count - 1);
+            let mut response = json!({
+                "history": chain[end..=start].iter().rev().map(history_json).collect::<Vec<_>>()
+            });
+            if end > 0 {
+                response["previous"] = to_uppercase_hex(&chain[end].previous).into();
+            }
+            response
+        }
+    }
+
+    fn walk(
+        chain: &[Block],
+        page_size: usize,
+        direction: HistoryDirection,
+    ) -> Result<Vec<Block>, RpcError> {
+        let mut pager = HistoryPager::new(&chain[0].account, page_size, direction);
+        let mut blocks = vec![];
+        while let Some(request) = pager.next_request() {
+            blocks.extend(pager.accept(Ok(simulate_node(chain, &request)))?);
+        }
+        Ok(blocks)
+    }
+
+    #[test]
+    fn history_from_frontier() {
+        let chain = create_chain(7);
+        let mut expected = chain.clone();
+        expected.reverse();
+        for page_size in [1, 2, 3, 7, 10] {
+            assert!(walk(&chain, page_size, HistoryDirection::FromFrontier).unwrap() == expected);
+        }
+    }
+
+    #[test]
+    fn history_from_open() {
+        let chain = create_chain(7);
+        for page_size in [1, 2, 3, 7, 10] {
+            assert!(walk(&chain, page_size, HistoryDirection::FromOpen).unwrap() == chain);
+        }
+    }
+
+    #[test]
+    fn history_chain_changed() {
+        let chain = create_chain(6);
+        let mut pager = HistoryPager::new(&chain[0].account, 3, HistoryDirection::FromOpen);
+
+        let request = pager.next_request().unwrap();
+        assert!(pager.accept(Ok(simulate_node(&chain, &request))).unwrap() == chain[..3]);
+
+        // the node now returns blocks which do not link to the ones already returned
+        let mut response = simulate_node(&chain, &pager.next_request().unwrap());
+        response["history"] = json!([history_json(&chain[1])]);
+        assert!(matches!(
+            pager.accept(Ok(response)),
+            Err(RpcError::ChainChanged)
+        ));
+        assert!(pager.next_request().is_none());
+
+        // the block to continue from was rolled back
+        let mut pager = HistoryPager::new(&chain[0].account, 2, HistoryDirection::FromFrontier);
+        let request = pager.next_request().unwrap();
+        pager.accept(Ok(simulate_node(&chain, &request))).unwrap();
+        let request = pager.next_request().unwrap();
+        assert!(matches!(
+            pager.accept(Ok(simulate_node(&chain[..2], &request))),
+            Err(RpcError::ChainChanged)
+        ));
+    }
+
+    #[test]
+    fn receivable_deduplicated() {
+        let account = create_chain(1)[0].account.clone();
+        let mut pager = ReceivablePager::new(&account, 2, 0);
+
+        let request = pager.next_request().unwrap();
+        assert!(request["offset"] == "0");
+        let page = pager
+            .accept(Ok(json!({"blocks": {
+                to_uppercase_hex(&[1; 32]): "10",
+                to_uppercase_hex(&[2; 32]): "20"
+            }})))
+            .unwrap();
+        assert!(page.len() == 2);
+
+        let request = pager.next_request().unwrap();
+        assert!(request["offset"] == "2");
+        let page = pager
+            .accept(Ok(json!({"blocks": {
+                to_uppercase_hex(&[2; 32]): "20",
+                to_uppercase_hex(&[3; 32]): "30"
+            }})))
+            .unwrap();
+        assert!(page.len() == 1);
+        assert!(page[0].block_hash == [3; 32]);
+
+        pager.accept(Ok(json!({"blocks": ""}))).unwrap();
+        assert!(pager.next_request().is_none());
+    }
+}
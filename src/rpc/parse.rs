@@ -1,5 +1,8 @@
-use super::{util::*, AccountInfo, BlockInfo, Receivable, RpcError};
-use crate::{block::check_work, Account, Block};
+use super::{
+    util::*, AccountInfo, BlockCount, BlockInfo, ConfirmationQuorum, NodeVersion, Receivable,
+    RpcError, TelemetryInfo,
+};
+use crate::{block::check_work, constants::SEND_WORK_DIFFICULTY, Account, Block, Telemetry};
 use hex::FromHexError;
 
 pub fn account_balance(raw_json: JsonValue) -> Result<u128, RpcError> {
@@ -7,15 +10,14 @@ pub fn account_balance(raw_json: JsonValue) -> Result<u128, RpcError> {
     Ok(balances)
 }
 
+pub fn account_block_count(raw_json: JsonValue) -> Result<usize, RpcError> {
+    usize_from_json(&raw_json["block_count"])
+}
+
 /// Will stop at first legacy block
 pub fn account_history(raw_json: JsonValue, account: &Account) -> Result<Vec<Block>, RpcError> {
-    let json_blocks = &raw_json["history"];
-    let json_blocks = json_blocks
-        .as_array()
-        .ok_or(RpcError::InvalidJsonDataType)?;
-
     let mut blocks: Vec<Block> = vec![];
-    for block in json_blocks {
+    for block in history_from_json(&raw_json)? {
         if trim_json(&block["type"].to_string()) != "state" {
             break;
         }
@@ -39,6 +41,52 @@ pub fn account_history(raw_json: JsonValue, account: &Account) -> Result<Vec<Blo
     Ok(blocks)
 }
 
+/// Same as `account_history`, but for `reverse` (oldest first) history.
+/// Will skip legacy blocks, which can only precede `state` blocks.
+pub fn account_history_reverse(
+    raw_json: JsonValue,
+    account: &Account,
+) -> Result<Vec<Block>, RpcError> {
+    let mut blocks: Vec<Block> = vec![];
+    for block in history_from_json(&raw_json)? {
+        if trim_json(&block["type"].to_string()) != "state" {
+            if !blocks.is_empty() {
+                return Err(RpcError::InvalidData);
+            }
+            continue;
+        }
+
+        let mut block = block_from_history_json(block)?;
+        // "account" field may be wrong due to a compatibility feature in the RPC protocol
+        block.account = account.clone();
+
+        if let Some(predecessor_block) = blocks.last() {
+            if block.previous != predecessor_block.hash() {
+                return Err(RpcError::InvalidData);
+            }
+        }
+
+        if !block.has_valid_signature() {
+            return Err(RpcError::InvalidData);
+        }
+
+        blocks.push(block)
+    }
+    Ok(blocks)
+}
+
+/// The node returns an empty string, rather than an empty array, if there is no history
+fn history_from_json(raw_json: &JsonValue) -> Result<&[JsonValue], RpcError> {
+    let json_blocks = &raw_json["history"];
+    if json_blocks.as_str() == Some("") {
+        return Ok(&[]);
+    }
+    json_blocks
+        .as_array()
+        .map(|json_blocks| json_blocks.as_slice())
+        .ok_or(RpcError::InvalidJsonDataType)
+}
+
 /// If an account is not yet opened, its frontier will be returned as `None`
 pub fn account_info(raw_json: JsonValue) -> Result<Option<AccountInfo>, RpcError> {
     if !raw_json["error"].is_null() {
@@ -58,10 +106,22 @@ pub fn account_info(raw_json: JsonValue) -> Result<Option<AccountInfo>, RpcError
     }))
 }
 
+pub fn account_key(raw_json: JsonValue, account: &Account) -> Result<[u8; 32], RpcError> {
+    let key: [u8; 32] = bytes_from_json(&raw_json["key"])?;
+    if key != account.compressed.to_bytes() {
+        return Err(RpcError::InvalidData);
+    }
+    Ok(key)
+}
+
 pub fn account_representative(history: Vec<Block>) -> Result<Option<Account>, RpcError> {
     Ok(history.first().map(|newest| newest.representative.clone()))
 }
 
+pub fn account_weight(raw_json: JsonValue) -> Result<u128, RpcError> {
+    u128_from_json(&raw_json["weight"])
+}
+
 pub fn accounts_balances(raw_json: JsonValue, accounts: &[Account]) -> Result<Vec<u128>, RpcError> {
     let mut balances = vec![];
     for account in accounts {
@@ -137,6 +197,29 @@ pub fn accounts_representatives(
     Ok(representatives)
 }
 
+pub fn block_account(raw_json: JsonValue) -> Result<Account, RpcError> {
+    account_from_json(&raw_json["account"])
+}
+
+pub fn block_confirm(raw_json: JsonValue) -> Result<(), RpcError> {
+    if !bool_from_json(&raw_json["started"])? {
+        return Err(RpcError::InvalidData);
+    }
+    Ok(())
+}
+
+pub fn block_count(raw_json: JsonValue) -> Result<BlockCount, RpcError> {
+    let block_count = BlockCount {
+        count: usize_from_json(&raw_json["count"])?,
+        unchecked: usize_from_json(&raw_json["unchecked"])?,
+        cemented: usize_from_json(&raw_json["cemented"])?,
+    };
+    if block_count.cemented > block_count.count {
+        return Err(RpcError::InvalidData);
+    }
+    Ok(block_count)
+}
+
 /// Legacy blocks, and blocks that don't exist, will return `None`
 pub fn block_info(raw_json: JsonValue) -> Result<Option<BlockInfo>, RpcError> {
     if !raw_json["error"].is_null() {
@@ -189,6 +272,43 @@ pub fn blocks_info(
     Ok(infos)
 }
 
+/// The first hash is always that of `block`, followed by its predecessors
+pub fn chain(
+    raw_json: JsonValue,
+    block: [u8; 32],
+    count: usize,
+) -> Result<Vec<[u8; 32]>, RpcError> {
+    hashes_sanity_check(hashes_from_json(&raw_json["blocks"])?, block, count)
+}
+
+pub fn confirmation_quorum(raw_json: JsonValue) -> Result<ConfirmationQuorum, RpcError> {
+    let quorum = ConfirmationQuorum {
+        quorum_delta: u128_from_json(&raw_json["quorum_delta"])?,
+        online_weight_quorum_percent: u8_from_json(&raw_json["online_weight_quorum_percent"])?,
+        online_weight_minimum: u128_from_json(&raw_json["online_weight_minimum"])?,
+        online_stake_total: u128_from_json(&raw_json["online_stake_total"])?,
+        peers_stake_total: u128_from_json(&raw_json["peers_stake_total"])?,
+        trended_stake_total: u128_from_json(&raw_json["trended_stake_total"])?,
+    };
+    if quorum.online_weight_quorum_percent > 100 {
+        return Err(RpcError::InvalidData);
+    }
+    Ok(quorum)
+}
+
+/// Returns each delegator, along with its balance
+pub fn delegators(raw_json: JsonValue, count: usize) -> Result<Vec<(Account, u128)>, RpcError> {
+    let delegators = weights_from_json(&raw_json["delegators"], u128_from_json)?;
+    if delegators.len() > count {
+        return Err(RpcError::InvalidData);
+    }
+    Ok(delegators)
+}
+
+pub fn delegators_count(raw_json: JsonValue) -> Result<usize, RpcError> {
+    usize_from_json(&raw_json["count"])
+}
+
 pub fn process(raw_json: JsonValue, hash: [u8; 32]) -> Result<[u8; 32], RpcError> {
     let rpc_hash: [u8; 32] = bytes_from_json(&raw_json["hash"])?;
 
@@ -198,6 +318,105 @@ pub fn process(raw_json: JsonValue, hash: [u8; 32]) -> Result<[u8; 32], RpcError
     Ok(hash)
 }
 
+pub fn receivable(raw_json: JsonValue, account: &Account) -> Result<Vec<Receivable>, RpcError> {
+    let json_blocks = &raw_json["blocks"];
+    // the node returns an empty string, rather than an empty map, if nothing is receivable
+    if json_blocks.as_str() == Some("") {
+        return Ok(vec![]);
+    }
+
+    let mut receivable = vec![];
+    for hash in map_keys_from_json(json_blocks)? {
+        let amount = u128_from_json(&json_blocks[hash])?;
+        let bytes = from_hex(hash)?
+            .try_into()
+            .map_err(|_| FromHexError::InvalidStringLength)?;
+
+        receivable.push((account.clone(), bytes, amount).into());
+    }
+    Ok(receivable)
+}
+
+pub fn receivable_exists(raw_json: JsonValue) -> Result<bool, RpcError> {
+    bool_from_json(&raw_json["exists"])
+}
+
+/// Returns each online representative, along with its voting weight
+pub fn representatives_online(raw_json: JsonValue) -> Result<Vec<(Account, u128)>, RpcError> {
+    weights_from_json(&raw_json["representatives"], |representative| {
+        u128_from_json(&representative["weight"])
+    })
+}
+
+/// The first hash is always that of `block`, followed by its successors
+pub fn successors(
+    raw_json: JsonValue,
+    block: [u8; 32],
+    count: usize,
+) -> Result<Vec<[u8; 32]>, RpcError> {
+    hashes_sanity_check(hashes_from_json(&raw_json["blocks"])?, block, count)
+}
+
+pub fn telemetry(raw_json: JsonValue) -> Result<TelemetryInfo, RpcError> {
+    let telemetry = TelemetryInfo {
+        block_count: usize_from_json(&raw_json["block_count"])?,
+        cemented_count: usize_from_json(&raw_json["cemented_count"])?,
+        unchecked_count: usize_from_json(&raw_json["unchecked_count"])?,
+        account_count: usize_from_json(&raw_json["account_count"])?,
+        bandwidth_cap: u64_from_json(&raw_json["bandwidth_cap"])?,
+        peer_count: usize_from_json(&raw_json["peer_count"])?,
+        protocol_version: u8_from_json(&raw_json["protocol_version"])?,
+        uptime: u64_from_json(&raw_json["uptime"])?,
+        genesis_block: bytes_from_json(&raw_json["genesis_block"])?,
+        major_version: u8_from_json(&raw_json["major_version"])?,
+        minor_version: u8_from_json(&raw_json["minor_version"])?,
+        patch_version: u8_from_json(&raw_json["patch_version"])?,
+        pre_release_version: u8_from_json(&raw_json["pre_release_version"])?,
+        maker: u8_from_json(&raw_json["maker"])?,
+        timestamp: u64_from_json(&raw_json["timestamp"])?,
+        active_difficulty: bytes_from_json(&raw_json["active_difficulty"])?,
+    };
+    if telemetry.cemented_count > telemetry.block_count {
+        return Err(RpcError::InvalidData);
+    }
+    Ok(telemetry)
+}
+
+/// Telemetry of each peer, with a signature from its node ID
+pub fn telemetry_raw(raw_json: JsonValue) -> Result<Vec<Telemetry>, RpcError> {
+    raw_json["metrics"]
+        .as_array()
+        .ok_or(RpcError::InvalidJsonDataType)?
+        .iter()
+        .map(telemetry_from_json)
+        .collect()
+}
+
+/// Uptime of the node, in seconds
+pub fn uptime(raw_json: JsonValue) -> Result<u64, RpcError> {
+    u64_from_json(&raw_json["seconds"])
+}
+
+pub fn version(raw_json: JsonValue) -> Result<NodeVersion, RpcError> {
+    Ok(NodeVersion {
+        rpc_version: usize_from_json(&raw_json["rpc_version"])?,
+        store_version: usize_from_json(&raw_json["store_version"])?,
+        protocol_version: usize_from_json(&raw_json["protocol_version"])?,
+        node_vendor: string_from_json(&raw_json["node_vendor"])?,
+        store_vendor: string_from_json(&raw_json["store_vendor"])?,
+        network: string_from_json(&raw_json["network"])?,
+        network_identifier: bytes_from_json(&raw_json["network_identifier"])?,
+        build_info: string_from_json(&raw_json["build_info"])?,
+    })
+}
+
+pub fn work_cancel(raw_json: JsonValue) -> Result<(), RpcError> {
+    if raw_json["success"].is_null() {
+        return Err(RpcError::InvalidData);
+    }
+    Ok(())
+}
+
 pub fn work_generate(
     raw_json: JsonValue,
     work_hash: [u8; 32],
@@ -217,10 +436,128 @@ pub fn work_generate(
     }
 }
 
+/// If `custom_difficulty` is `None`, returns whether or not the work is valid for all blocks
+pub fn work_validate(
+    raw_json: JsonValue,
+    work_hash: [u8; 32],
+    work: [u8; 8],
+    custom_difficulty: Option<[u8; 8]>,
+) -> Result<bool, RpcError> {
+    let (valid, difficulty) = match custom_difficulty {
+        Some(difficulty) => (bool_from_json(&raw_json["valid"])?, difficulty),
+        None => (
+            bool_from_json(&raw_json["valid_all"])?,
+            SEND_WORK_DIFFICULTY,
+        ),
+    };
+
+    if check_work(work_hash, difficulty, work) != valid {
+        return Err(RpcError::InvalidData);
+    }
+    Ok(valid)
+}
+
+/// A request to a work server, as made by `encode::work_generate`, `encode::work_cancel` or `encode::work_validate`
+#[cfg(feature = "work-server")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorkRequest {
+    Generate {
+        work_hash: [u8; 32],
+        custom_difficulty: Option<[u8; 8]>,
+    },
+    Cancel {
+        work_hash: [u8; 32],
+    },
+    Validate {
+        work_hash: [u8; 32],
+        work: [u8; 8],
+        custom_difficulty: Option<[u8; 8]>,
+    },
+}
+
+/// Parse a request to a work server, returning the node's error message if it is invalid
+#[cfg(feature = "work-server")]
+pub fn work_request(raw_json: &JsonValue) -> Result<WorkRequest, &'static str> {
+    let action = raw_json["action"].as_str();
+    if !matches!(
+        action,
+        Some("work_generate" | "work_cancel" | "work_validate")
+    ) {
+        return Err("Unknown command");
+    }
+    let work_hash = bytes_from_json(&raw_json["hash"]).map_err(|_| "Bad block hash")?;
+    let custom_difficulty = match &raw_json["difficulty"] {
+        JsonValue::Null => None,
+        difficulty => Some(bytes_from_json(difficulty).map_err(|_| "Bad difficulty")?),
+    };
+
+    Ok(match action {
+        Some("work_generate") => WorkRequest::Generate {
+            work_hash,
+            custom_difficulty,
+        },
+        Some("work_cancel") => WorkRequest::Cancel { work_hash },
+        _ => WorkRequest::Validate {
+            work_hash,
+            work: bytes_from_json(&raw_json["work"]).map_err(|_| "Bad work")?,
+            custom_difficulty,
+        },
+    })
+}
+
+/// Specific to `chain` and `successors`
+fn hashes_from_json(value: &JsonValue) -> Result<Vec<[u8; 32]>, RpcError> {
+    // the node returns an empty string, rather than an empty array, if there are no blocks
+    if value.as_str() == Some("") {
+        return Ok(vec![]);
+    }
+    value
+        .as_array()
+        .ok_or(RpcError::InvalidJsonDataType)?
+        .iter()
+        .map(bytes_from_json)
+        .collect()
+}
+
+/// Specific to `chain` and `successors`
+fn hashes_sanity_check(
+    hashes: Vec<[u8; 32]>,
+    block: [u8; 32],
+    count: usize,
+) -> Result<Vec<[u8; 32]>, RpcError> {
+    if hashes.len() > count || hashes.first().is_some_and(|first| first != &block) {
+        return Err(RpcError::InvalidData);
+    }
+    Ok(hashes)
+}
+
+/// Specific to `delegators` and `representatives_online`
+fn weights_from_json(
+    value: &JsonValue,
+    weight_from_json: impl Fn(&JsonValue) -> Result<u128, RpcError>,
+) -> Result<Vec<(Account, u128)>, RpcError> {
+    // the node returns an empty string, rather than an empty map, if there are no accounts
+    if value.as_str() == Some("") {
+        return Ok(vec![]);
+    }
+
+    let mut weights = vec![];
+    let mut total: u128 = 0;
+    for account in map_keys_from_json(value)? {
+        let weight = weight_from_json(&value[account])?;
+        total = total.checked_add(weight).ok_or(RpcError::InvalidData)?;
+
+        let account = Account::try_from(account).map_err(|_| RpcError::InvalidAccount)?;
+        weights.push((account, weight));
+    }
+    Ok(weights)
+}
+
 #[cfg(test)]
 mod tests {
     use super::to_uppercase_hex;
-    use crate::{block::check_work, Account, Block, BlockType};
+    use crate::telemetry::{encode_node_id, tests::signed_telemetry};
+    use crate::{block::check_work, Account, Block, BlockType, Key, SecretBytes};
     use serde_json::json;
 
     #[test]
@@ -234,6 +571,12 @@ mod tests {
         assert!(balance == 10000)
     }
 
+    #[test]
+    fn account_block_count() {
+        let count = super::account_block_count(json!({"block_count": "19"})).unwrap();
+        assert!(count == 19)
+    }
+
     #[test]
     fn account_history() {
         let history = super::account_history(
@@ -340,6 +683,77 @@ mod tests {
         )
     }
 
+    #[test]
+    fn account_history_reverse() {
+        let account =
+            Account::try_from("nano_1ipx847tk8o46pwxt5qjdbncjqcbwcc1rrmqnkztrfjy5k7z4imsrata9est")
+                .unwrap();
+        let history = super::account_history_reverse(
+            json!({
+                "account":"nano_1ipx847tk8o46pwxt5qjdbncjqcbwcc1rrmqnkztrfjy5k7z4imsrata9est",
+                "history":[
+                    {
+                        "type":"send",
+                        "account":"nano_1ipx847tk8o46pwxt5qjdbncjqcbwcc1rrmqnkztrfjy5k7z4imsrata9est",
+                        "previous":"0000000000000000000000000000000000000000000000000000000000000000",
+                        "destination":"nano_1ipx847tk8o46pwxt5qjdbncjqcbwcc1rrmqnkztrfjy5k7z4imsrata9est",
+                        "balance":"00000000000000000000000000000000",
+                        "work":"0000000000000000",
+                        "signature":"00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000"
+                    },
+                    {
+                        "type":"state",
+                        "representative":"nano_1stofnrxuz3cai7ze75o174bpm7scwj9jn3nxsn8ntzg784jf1gzn1jjdkou",
+                        "link":"C71CCE9A2BDD1DB6424B789885A8FBDA298E1BB009165B17209771182B0509C7",
+                        "balance":"116024995745747584010554620134",
+                        "previous":"EC9A8131D76E820818AD84554F3AE276542A642DB118C1B098C77A0A8A8446B5",
+                        "subtype":"send",
+                        "account":"nano_3jrwstf4qqaxps36py6ripnhqpjbjrfu14apdedk37uj51oic4g94qcabf1i",
+                        "amount":"22066000000000000000000000000000000",
+                        "local_timestamp":"1575915652",
+                        "height":"280",
+                        "hash":"F8F83276ACCBFCCD13783309861EEE81E5FAF97BD28F84ED1DA62C7D4460E531",
+                        "confirmed":"true",
+                        "work":"b1bd2f559a745b5a",
+                        "signature":"5CB5A90D35301213B45706D1D5318D8E0B27DAA58782892411CB07F4E878E447F6B70AA7612B637FE7302D84750B621747303707ECE38C5F1F719D5446670207"
+                    },
+                    {
+                        "type":"state",
+                        "representative":"nano_1stofnrxuz3cai7ze75o174bpm7scwj9jn3nxsn8ntzg784jf1gzn1jjdkou",
+                        "link":"65706F636820763220626C6F636B000000000000000000000000000000000000",
+                        "balance":"116024995745747584010554620134",
+                        "previous":"F8F83276ACCBFCCD13783309861EEE81E5FAF97BD28F84ED1DA62C7D4460E531",
+                        "subtype":"epoch",
+                        "account":"nano_3qb6o6i1tkzr6jwr5s7eehfxwg9x6eemitdinbpi7u8bjjwsgqfj4wzser3x",
+                        "local_timestamp":"1598397125",
+                        "height":"281",
+                        "hash":"BFD5D5214A93E614D64A7C05624F69E6CFD4F1CED3C5926562F282DF135B15CF",
+                        "confirmed":"true",
+                        "work":"894045458d590e7c",
+                        "signature":"3D45D616545D5CCE9766E3F6268C9AE88C0DCA61A6B034AE4804D46C9F75EA94BCA7E7AEBA46EA98117120FB491FE2F7D0664675EF36D8BFD9818DAE62209F06"
+                    }
+                ]
+            }),
+            &account
+        ).unwrap();
+
+        assert!(history.len() == 2);
+        assert!(history[0].block_type == BlockType::Send);
+        assert!(history[1].block_type == BlockType::Epoch);
+        assert!(history[1].previous == history[0].hash());
+        assert!(history.iter().all(|block| block.account == account));
+
+        assert!(super::account_history_reverse(
+            json!({
+                "account":"nano_1ipx847tk8o46pwxt5qjdbncjqcbwcc1rrmqnkztrfjy5k7z4imsrata9est",
+                "history":""
+            }),
+            &account
+        )
+        .unwrap()
+        .is_empty());
+    }
+
     #[test]
     fn account_info() {
         let info = super::account_info(json!({
@@ -384,6 +798,25 @@ mod tests {
         .is_none());
     }
 
+    #[test]
+    fn account_key() {
+        let account =
+            Account::try_from("nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3")
+                .unwrap();
+        let key = super::account_key(
+            json!({"key": "E89208DD038FBB269987689621D52292AE9C35941A7484756ECCED92A65093BA"}),
+            &account,
+        )
+        .unwrap();
+        assert!(key == account.compressed.to_bytes());
+
+        assert!(super::account_key(
+            json!({"key": "87434F8041869A01C8F6F263B87972D7BA443A72E0A97D7A3FD0CCC2358FD6F9"}),
+            &account
+        )
+        .is_err())
+    }
+
     #[test]
     fn account_representative() {
         let signature: [u8; 64] = hex::decode("3D45D616545D5CCE9766E3F6268C9AE88C0DCA61A6B034AE4804D46C9F75EA94BCA7E7AEBA46EA98117120FB491FE2F7D0664675EF36D8BFD9818DAE62209F06").unwrap().try_into().unwrap();
@@ -419,6 +852,12 @@ mod tests {
         )
     }
 
+    #[test]
+    fn account_weight() {
+        let weight = super::account_weight(json!({"weight": "10000"})).unwrap();
+        assert!(weight == 10000)
+    }
+
     #[test]
     fn accounts_balances() {
         let balances = super::accounts_balances(
@@ -586,6 +1025,48 @@ mod tests {
         assert!(representatives[2].is_none())
     }
 
+    #[test]
+    fn block_account() {
+        let account = super::block_account(
+            json!({"account": "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3"}),
+        )
+        .unwrap();
+        assert!(
+            account
+                == Account::try_from(
+                    "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3"
+                )
+                .unwrap()
+        )
+    }
+
+    #[test]
+    fn block_confirm() {
+        assert!(super::block_confirm(json!({"started": "1"})).is_ok());
+        assert!(super::block_confirm(json!({"started": "0"})).is_err());
+        assert!(super::block_confirm(json!({})).is_err());
+    }
+
+    #[test]
+    fn block_count() {
+        let count = super::block_count(json!({
+            "count": "1000",
+            "unchecked": "10",
+            "cemented": "25"
+        }))
+        .unwrap();
+        assert!(count.count == 1000);
+        assert!(count.unchecked == 10);
+        assert!(count.cemented == 25);
+
+        assert!(super::block_count(json!({
+            "count": "1000",
+            "unchecked": "10",
+            "cemented": "1001"
+        }))
+        .is_err())
+    }
+
     #[test]
     fn block_info() {
         // block found
@@ -718,6 +1199,97 @@ mod tests {
         assert!(infos[1].is_none());
     }
 
+    #[test]
+    fn chain() {
+        let hash: [u8; 32] =
+            hex::decode("87434F8041869A01C8F6F263B87972D7BA443A72E0A97D7A3FD0CCC2358FD6F9")
+                .unwrap()
+                .try_into()
+                .unwrap();
+        let json = json!({
+            "blocks": [
+                "87434F8041869A01C8F6F263B87972D7BA443A72E0A97D7A3FD0CCC2358FD6F9",
+                "000D1BAEC8EC208142C99059B393051BAC8380F9B5A2E6B2489A277D81789F3F"
+            ]
+        });
+
+        let chain = super::chain(json.clone(), hash, 2).unwrap();
+        assert!(chain.len() == 2);
+        assert!(chain[0] == hash);
+        assert!(
+            to_uppercase_hex(&chain[1])
+                == "000D1BAEC8EC208142C99059B393051BAC8380F9B5A2E6B2489A277D81789F3F"
+        );
+
+        assert!(super::chain(json.clone(), hash, 1).is_err());
+        assert!(super::chain(json, [0; 32], 2).is_err());
+        assert!(super::chain(json!({"blocks": ""}), hash, 2)
+            .unwrap()
+            .is_empty())
+    }
+
+    #[test]
+    fn confirmation_quorum() {
+        let quorum = super::confirmation_quorum(json!({
+            "quorum_delta": "41469707173777717318245825935516662250",
+            "online_weight_quorum_percent": "50",
+            "online_weight_minimum": "60000000000000000000000000000000000000",
+            "online_stake_total": "82939414347555434636491651871033324568",
+            "trended_stake_total": "81939414347555434636491651871033324568",
+            "peers_stake_total": "69026910610720098597176027400951402360"
+        }))
+        .unwrap();
+        assert!(quorum.quorum_delta == 41469707173777717318245825935516662250);
+        assert!(quorum.online_weight_quorum_percent == 50);
+        assert!(quorum.online_weight_minimum == 60000000000000000000000000000000000000);
+        assert!(quorum.online_stake_total == 82939414347555434636491651871033324568);
+        assert!(quorum.trended_stake_total == 81939414347555434636491651871033324568);
+        assert!(quorum.peers_stake_total == 69026910610720098597176027400951402360);
+    }
+
+    #[test]
+    fn delegators() {
+        let json = json!({
+            "delegators": {
+                "nano_1ipx847tk8o46pwxt5qjdbncjqcbwcc1rrmqnkztrfjy5k7z4imsrata9est": "500000000000000000000000000000000000",
+                "nano_3i1aq1cchnmbn9x5rsbap8b15akfh7wj7pwskuzi7ahz8oq6cobd99d4r3b7": "961647970820730000000000000000000000"
+            }
+        });
+        let delegators = super::delegators(json.clone(), 10).unwrap();
+        assert!(delegators.len() == 2);
+        assert!(delegators.contains(&(
+            Account::try_from("nano_1ipx847tk8o46pwxt5qjdbncjqcbwcc1rrmqnkztrfjy5k7z4imsrata9est")
+                .unwrap(),
+            500000000000000000000000000000000000
+        )));
+        assert!(delegators.contains(&(
+            Account::try_from("nano_3i1aq1cchnmbn9x5rsbap8b15akfh7wj7pwskuzi7ahz8oq6cobd99d4r3b7")
+                .unwrap(),
+            961647970820730000000000000000000000
+        )));
+
+        assert!(super::delegators(json, 1).is_err());
+        assert!(super::delegators(json!({"delegators": ""}), 10)
+            .unwrap()
+            .is_empty());
+        assert!(super::delegators(
+            json!({
+                "delegators": {
+                    "nano_1ipx847tk8o46pwxt5qjdbncjqcbwcc1rrmqnkztrfjy5k7z4imsrata9est": "340282366920938463463374607431768211455",
+                    "nano_3i1aq1cchnmbn9x5rsbap8b15akfh7wj7pwskuzi7ahz8oq6cobd99d4r3b7": "1"
+                }
+            }),
+            10
+        )
+        .is_err())
+    }
+
+    #[test]
+    fn delegators_count() {
+        let count = super::delegators_count(json!({"count": "2"})).unwrap();
+        assert!(count == 2)
+    }
+
     #[test]
     fn process() {
         let block_hash: [u8; 32] =
@@ -738,6 +1310,200 @@ mod tests {
         assert!(hash == block_hash)
     }
 
+    #[test]
+    fn receivable() {
+        let account: Account = "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3"
+            .parse()
+            .unwrap();
+        let receivable = super::receivable(
+            json!({
+                "blocks":{
+                    "000D1BAEC8EC208142C99059B393051BAC8380F9B5A2E6B2489A277D81789F3F": "6000000000000000000000000000000",
+                    "87434F8041869A01C8F6F263B87972D7BA443A72E0A97D7A3FD0CCC2358FD6F9": "1000000000000000000000000000000"
+                }
+            }),
+            &account,
+        )
+        .unwrap();
+        assert!(receivable.len() == 2);
+        assert!(receivable
+            .iter()
+            .all(|receivable| receivable.recipient == account));
+        assert!(
+            to_uppercase_hex(&receivable[0].block_hash)
+                == "000D1BAEC8EC208142C99059B393051BAC8380F9B5A2E6B2489A277D81789F3F"
+        );
+        assert!(receivable[0].amount == 6000000000000000000000000000000);
+        assert!(receivable[1].amount == 1000000000000000000000000000000);
+
+        assert!(super::receivable(json!({"blocks": ""}), &account)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn receivable_exists() {
+        assert!(super::receivable_exists(json!({"exists": "1"})).unwrap());
+        assert!(!super::receivable_exists(json!({"exists": "0"})).unwrap());
+    }
+
+    #[test]
+    fn representatives_online() {
+        let representatives = super::representatives_online(json!({
+            "representatives": {
+                "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3": {
+                    "weight": "150462654614686936429917024683496890"
+                },
+                "nano_3i1aq1cchnmbn9x5rsbap8b15akfh7wj7pwskuzi7ahz8oq6cobd99d4r3b7": {
+                    "weight": "10000"
+                }
+            }
+        }))
+        .unwrap();
+        assert!(representatives.len() == 2);
+        assert!(representatives.contains(&(
+            Account::try_from("nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3")
+                .unwrap(),
+            150462654614686936429917024683496890
+        )));
+        assert!(representatives.contains(&(
+            Account::try_from("nano_3i1aq1cchnmbn9x5rsbap8b15akfh7wj7pwskuzi7ahz8oq6cobd99d4r3b7")
+                .unwrap(),
+            10000
+        )));
+    }
+
+    #[test]
+    fn successors() {
+        let hash: [u8; 32] =
+            hex::decode("87434F8041869A01C8F6F263B87972D7BA443A72E0A97D7A3FD0CCC2358FD6F9")
+                .unwrap()
+                .try_into()
+                .unwrap();
+        let successors = super::successors(
+            json!({
+                "blocks": [
+                    "87434F8041869A01C8F6F263B87972D7BA443A72E0A97D7A3FD0CCC2358FD6F9",
+                    "000D1BAEC8EC208142C99059B393051BAC8380F9B5A2E6B2489A277D81789F3F"
+                ]
+            }),
+            hash,
+            5,
+        )
+        .unwrap();
+        assert!(successors.len() == 2);
+        assert!(successors[0] == hash);
+    }
+
+    #[test]
+    fn telemetry() {
+        let telemetry = super::telemetry(json!({
+            "block_count": "5777903",
+            "cemented_count": "688819",
+            "unchecked_count": "443468",
+            "account_count": "620750",
+            "bandwidth_cap": "1572864",
+            "peer_count": "32",
+            "protocol_version": "18",
+            "uptime": "556896",
+            "genesis_block": "F824C697633FAB78B703D75189B7A7E18DA438A2ED5FFE7495F02F681CD56D41",
+            "major_version": "21",
+            "minor_version": "0",
+            "patch_version": "0",
+            "pre_release_version": "0",
+            "maker": "0",
+            "timestamp": "1587055945990",
+            "active_difficulty": "ffffffcdbf40aa45"
+        }))
+        .unwrap();
+        assert!(telemetry.block_count == 5777903);
+        assert!(telemetry.cemented_count == 688819);
+        assert!(telemetry.unchecked_count == 443468);
+        assert!(telemetry.account_count == 620750);
+        assert!(telemetry.bandwidth_cap == 1572864);
+        assert!(telemetry.peer_count == 32);
+        assert!(telemetry.protocol_version == 18);
+        assert!(telemetry.uptime == 556896);
+        assert!(
+            to_uppercase_hex(&telemetry.genesis_block)
+                == "F824C697633FAB78B703D75189B7A7E18DA438A2ED5FFE7495F02F681CD56D41"
+        );
+        assert!(telemetry.major_version == 21);
+        assert!(telemetry.timestamp == 1587055945990);
+        assert!(telemetry.active_difficulty == 0xffffffcdbf40aa45_u64.to_be_bytes());
+    }
+
+    #[test]
+    fn telemetry_raw() {
+        let key = Key::from_seed(&SecretBytes::from([5; 32]), 0);
+        let telemetry = signed_telemetry(&key);
+        let mut metrics = json!({
+            "block_count": "1",
+            "cemented_count": "1",
+            "unchecked_count": "3",
+            "account_count": "4",
+            "bandwidth_cap": "5",
+            "peer_count": "6",
+            "protocol_version": "21",
+            "uptime": "7",
+            "genesis_block": to_uppercase_hex(&[8; 32]),
+            "major_version": "27",
+            "minor_version": "1",
+            "patch_version": "0",
+            "pre_release_version": "0",
+            "maker": "0",
+            "timestamp": "9",
+            "active_difficulty": "0a0a0a0a0a0a0a0a",
+            "node_id": encode_node_id(&key.to_account()),
+            "signature": to_uppercase_hex(&telemetry.signature.to_bytes()),
+            "address": "::ffff:127.0.0.1",
+            "port": "7075"
+        });
+        let parsed = super::telemetry_raw(json!({"metrics": [metrics.clone()]})).unwrap();
+        assert!(parsed == vec![telemetry]);
+        assert!(parsed[0].has_valid_signature());
+
+        metrics["node_id"] = key.to_account().to_string().into();
+        assert!(super::telemetry_raw(json!({"metrics": [metrics]})).is_err());
+    }
+
+    #[test]
+    fn uptime() {
+        let uptime = super::uptime(json!({"seconds": "6000"})).unwrap();
+        assert!(uptime == 6000)
+    }
+
+    #[test]
+    fn version() {
+        let version = super::version(json!({
+            "rpc_version": "1",
+            "store_version": "14",
+            "protocol_version": "17",
+            "node_vendor": "Nano V21.0",
+            "store_vendor": "LMDB 0.9.23",
+            "network": "live",
+            "network_identifier": "991CF190094C00F0B68E2E5F75F6BEE95A2E0BD93CEAA4A6734DB9F19B728948",
+            "build_info": "Build Info <git hash> \"<compiler> version \" \"<compiler version string>\" \"BOOST <boost version>\" BUILT \"<date built>\""
+        }))
+        .unwrap();
+        assert!(version.rpc_version == 1);
+        assert!(version.store_version == 14);
+        assert!(version.protocol_version == 17);
+        assert!(version.node_vendor == "Nano V21.0");
+        assert!(version.store_vendor == "LMDB 0.9.23");
+        assert!(version.network == "live");
+        assert!(
+            to_uppercase_hex(&version.network_identifier)
+                == "991CF190094C00F0B68E2E5F75F6BEE95A2E0BD93CEAA4A6734DB9F19B728948"
+        );
+    }
+
+    #[test]
+    fn work_cancel() {
+        assert!(super::work_cancel(json!({"success": ""})).is_ok());
+        assert!(super::work_cancel(json!({"error": "Unable to parse JSON"})).is_err());
+    }
+
     #[test]
     fn work_generate() {
         // valid
@@ -781,4 +1547,114 @@ mod tests {
         )
         .unwrap_err();
     }
+
+    #[test]
+    fn work_validate() {
+        let work_hash: [u8; 32] = [
+            51, 190, 253, 128, 226, 21, 179, 253, 60, 46, 69, 62, 113, 112, 141, 197, 34, 189, 51,
+            236, 38, 152, 45, 3, 139, 137, 116, 69, 182, 168, 248, 216,
+        ];
+        let work: [u8; 8] = [13, 162, 2, 90, 186, 82, 152, 241];
+
+        assert!(super::work_validate(
+            json!({
+                "valid_all": "1",
+                "valid_receive": "1",
+                "difficulty": "fffffff800000000",
+                "multiplier": "1"
+            }),
+            work_hash,
+            work,
+            None
+        )
+        .unwrap());
+        // the node claims invalid work is valid
+        assert!(super::work_validate(
+            json!({
+                "valid_all": "1",
+                "valid_receive": "1",
+                "difficulty": "ffffffffffffffff",
+                "multiplier": "1"
+            }),
+            work_hash,
+            [0; 8],
+            None
+        )
+        .is_err());
+        // the node claims valid work is invalid
+        assert!(super::work_validate(
+            json!({
+                "valid_all": "0",
+                "valid_receive": "0",
+                "difficulty": "0000000000000000",
+                "multiplier": "0"
+            }),
+            work_hash,
+            work,
+            None
+        )
+        .is_err());
+
+        let difficulty = Some(0xfffffff800000000_u64.to_be_bytes());
+        assert!(super::work_validate(json!({"valid": "1"}), work_hash, work, difficulty).unwrap());
+        assert!(super::work_validate(json!({"valid": "0"}), work_hash, work, difficulty).is_err());
+        assert!(
+            !super::work_validate(json!({"valid": "0"}), work_hash, work, Some([255; 8])).unwrap()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "work-server")]
+    fn work_server_roundtrip() {
+        use super::super::encode;
+        use super::WorkRequest;
+        use crate::{block::get_work_difficulty, Network};
+
+        let hash = [3; 32];
+        let easy = [0xf0, 0, 0, 0, 0, 0, 0, 0];
+        let work = (0..u64::MAX)
+            .map(u64::to_be_bytes)
+            .find(|work| check_work(hash, easy, *work))
+            .unwrap();
+        let harder = (u64::from_be_bytes(get_work_difficulty(hash, work)) + 1).to_be_bytes();
+
+        // requests, as encoded by `Rpc`
+        let request = super::work_request(&encode::work_generate(hash, Some(easy)));
+        assert!(
+            request
+                == Ok(WorkRequest::Generate {
+                    work_hash: hash,
+                    custom_difficulty: Some(easy)
+                })
+        );
+        let request = super::work_request(&encode::work_cancel(hash));
+        assert!(request == Ok(WorkRequest::Cancel { work_hash: hash }));
+        let request = super::work_request(&encode::work_validate(hash, work, None));
+        assert!(
+            request
+                == Ok(WorkRequest::Validate {
+                    work_hash: hash,
+                    work,
+                    custom_difficulty: None
+                })
+        );
+        let mut bad_hash = encode::work_cancel(hash);
+        bad_hash["hash"] = "1234".into();
+        assert!(super::work_request(&bad_hash) == Err("Bad block hash"));
+        let mut unknown = encode::work_cancel(hash);
+        unknown["action"] = "account_info".into();
+        assert!(super::work_request(&unknown) == Err("Unknown command"));
+
+        // responses, as parsed by `Rpc`
+        let response = encode::work_generate_response(hash, work, easy);
+        assert!(super::work_generate(response.clone(), hash, None).unwrap() == work);
+        assert!(super::work_generate(response, hash, Some(easy)).unwrap() == work);
+        assert!(super::work_cancel(encode::work_cancel_response()).is_ok());
+        let network = Network::live();
+        let response = encode::work_validate_response(hash, work, Some(easy), &network);
+        assert!(super::work_validate(response, hash, work, Some(easy)).unwrap());
+        let response = encode::work_validate_response(hash, work, Some(harder), &network);
+        assert!(!super::work_validate(response, hash, work, Some(harder)).unwrap());
+        assert!(super::work_cancel(encode::error_response("Cancelled")).is_err());
+    }
 }
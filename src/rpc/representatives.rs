@@ -0,0 +1,196 @@
+//! Choosing a representative, based on voting weight and observed uptime.
+//!
+//! The node does not report how reliable each representative is, so `UptimeTracker` estimates it
+//! by repeatedly sampling `representatives_online`.
+
+use super::{Rpc, RpcError};
+use crate::{Account, Block, BlockType, Signature};
+use std::collections::HashMap;
+
+/// A representative's voting weight and observed uptime
+#[derive(Debug, Clone, PartialEq)]
+pub struct RepresentativeStats {
+    pub account: Account,
+    /// Voting weight, in raw
+    pub weight: u128,
+    /// Fraction of samples in which the representative was online, from `0.0` to `1.0`
+    pub uptime: f64,
+}
+
+/// Estimates the uptime of representatives, by recording which are online at each sample
+#[derive(Debug, Clone, Default)]
+pub struct UptimeTracker {
+    samples: usize,
+    /// The number of samples each representative was online for, and its latest weight
+    seen: HashMap<Account, (usize, u128)>,
+}
+impl UptimeTracker {
+    pub fn new() -> UptimeTracker {
+        UptimeTracker::default()
+    }
+
+    /// The number of samples recorded so far
+    pub fn samples(&self) -> usize {
+        self.samples
+    }
+
+    /// Fetch the online representatives from the node, and record them as a sample
+    pub async fn sample(&mut self, rpc: &Rpc) -> Result<(), RpcError> {
+        let online = rpc.representatives_online().await?;
+        self.record(&online);
+        Ok(())
+    }
+
+    /// Record a sample of the online representatives and their weights,
+    /// as returned by `representatives_online`
+    pub fn record(&mut self, online: &[(Account, u128)]) {
+        self.samples += 1;
+        for (account, weight) in online {
+            let entry = self.seen.entry(account.clone()).or_default();
+            entry.0 += 1;
+            entry.1 = *weight;
+        }
+    }
+
+    /// The stats of every representative which has been seen online at least once
+    pub fn stats(&self) -> Vec<RepresentativeStats> {
+        self.seen
+            .iter()
+            .map(|(account, (count, weight))| RepresentativeStats {
+                account: account.clone(),
+                weight: *weight,
+                uptime: *count as f64 / self.samples as f64,
+            })
+            .collect()
+    }
+}
+
+/// Options for `score` and `recommend`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScoringOptions {
+    /// Minimum uptime (default: `0.95`)
+    pub min_uptime: f64,
+    /// Minimum share of the total weight, so that the representative's votes count (default: `0.001`, the principal representative threshold)
+    pub min_weight_share: f64,
+    /// Maximum share of the total weight, to avoid further centralizing the network (default: `0.03`)
+    pub max_weight_share: f64,
+}
+impl Default for ScoringOptions {
+    fn default() -> Self {
+        ScoringOptions {
+            min_uptime: 0.95,
+            min_weight_share: 0.001,
+            max_weight_share: 0.03,
+        }
+    }
+}
+
+/// Score each eligible representative from `0.0` to `1.0`, sorted from best to worst.
+///
+/// Weight shares are relative to the total weight of `representatives`.
+/// Representatives outside of the options' bounds are skipped.
+/// Otherwise, the score is the uptime, scaled down (by up to half) as the weight share approaches `max_weight_share`,
+/// so that smaller (but still principal) representatives are preferred.
+pub fn score(
+    representatives: &[RepresentativeStats],
+    options: ScoringOptions,
+) -> Vec<(RepresentativeStats, f64)> {
+    let total: u128 = representatives.iter().map(|stats| stats.weight).sum();
+    if total == 0 {
+        return vec![];
+    }
+
+    let mut scored: Vec<_> = representatives
+        .iter()
+        .filter_map(|stats| {
+            let share = stats.weight as f64 / total as f64;
+            let eligible = stats.uptime >= options.min_uptime
+                && share >= options.min_weight_share
+                && share <= options.max_weight_share;
+            let score = stats.uptime * (1.0 - share / options.max_weight_share / 2.0);
+            eligible.then(|| (stats.clone(), score))
+        })
+        .collect();
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+    scored
+}
+
+/// The best-scoring representative, if any are eligible
+pub fn recommend(
+    representatives: &[RepresentativeStats],
+    options: ScoringOptions,
+) -> Option<Account> {
+    score(representatives, options)
+        .into_iter()
+        .next()
+        .map(|(stats, _)| stats.account)
+}
+
+/// Build an (unsigned, without work) change block on top of the account's `frontier`
+pub fn change_block(frontier: &Block, representative: &Account) -> Block {
+    Block {
+        block_type: BlockType::Change,
+        account: frontier.account.clone(),
+        previous: frontier.hash(),
+        representative: representative.clone(),
+        balance: frontier.balance,
+        link: [0; 32],
+        signature: Signature::default(),
+        work: [0; 8],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Key, SecretBytes};
+
+    fn account(i: u32) -> Account {
+        Key::from_seed(&SecretBytes::from([6; 32]), i).to_account()
+    }
+
+    fn stats(i: u32, weight: u128, uptime: f64) -> RepresentativeStats {
+        RepresentativeStats {
+            account: account(i),
+            weight,
+            uptime,
+        }
+    }
+
+    #[test]
+    fn uptime() {
+        let mut tracker = UptimeTracker::new();
+        tracker.record(&[(account(0), 10), (account(1), 20)]);
+        tracker.record(&[(account(0), 15)]);
+
+        let stats = tracker.stats();
+        let first = stats.iter().find(|stats| stats.account == account(0));
+        let second = stats.iter().find(|stats| stats.account == account(1));
+        assert!(first.unwrap() == &super::tests::stats(0, 15, 1.0));
+        assert!(second.unwrap().uptime == 0.5);
+    }
+
+    #[test]
+    fn recommend() {
+        let representatives = [
+            // over-weighted
+            stats(0, 9_000, 1.0),
+            // offline too often
+            stats(1, 200, 0.5),
+            // below the principal threshold
+            stats(2, 1, 1.0),
+            stats(3, 200, 0.99),
+            stats(4, 100, 0.99),
+            stats(5, 100, 0.97),
+        ];
+        let options = ScoringOptions {
+            max_weight_share: 0.1,
+            ..Default::default()
+        };
+
+        let scored = score(&representatives, options);
+        assert!(scored.len() == 3);
+        assert!(super::recommend(&representatives, options) == Some(account(4)));
+        assert!(super::recommend(&representatives[..3], options).is_none());
+    }
+}
@@ -0,0 +1,51 @@
+use super::paging::{HistoryPager, ReceivablePager};
+use super::{HistoryDirection, Receivable, Rpc, RpcError};
+use crate::{Account, Block};
+use futures_util::stream::{self, Stream, TryStreamExt};
+
+impl Rpc {
+    /// Walks the account's entire history, requesting `page_size` blocks at a time.
+    ///
+    /// Each page is checked to link to the previous one; if the account's blockchain changes mid-walk
+    /// (for example, due to a fork being resolved), `RpcError::ChainChanged` is returned.
+    ///
+    /// Will stop at the first legacy block when walking from the frontier,
+    /// and skip legacy blocks when walking from the `open` block.
+    pub fn account_history_stream<'a>(
+        &'a self,
+        account: &Account,
+        page_size: usize,
+        direction: HistoryDirection,
+    ) -> impl Stream<Item = Result<Block, RpcError>> + 'a {
+        let pager = HistoryPager::new(account, page_size, direction);
+        stream::try_unfold(pager, move |mut pager| async move {
+            let Some(request) = pager.next_request() else {
+                return Ok::<_, RpcError>(None);
+            };
+            let page = pager.accept(self._raw_request(request).await)?;
+            Ok(Some((stream::iter(page.into_iter().map(Ok)), pager)))
+        })
+        .try_flatten()
+    }
+
+    /// Lists all receivable transactions for the account above `threshold`, requesting `page_size` at a time.
+    ///
+    /// Transactions which are received (or become receivable) mid-walk may shift the pages,
+    /// so a transaction may be missed, but will never be returned twice.
+    pub fn receivable_stream<'a>(
+        &'a self,
+        account: &Account,
+        page_size: usize,
+        threshold: u128,
+    ) -> impl Stream<Item = Result<Receivable, RpcError>> + 'a {
+        let pager = ReceivablePager::new(account, page_size, threshold);
+        stream::try_unfold(pager, move |mut pager| async move {
+            let Some(request) = pager.next_request() else {
+                return Ok::<_, RpcError>(None);
+            };
+            let page = pager.accept(self._raw_request(request).await)?;
+            Ok(Some((stream::iter(page.into_iter().map(Ok)), pager)))
+        })
+        .try_flatten()
+    }
+}
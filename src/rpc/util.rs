@@ -1,5 +1,6 @@
 use super::{BlockInfo, RpcError};
-use crate::{Account, Block, BlockType};
+use crate::telemetry::decode_node_id;
+use crate::{Account, Block, BlockType, Telemetry};
 use hex::FromHexError;
 
 pub mod parse {
@@ -49,12 +50,28 @@ pub fn u128_from_json(value: &JsonValue) -> Result<u128, RpcError> {
         .map_err(|_| RpcError::InvalidInteger)
 }
 
+/// Accepts `true` and `false`, as well as `1` and `0`
 pub fn bool_from_json(value: &JsonValue) -> Result<bool, RpcError> {
+    match trim_json(&value.to_string()) {
+        "1" => Ok(true),
+        "0" => Ok(false),
+        value => value.parse::<bool>().map_err(|_| RpcError::InvalidInteger),
+    }
+}
+
+pub fn u8_from_json(value: &JsonValue) -> Result<u8, RpcError> {
     trim_json(&value.to_string())
-        .parse::<bool>()
+        .parse::<u8>()
         .map_err(|_| RpcError::InvalidInteger)
 }
 
+pub fn string_from_json(value: &JsonValue) -> Result<String, RpcError> {
+    value
+        .as_str()
+        .map(|value| value.to_string())
+        .ok_or(RpcError::InvalidJsonDataType)
+}
+
 pub fn bytes_from_json<const T: usize>(value: &JsonValue) -> Result<[u8; T], RpcError> {
     from_hex(&value.to_string())?
         .try_into()
@@ -84,11 +101,47 @@ pub fn block_from_json(block: &JsonValue, block_type: BlockType) -> Result<Block
         link: bytes_from_json(&block["link"])?,
         signature: bytes_from_json::<64>(&block["signature"])?
             .try_into()
-            .unwrap(),
+            .map_err(|_| RpcError::InvalidData)?,
         work: bytes_from_json(&block["work"])?,
     })
 }
 
+/// Parse the telemetry of a single node, including its `node_id` and `signature`.
+///
+/// The node's JSON does not include `Telemetry::unknown_data`, which is left empty.
+pub fn telemetry_from_json(value: &JsonValue) -> Result<Telemetry, RpcError> {
+    let telemetry = Telemetry {
+        node_id: decode_node_id(trim_json(&value["node_id"].to_string()))
+            .map_err(|_| RpcError::InvalidData)?,
+        signature: bytes_from_json::<64>(&value["signature"])?
+            .try_into()
+            .map_err(|_| RpcError::InvalidData)?,
+        block_count: u64_from_json(&value["block_count"])?,
+        cemented_count: u64_from_json(&value["cemented_count"])?,
+        unchecked_count: u64_from_json(&value["unchecked_count"])?,
+        account_count: u64_from_json(&value["account_count"])?,
+        bandwidth_cap: u64_from_json(&value["bandwidth_cap"])?,
+        peer_count: u64_from_json(&value["peer_count"])?
+            .try_into()
+            .map_err(|_| RpcError::InvalidData)?,
+        protocol_version: u8_from_json(&value["protocol_version"])?,
+        uptime: u64_from_json(&value["uptime"])?,
+        genesis_block: bytes_from_json(&value["genesis_block"])?,
+        major_version: u8_from_json(&value["major_version"])?,
+        minor_version: u8_from_json(&value["minor_version"])?,
+        patch_version: u8_from_json(&value["patch_version"])?,
+        pre_release_version: u8_from_json(&value["pre_release_version"])?,
+        maker: u8_from_json(&value["maker"])?,
+        timestamp: u64_from_json(&value["timestamp"])?,
+        active_difficulty: bytes_from_json(&value["active_difficulty"])?,
+        unknown_data: vec![],
+    };
+    if telemetry.cemented_count > telemetry.block_count {
+        return Err(RpcError::InvalidData);
+    }
+    Ok(telemetry)
+}
+
 /// Specific to `account_history`
 pub(crate) fn block_from_history_json(block: &JsonValue) -> Result<Block, RpcError> {
     let block_type = block["type"].to_string();
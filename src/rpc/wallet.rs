@@ -0,0 +1,581 @@
+use super::representatives::change_block;
+use super::work::{checked_work, WorkSource};
+use super::{BlockInfo, ConfirmationOptions, Rpc, RpcError};
+use crate::{Account, Block, BlockType, Key, Network, SecretBytes, Signature};
+use std::cmp::Reverse;
+use std::ops::Range;
+use std::sync::Arc;
+
+/// Options for `Rpc::receive_all`
+#[derive(Debug, Clone)]
+pub struct ReceiveOptions {
+    /// Minimum amount to receive, in raw (default: 1)
+    pub threshold: u128,
+    /// How many receivable transactions to request at a time (default: 100)
+    pub batch_size: usize,
+    /// Representative to use if the account has not been opened yet.
+    /// If `None`, the account will represent itself. (default: `None`)
+    pub representative: Option<Account>,
+    /// Work difficulty of the receive blocks (default: the network's `receive_work_difficulty`)
+    pub difficulty: [u8; 8],
+    /// Where to get work from. If `None`, the node's `work_generate` is used. (default: `None`)
+    pub work_source: Option<Arc<dyn WorkSource>>,
+}
+impl ReceiveOptions {
+    /// The default options, using the given network's work threshold
+    pub fn for_network(network: &Network) -> ReceiveOptions {
+        ReceiveOptions {
+            threshold: 1,
+            batch_size: 100,
+            representative: None,
+            difficulty: network.receive_work_difficulty,
+            work_source: None,
+        }
+    }
+}
+impl Default for ReceiveOptions {
+    fn default() -> Self {
+        ReceiveOptions::for_network(&Network::live())
+    }
+}
+
+/// Options for `Rpc::send` and `Rpc::change_representative`
+#[derive(Debug, Clone)]
+pub struct SendOptions {
+    /// Work difficulty of the block (default: the network's `send_work_difficulty`)
+    pub difficulty: [u8; 8],
+    /// Where to get work from. If `None`, the node's `work_generate` is used. (default: `None`)
+    pub work_source: Option<Arc<dyn WorkSource>>,
+    /// How to wait for the block to be confirmed
+    pub confirmation: ConfirmationOptions,
+}
+impl SendOptions {
+    /// The default options, using the given network's work threshold
+    pub fn for_network(network: &Network) -> SendOptions {
+        SendOptions {
+            difficulty: network.send_work_difficulty,
+            work_source: None,
+            confirmation: ConfirmationOptions::default(),
+        }
+    }
+}
+impl Default for SendOptions {
+    fn default() -> Self {
+        SendOptions::for_network(&Network::live())
+    }
+}
+
+/// A receivable transaction which was received
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Received {
+    /// Hash of the published receive block
+    pub hash: [u8; 32],
+    /// Hash of the send block which was received
+    pub send_hash: [u8; 32],
+    /// The amount received
+    pub amount: u128,
+}
+
+/// The outcome of `Rpc::receive_all`
+#[derive(Debug)]
+pub struct ReceiveReport {
+    /// The transactions which were received, in the order that they were published
+    pub received: Vec<Received>,
+    /// The first error encountered, if any. No more transactions were received after it.
+    pub error: Option<RpcError>,
+}
+impl ReceiveReport {
+    /// Returns `true` if every receivable transaction was received
+    pub fn is_complete(&self) -> bool {
+        self.error.is_none()
+    }
+
+    /// The total amount received
+    pub fn total(&self) -> u128 {
+        self.received.iter().map(|received| received.amount).sum()
+    }
+}
+
+impl Rpc {
+    /// Receive every receivable transaction above `options.threshold`, publishing one receive block each,
+    /// in the order returned by the node (largest amount first).
+    ///
+    /// If the account has not been opened, the first block opens it with `options.representative`.
+    /// Otherwise, the account's current representative is kept, and its frontier block is checked like in `send`.
+    ///
+    /// Stops at the first error, which is returned in the report along with the blocks that were published.
+    pub async fn receive_all(&self, key: &Key, options: ReceiveOptions) -> ReceiveReport {
+        let mut received = vec![];
+        let error = self
+            .receive_batches(key, &options, &mut received)
+            .await
+            .err();
+        ReceiveReport { received, error }
+    }
+
+    async fn receive_batches(
+        &self,
+        key: &Key,
+        options: &ReceiveOptions,
+        received: &mut Vec<Received>,
+    ) -> Result<(), RpcError> {
+        let account = key.to_account();
+        let work_source: &dyn WorkSource = match &options.work_source {
+            Some(work_source) => work_source.as_ref(),
+            None => self,
+        };
+        let (mut frontier, mut balance, representative) =
+            match self.verified_frontier(&account).await? {
+                Some(frontier) => (
+                    frontier.hash(),
+                    frontier.balance,
+                    frontier.representative.clone(),
+                ),
+                None => (
+                    [0; 32],
+                    0,
+                    options.representative.clone().unwrap_or(account.clone()),
+                ),
+            };
+
+        loop {
+            // the node does not return amounts if the threshold is 0
+            let batch = self
+                .accounts_receivable(
+                    std::slice::from_ref(&account),
+                    options.batch_size,
+                    options.threshold.max(1),
+                )
+                .await?;
+            let mut batch: Vec<_> = batch
+                .into_iter()
+                .flatten()
+                .filter(|receivable| {
+                    !received
+                        .iter()
+                        .any(|received| received.send_hash == receivable.block_hash)
+                })
+                .collect();
+            if batch.is_empty() {
+                return Ok(());
+            }
+            // the order of the node's response is lost when parsing it
+            batch.sort_by_key(|receivable| Reverse(receivable.amount));
+
+            for receivable in batch {
+                balance = balance
+                    .checked_add(receivable.amount)
+                    .ok_or(RpcError::InvalidData)?;
+                let block = Block {
+                    block_type: BlockType::Receive,
+                    account: account.clone(),
+                    previous: frontier,
+                    representative: representative.clone(),
+                    balance,
+                    link: receivable.block_hash,
+                    signature: Signature::default(),
+                    work: [0; 8],
+                };
+                frontier = self
+                    .sign_and_publish(key, block, work_source, options.difficulty)
+                    .await?;
+                received.push(Received {
+                    hash: frontier,
+                    send_hash: receivable.block_hash,
+                    amount: receivable.amount,
+                });
+            }
+        }
+    }
+
+    /// Send `amount` (in raw) to `destination`, and wait for the send block to be confirmed.
+    ///
+    /// The new block is built on top of the account's frontier block, which must have a valid signature
+    /// and match the frontier returned by `account_info`. Otherwise, `RpcError::InvalidData` is returned
+    /// and nothing is published.
+    pub async fn send(
+        &self,
+        key: &Key,
+        destination: &Account,
+        amount: u128,
+        options: SendOptions,
+    ) -> Result<BlockInfo, RpcError> {
+        let account = key.to_account();
+        let work_source: &dyn WorkSource = match &options.work_source {
+            Some(work_source) => work_source.as_ref(),
+            None => self,
+        };
+        let frontier = self
+            .verified_frontier(&account)
+            .await?
+            .ok_or(RpcError::InsufficientBalance)?;
+
+        let block = Block {
+            block_type: BlockType::Send,
+            account,
+            previous: frontier.hash(),
+            representative: frontier.representative.clone(),
+            balance: frontier
+                .balance
+                .checked_sub(amount)
+                .ok_or(RpcError::InsufficientBalance)?,
+            link: destination.compressed.to_bytes(),
+            signature: Signature::default(),
+            work: [0; 8],
+        };
+        let hash = self
+            .sign_and_publish(key, block, work_source, options.difficulty)
+            .await?;
+        self.wait_for_confirmation(hash, options.confirmation).await
+    }
+
+    /// Change the account's representative, and wait for the change block to be confirmed.
+    ///
+    /// The change block is built on top of the account's frontier block, which is checked like in `send`.
+    /// Returns `RpcError::InvalidData` if the account has not been opened.
+    pub async fn change_representative(
+        &self,
+        key: &Key,
+        representative: &Account,
+        options: SendOptions,
+    ) -> Result<BlockInfo, RpcError> {
+        let account = key.to_account();
+        let work_source: &dyn WorkSource = match &options.work_source {
+            Some(work_source) => work_source.as_ref(),
+            None => self,
+        };
+        let frontier = self
+            .verified_frontier(&account)
+            .await?
+            .ok_or(RpcError::InvalidData)?;
+
+        let block = change_block(&frontier, representative);
+        let hash = self
+            .sign_and_publish(key, block, work_source, options.difficulty)
+            .await?;
+        self.wait_for_confirmation(hash, options.confirmation).await
+    }
+
+    /// Change the representative of every account derived from `seed` with an index in `indexes`,
+    /// one account at a time.
+    ///
+    /// Accounts which have not been opened, or which already use `representative`, are skipped.
+    /// Returns the result for each of the other accounts, stopping early only if `accounts_representatives` fails.
+    pub async fn change_representatives(
+        &self,
+        seed: &SecretBytes<32>,
+        indexes: Range<u32>,
+        representative: &Account,
+        options: SendOptions,
+    ) -> Result<Vec<(Account, Result<BlockInfo, RpcError>)>, RpcError> {
+        let keys: Vec<Key> = indexes.map(|i| Key::from_seed(seed, i)).collect();
+        let accounts: Vec<Account> = keys.iter().map(Key::to_account).collect();
+        let current = self.accounts_representatives(&accounts).await?;
+
+        let mut results = vec![];
+        for ((key, account), current) in keys.iter().zip(accounts).zip(current) {
+            if current.is_none() || current.as_ref() == Some(representative) {
+                continue;
+            }
+            let result = self
+                .change_representative(key, representative, options.clone())
+                .await;
+            results.push((account, result));
+        }
+        Ok(results)
+    }
+
+    /// Returns the account's frontier block, or `None` if the account has not been opened.
+    ///
+    /// The block must match the frontier returned by `account_info` (`block_info` checks its signature).
+    async fn verified_frontier(&self, account: &Account) -> Result<Option<Block>, RpcError> {
+        let Some(info) = self.account_info(account).await? else {
+            return Ok(None);
+        };
+        let frontier = self
+            .block_info(info.frontier)
+            .await?
+            .ok_or(RpcError::InvalidData)?
+            .block
+            .clone();
+        if frontier.hash() != info.frontier || &frontier.account != account {
+            return Err(RpcError::InvalidData);
+        }
+        Ok(Some(frontier))
+    }
+
+    /// Sign the block, generate its work, and publish it
+    async fn sign_and_publish(
+        &self,
+        key: &Key,
+        mut block: Block,
+        work_source: &dyn WorkSource,
+        difficulty: [u8; 8],
+    ) -> Result<[u8; 32], RpcError> {
+        block.sign(key);
+        block.work = checked_work(work_source, block.work_hash(), difficulty).await?;
+        self.process(&block).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::mock::mock_rpc;
+    use crate::rpc::util::{block_from_json, block_to_json, bytes_from_json, to_uppercase_hex};
+    use crate::rpc::work::LocalWork;
+    use crate::SecretBytes;
+    use json::{json, Value as JsonValue};
+    use serde_json as json;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    const EASY: [u8; 8] = [0xf0, 0, 0, 0, 0, 0, 0, 0];
+
+    fn key() -> Key {
+        Key::from_seed(&SecretBytes::from([4; 32]), 0)
+    }
+
+    /// A node with receivable transactions, optionally rejecting the nth block.
+    /// The account is unopened, unless `blocks` is set.
+    #[derive(Debug, Default)]
+    struct Node {
+        receivable: Vec<([u8; 32], u128)>,
+        blocks: Vec<Block>,
+        reject: Option<usize>,
+    }
+    impl Node {
+        fn handle(&mut self, request: JsonValue) -> JsonValue {
+            let account = key().to_account().to_string();
+            match request["action"].as_str().unwrap() {
+                "account_info" => match self.blocks.last() {
+                    Some(frontier) => json!({
+                        "frontier": to_uppercase_hex(&frontier.hash()),
+                        "open_block": to_uppercase_hex(&self.blocks[0].hash()),
+                        "balance": frontier.balance.to_string(),
+                        "modified_timestamp": "1564935350",
+                        "block_count": self.blocks.len().to_string(),
+                        "account_version": "2",
+                        "representative": frontier.representative.to_string(),
+                        "weight": "0",
+                        "receivable": "0"
+                    }),
+                    None => json!({"error": "Account not found"}),
+                },
+                "accounts_representatives" => {
+                    let representatives: json::Map<_, _> = self
+                        .blocks
+                        .last()
+                        .map(|frontier| (account, frontier.representative.to_string().into()))
+                        .into_iter()
+                        .collect();
+                    json!({ "representatives": representatives })
+                }
+                "block_info" => {
+                    let hash: [u8; 32] = bytes_from_json(&request["hash"]).unwrap();
+                    match self.blocks.iter().position(|block| block.hash() == hash) {
+                        Some(i) => json!({
+                            "block_account": account,
+                            "amount": "0",
+                            "balance": self.blocks[i].balance.to_string(),
+                            "height": (i + 1).to_string(),
+                            "local_timestamp": "1564935350",
+                            "confirmed": "true",
+                            "contents": block_to_json(&self.blocks[i]),
+                            "subtype": self.blocks[i].block_type.to_string()
+                        }),
+                        None => json!({"error": "Block not found"}),
+                    }
+                }
+                "accounts_receivable" => {
+                    let blocks: json::Map<_, _> = self
+                        .receivable
+                        .iter()
+                        .map(|(hash, amount)| (to_uppercase_hex(hash), amount.to_string().into()))
+                        .collect();
+                    json!({"blocks": {account: blocks}})
+                }
+                "process" => {
+                    let subtype =
+                        BlockType::from_subtype_string(request["subtype"].as_str().unwrap());
+                    let block = block_from_json(&request["block"], subtype.unwrap()).unwrap();
+                    if self.reject == Some(self.blocks.len()) {
+                        return json!({"error": "Fork"});
+                    }
+                    let previous = self.blocks.last().map(Block::hash).unwrap_or([0; 32]);
+                    let balance = self.blocks.last().map(|block| block.balance).unwrap_or(0);
+                    assert!(block.has_valid_signature() && block.has_valid_work(EASY));
+                    assert!(block.previous == previous);
+                    if block.block_type.is_receive() {
+                        let amount = self
+                            .receivable
+                            .iter()
+                            .position(|(hash, _)| hash == &block.link)
+                            .map(|i| self.receivable.remove(i).1)
+                            .unwrap();
+                        assert!(block.balance == balance + amount);
+                    }
+
+                    let hash = block.hash();
+                    self.blocks.push(block);
+                    json!({ "hash": to_uppercase_hex(&hash) })
+                }
+                _ => panic!("unexpected request"),
+            }
+        }
+    }
+
+    async fn start(node: Node) -> (Rpc, Arc<Mutex<Node>>) {
+        let node = Arc::new(Mutex::new(node));
+        let handler = node.clone();
+        let url = mock_rpc(move |request| handler.lock().unwrap().handle(request)).await;
+        (Rpc::new(&url, None).unwrap(), node)
+    }
+
+    /// An opened account with a balance of 1000
+    fn opened() -> Vec<Block> {
+        let mut open = Block {
+            block_type: BlockType::Receive,
+            account: key().to_account(),
+            previous: [0; 32],
+            representative: key().to_account(),
+            balance: 1000,
+            link: [1; 32],
+            signature: Signature::default(),
+            work: [0; 8],
+        };
+        open.sign(&key());
+        vec![open]
+    }
+
+    fn send_options() -> SendOptions {
+        SendOptions {
+            difficulty: EASY,
+            work_source: Some(Arc::new(LocalWork)),
+            confirmation: ConfirmationOptions {
+                timeout: Duration::from_secs(5),
+                poll_interval: Duration::from_millis(10),
+                block_confirm: false,
+            },
+        }
+    }
+
+    fn options(representative: &Account) -> ReceiveOptions {
+        ReceiveOptions {
+            representative: Some(representative.clone()),
+            difficulty: EASY,
+            work_source: Some(Arc::new(LocalWork)),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn receive_all() {
+        let (rpc, node) = start(Node {
+            receivable: vec![([1; 32], 200), ([2; 32], 300)],
+            ..Default::default()
+        })
+        .await;
+        let representative = Key::from_seed(&SecretBytes::from([5; 32]), 0).to_account();
+
+        // largest amount first
+        let report = rpc.receive_all(&key(), options(&representative)).await;
+        assert!(report.is_complete());
+        assert!(report.total() == 500);
+        assert!(report.received[0].send_hash == [2; 32]);
+
+        let node = node.lock().unwrap();
+        assert!(node.receivable.is_empty());
+        assert!(node.blocks[0].previous == [0; 32]);
+        assert!(node.blocks[1].representative == representative);
+        assert!(report.received[1].hash == node.blocks[1].hash());
+    }
+
+    #[tokio::test]
+    async fn first_failure() {
+        let (rpc, node) = start(Node {
+            receivable: vec![([1; 32], 300), ([2; 32], 200), ([3; 32], 100)],
+            reject: Some(1),
+            ..Default::default()
+        })
+        .await;
+
+        let report = rpc.receive_all(&key(), options(&key().to_account())).await;
+        assert!(!report.is_complete());
+        assert!(report.received.len() == 1);
+        assert!(node.lock().unwrap().receivable.len() == 2);
+    }
+
+    #[tokio::test]
+    async fn send() {
+        let (rpc, node) = start(Node {
+            blocks: opened(),
+            ..Default::default()
+        })
+        .await;
+        let destination = Key::from_seed(&SecretBytes::from([5; 32]), 0).to_account();
+
+        let info = rpc
+            .send(&key(), &destination, 400, send_options())
+            .await
+            .unwrap();
+        assert!(info.confirmed && info.height == 2);
+        assert!(info.block.balance == 600);
+        assert!(info.block.link_as_account().unwrap() == destination);
+        assert!(info.block == node.lock().unwrap().blocks[1]);
+
+        let too_much = rpc.send(&key(), &destination, 601, send_options()).await;
+        assert!(matches!(too_much, Err(RpcError::InsufficientBalance)));
+    }
+
+    #[tokio::test]
+    async fn invalid_frontier() {
+        let mut blocks = opened();
+        blocks[0].balance = 2000;
+        let (rpc, node) = start(Node {
+            receivable: vec![([2; 32], 100)],
+            blocks,
+            ..Default::default()
+        })
+        .await;
+
+        let result = rpc
+            .send(&key(), &key().to_account(), 1, send_options())
+            .await;
+        assert!(matches!(result, Err(RpcError::InvalidData)));
+        let report = rpc.receive_all(&key(), options(&key().to_account())).await;
+        assert!(matches!(report.error, Some(RpcError::InvalidData)));
+        assert!(report.received.is_empty());
+        assert!(node.lock().unwrap().blocks.len() == 1);
+    }
+
+    #[tokio::test]
+    async fn change_representatives() {
+        let (rpc, node) = start(Node {
+            blocks: opened(),
+            ..Default::default()
+        })
+        .await;
+        let seed = SecretBytes::from([4; 32]);
+        let representative = Key::from_seed(&SecretBytes::from([5; 32]), 0).to_account();
+
+        // only the first account has been opened
+        let results = rpc
+            .change_representatives(&seed, 0..3, &representative, send_options())
+            .await
+            .unwrap();
+        assert!(results.len() == 1 && results[0].0 == key().to_account());
+        let info = results[0].1.as_ref().unwrap();
+        assert!(info.block.block_type == BlockType::Change);
+        assert!(info.block.representative == representative);
+        assert!(info.block.balance == 1000);
+        assert!(node.lock().unwrap().blocks.len() == 2);
+
+        // already changed
+        let results = rpc
+            .change_representatives(&seed, 0..3, &representative, send_options())
+            .await
+            .unwrap();
+        assert!(results.is_empty());
+    }
+}
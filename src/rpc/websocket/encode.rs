@@ -0,0 +1,119 @@
+use super::Topic;
+use crate::Account;
+use json::{Map, Value as JsonValue};
+use serde_json as json;
+
+fn accounts_to_json(accounts: &[Account]) -> JsonValue {
+    accounts
+        .iter()
+        .map(|account| account.into())
+        .collect::<Vec<JsonValue>>()
+        .into()
+}
+
+pub fn subscribe(topic: &Topic, id: &str) -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "subscribe".into());
+    arguments.insert("topic".into(), topic.name().into());
+    arguments.insert("ack".into(), true.into());
+    arguments.insert("id".into(), id.into());
+
+    let mut options = Map::new();
+    match topic {
+        Topic::Confirmation { accounts } => {
+            options.insert("include_block".into(), "true".into());
+            options.insert("include_sideband_info".into(), "true".into());
+            if !accounts.is_empty() {
+                options.insert("accounts".into(), accounts_to_json(accounts));
+            }
+        }
+        Topic::Votes { representatives } if !representatives.is_empty() => {
+            options.insert("representatives".into(), accounts_to_json(representatives));
+        }
+        _ => (),
+    }
+    if !options.is_empty() {
+        arguments.insert("options".into(), options.into());
+    }
+    JsonValue::Object(arguments)
+}
+
+pub fn unsubscribe(topic: &Topic, id: &str) -> JsonValue {
+    let mut arguments = Map::new();
+    arguments.insert("action".into(), "unsubscribe".into());
+    arguments.insert("topic".into(), topic.name().into());
+    arguments.insert("ack".into(), true.into());
+    arguments.insert("id".into(), id.into());
+    JsonValue::Object(arguments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Topic;
+    use crate::Account;
+    use serde_json::json;
+
+    #[test]
+    fn subscribe() {
+        let account: Account = "nano_1ipx847tk8o46pwxt5qjdbncjqcbwcc1rrmqnkztrfjy5k7z4imsrata9est"
+            .parse()
+            .unwrap();
+
+        let confirmation = super::subscribe(
+            &Topic::Confirmation {
+                accounts: vec![account.clone()],
+            },
+            "1",
+        );
+        assert!(
+            confirmation
+                == json!({
+                    "action": "subscribe",
+                    "topic": "confirmation",
+                    "ack": true,
+                    "id": "1",
+                    "options": {
+                        "include_block": "true",
+                        "include_sideband_info": "true",
+                        "accounts": ["nano_1ipx847tk8o46pwxt5qjdbncjqcbwcc1rrmqnkztrfjy5k7z4imsrata9est"]
+                    }
+                })
+        );
+
+        let votes = super::subscribe(
+            &Topic::Votes {
+                representatives: vec![account],
+            },
+            "2",
+        );
+        assert!(votes["topic"] == "vote");
+        assert!(
+            votes["options"]
+                == json!({"representatives": ["nano_1ipx847tk8o46pwxt5qjdbncjqcbwcc1rrmqnkztrfjy5k7z4imsrata9est"]})
+        );
+
+        let telemetry = super::subscribe(&Topic::Telemetry, "3");
+        assert!(
+            telemetry
+                == json!({
+                    "action": "subscribe",
+                    "topic": "telemetry",
+                    "ack": true,
+                    "id": "3"
+                })
+        );
+    }
+
+    #[test]
+    fn unsubscribe() {
+        let json = super::unsubscribe(&Topic::StoppedElection, "4");
+        assert!(
+            json == json!({
+                "action": "unsubscribe",
+                "topic": "stopped_election",
+                "ack": true,
+                "id": "4"
+            })
+        );
+    }
+}
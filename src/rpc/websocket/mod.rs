@@ -0,0 +1,625 @@
+//! Client for the node's [WebSocket API](https://docs.nano.org/integration-guides/websockets/).
+//!
+//! Unlike the RPC, the node *pushes* events (such as confirmations) to the client as they happen.
+
+mod encode;
+mod parse;
+
+use super::{BlockInfo, RpcError};
+use crate::{Account, Signature, Telemetry, Vote};
+use futures_util::{SinkExt, StreamExt};
+use parse::Incoming;
+use std::collections::VecDeque;
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio_tungstenite::{
+    connect_async,
+    tungstenite::{Error as WsError, Message},
+    MaybeTlsStream, WebSocketStream,
+};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// A topic which can be subscribed to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Topic {
+    /// Confirmed blocks.
+    /// If `accounts` is empty, *all* confirmations will be received.
+    Confirmation {
+        accounts: Vec<Account>,
+    },
+    /// Votes by representatives.
+    /// If `representatives` is empty, votes of *all* representatives will be received.
+    Votes {
+        representatives: Vec<Account>,
+    },
+    StartedElection,
+    StoppedElection,
+    ActiveDifficulty,
+    Telemetry,
+}
+impl Topic {
+    /// The name of the topic, as used by the node
+    pub fn name(&self) -> &'static str {
+        match self {
+            Topic::Confirmation { .. } => "confirmation",
+            Topic::Votes { .. } => "vote",
+            Topic::StartedElection => "started_election",
+            Topic::StoppedElection => "stopped_election",
+            Topic::ActiveDifficulty => "active_difficulty",
+            Topic::Telemetry => "telemetry",
+        }
+    }
+}
+
+/// A confirmed block
+#[derive(Debug, Clone, Zeroize, ZeroizeOnDrop, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Confirmation {
+    /// The block, along with its height and local timestamp
+    pub block_info: BlockInfo,
+    /// The amount sent or received by the block, if any
+    pub amount: u128,
+    /// How the block was confirmed, such as `active_quorum`
+    pub confirmation_type: String,
+}
+
+/// A vote, as reported by the node.
+///
+/// **The signature has not been checked.**
+#[derive(Debug, Clone, Zeroize, ZeroizeOnDrop, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct VoteMessage {
+    pub representative: Account,
+    pub signature: Signature,
+    /// `u64::MAX` for final votes
+    pub timestamp: u64,
+    /// Hashes of the blocks being voted for
+    pub hashes: Vec<[u8; 32]>,
+    /// Such as `vote` or `replay`
+    pub vote_type: String,
+}
+
+impl VoteMessage {
+    /// The vote itself, without the `vote_type`
+    pub fn to_vote(&self) -> Vote {
+        Vote {
+            representative: self.representative.clone(),
+            signature: self.signature,
+            timestamp: self.timestamp,
+            hashes: self.hashes.clone(),
+        }
+    }
+}
+
+/// The network's current work difficulty
+#[derive(Debug, Clone, Zeroize, ZeroizeOnDrop, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ActiveDifficulty {
+    pub network_current: [u8; 8],
+    pub network_minimum: [u8; 8],
+    pub network_receive_current: [u8; 8],
+    pub network_receive_minimum: [u8; 8],
+}
+
+/// An event pushed by the node
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    Confirmation(Box<Confirmation>),
+    Vote(Box<VoteMessage>),
+    /// Hash of the block whose election started
+    StartedElection([u8; 32]),
+    /// Hash of the block whose election stopped
+    StoppedElection([u8; 32]),
+    ActiveDifficulty(ActiveDifficulty),
+    /// Telemetry of a single peer, which can be checked with `Telemetry::has_valid_signature`
+    Telemetry(Box<Telemetry>),
+}
+
+/// A connection to the node's WebSocket server.
+///
+/// If the connection is lost, `next_event` will automatically reconnect and resubscribe to all topics.
+#[derive(Debug)]
+pub struct WebSocket {
+    url: String,
+    stream: Option<WsStream>,
+    subscriptions: Vec<Topic>,
+    /// Events received while waiting for an acknowledgement
+    pending: VecDeque<Event>,
+    reconnect_delay: Duration,
+    reconnect_attempts: usize,
+    next_id: u64,
+}
+impl WebSocket {
+    pub async fn connect(url: &str) -> Result<WebSocket, RpcError> {
+        let (stream, _) = connect_async(url).await?;
+        Ok(WebSocket {
+            url: url.into(),
+            stream: Some(stream),
+            subscriptions: vec![],
+            pending: VecDeque::new(),
+            reconnect_delay: Duration::from_secs(1),
+            reconnect_attempts: 5,
+            next_id: 0,
+        })
+    }
+
+    /// Get the URL of this WebSocket
+    pub fn get_url(&self) -> &str {
+        &self.url
+    }
+
+    /// Get the topics currently subscribed to
+    pub fn get_subscriptions(&self) -> &[Topic] {
+        &self.subscriptions
+    }
+
+    /// Set how long to wait before each reconnection attempt (default: 1 second)
+    pub fn set_reconnect_delay(&mut self, delay: Duration) {
+        self.reconnect_delay = delay
+    }
+
+    /// Set how many times to try reconnecting before giving up (default: 5)
+    pub fn set_reconnect_attempts(&mut self, attempts: usize) {
+        self.reconnect_attempts = attempts
+    }
+
+    /// Subscribe to the topic, waiting for the node to acknowledge it.
+    /// Replaces any previous subscription to the same topic.
+    pub async fn subscribe(&mut self, topic: Topic) -> Result<(), RpcError> {
+        self.subscriptions
+            .retain(|subscribed| subscribed.name() != topic.name());
+        self.subscriptions.push(topic.clone());
+
+        let id = self.new_id();
+        match self.request(encode::subscribe(&topic, &id), &id).await {
+            // reconnecting will resubscribe to this topic
+            Err(RpcError::WebSocketError(_)) => self.reconnect().await,
+            result => result,
+        }
+    }
+
+    /// Unsubscribe from the topic, waiting for the node to acknowledge it
+    pub async fn unsubscribe(&mut self, topic: &Topic) -> Result<(), RpcError> {
+        self.subscriptions
+            .retain(|subscribed| subscribed.name() != topic.name());
+
+        let id = self.new_id();
+        match self.request(encode::unsubscribe(topic, &id), &id).await {
+            // reconnecting will not resubscribe to this topic
+            Err(RpcError::WebSocketError(_)) => self.reconnect().await,
+            result => result,
+        }
+    }
+
+    /// Wait for the next event.
+    ///
+    /// Reconnects and resubscribes if the connection was lost.
+    /// An error is only returned if reconnecting failed, or if the node sent invalid data.
+    pub async fn next_event(&mut self) -> Result<Event, RpcError> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Ok(event);
+            }
+            match self.receive().await {
+                Ok(Incoming::Event(event)) => return Ok(event),
+                Ok(Incoming::Ack(_)) => (),
+                Err(RpcError::WebSocketError(_)) => self.reconnect().await?,
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    /// Close the connection
+    pub async fn close(mut self) -> Result<(), RpcError> {
+        if let Some(mut stream) = self.stream.take() {
+            stream.close(None).await?;
+        }
+        Ok(())
+    }
+
+    /// Put the events back, to be returned by `next_event` before any new ones
+    pub(crate) fn requeue(&mut self, events: Vec<Event>) {
+        for event in events.into_iter().rev() {
+            self.pending.push_front(event)
+        }
+    }
+
+    fn new_id(&mut self) -> String {
+        self.next_id += 1;
+        self.next_id.to_string()
+    }
+
+    /// Send the request, and wait for the node to acknowledge it
+    async fn request(&mut self, json: serde_json::Value, id: &str) -> Result<(), RpcError> {
+        let stream = self.stream.as_mut().ok_or(WsError::AlreadyClosed)?;
+        stream.send(Message::Text(json.to_string())).await?;
+        loop {
+            match self.receive().await? {
+                Incoming::Ack(ack) if ack == id => return Ok(()),
+                Incoming::Ack(_) => (),
+                Incoming::Event(event) => self.pending.push_back(event),
+            }
+        }
+    }
+
+    /// Receive the next message which is either an acknowledgement, or an event of a known topic
+    async fn receive(&mut self) -> Result<Incoming, RpcError> {
+        loop {
+            let stream = self.stream.as_mut().ok_or(WsError::AlreadyClosed)?;
+            let text = match stream.next().await {
+                Some(Ok(Message::Text(text))) => text,
+                Some(Ok(Message::Close(_))) | None => {
+                    self.stream = None;
+                    return Err(WsError::ConnectionClosed.into());
+                }
+                Some(Ok(_)) => continue,
+                Some(Err(err)) => {
+                    self.stream = None;
+                    return Err(err.into());
+                }
+            };
+            if let Some(incoming) = parse::incoming(serde_json::from_str(&text)?)? {
+                return Ok(incoming);
+            }
+        }
+    }
+
+    /// Reconnect, and resubscribe to all topics
+    async fn reconnect(&mut self) -> Result<(), RpcError> {
+        self.stream = None;
+        let mut last_error = WsError::ConnectionClosed.into();
+        'attempts: for _ in 0..self.reconnect_attempts {
+            tokio::time::sleep(self.reconnect_delay).await;
+            match connect_async(&self.url).await {
+                Ok((stream, _)) => self.stream = Some(stream),
+                Err(err) => {
+                    last_error = err.into();
+                    continue;
+                }
+            }
+            for topic in self.subscriptions.clone() {
+                let id = self.new_id();
+                if let Err(err) = self.request(encode::subscribe(&topic, &id), &id).await {
+                    self.stream = None;
+                    last_error = err;
+                    continue 'attempts;
+                }
+            }
+            return Ok(());
+        }
+        Err(last_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse::tests::{confirmation_json, signed_block};
+    use super::*;
+    use crate::rpc::mock::mock_rpc;
+    use crate::rpc::util::{block_to_json, to_uppercase_hex};
+    use crate::rpc::{ConfirmationOptions, Rpc};
+    use serde_json::{json, Value as JsonValue};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::accept_async;
+
+    type ServerStream = WebSocketStream<TcpStream>;
+
+    async fn accept(listener: &TcpListener) -> ServerStream {
+        let (tcp, _) = listener.accept().await.unwrap();
+        accept_async(tcp).await.unwrap()
+    }
+
+    /// Receive a request, check it, and acknowledge it
+    async fn expect_request(server: &mut ServerStream, action: &str, topic: &str) -> JsonValue {
+        let request = match server.next().await.unwrap().unwrap() {
+            Message::Text(text) => serde_json::from_str::<JsonValue>(&text).unwrap(),
+            other => panic!("unexpected message: {other:?}"),
+        };
+        assert!(request["action"] == action);
+        assert!(request["topic"] == topic);
+        let ack = json!({"ack": action, "time": "1564935350664", "id": request["id"]});
+        server.send(Message::Text(ack.to_string())).await.unwrap();
+        request
+    }
+
+    fn election_json(topic: &str, hash: [u8; 32]) -> Message {
+        Message::Text(
+            json!({
+                "topic": topic,
+                "time": "1564935350664",
+                "message": {"hash": to_uppercase_hex(&hash)}
+            })
+            .to_string(),
+        )
+    }
+
+    #[tokio::test]
+    async fn reconnect_and_resubscribe() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let block = signed_block();
+
+        let server = tokio::spawn(async move {
+            let mut server = accept(&listener).await;
+            let request = expect_request(&mut server, "subscribe", "confirmation").await;
+            assert!(request["options"]["accounts"] == json!([signed_block().account.to_string()]));
+            expect_request(&mut server, "subscribe", "stopped_election").await;
+            let confirmation = confirmation_json(&signed_block()).to_string();
+            server.send(Message::Text(confirmation)).await.unwrap();
+            // drop the connection
+            drop(server);
+
+            let mut server = accept(&listener).await;
+            let request = expect_request(&mut server, "subscribe", "confirmation").await;
+            assert!(request["options"]["accounts"] == json!([signed_block().account.to_string()]));
+            expect_request(&mut server, "subscribe", "stopped_election").await;
+            server
+                .send(election_json("stopped_election", [4; 32]))
+                .await
+                .unwrap();
+            server.close(None).await.unwrap();
+        });
+
+        let mut websocket = WebSocket::connect(&url).await.unwrap();
+        websocket.set_reconnect_delay(Duration::from_millis(10));
+        websocket
+            .subscribe(Topic::Confirmation {
+                accounts: vec![block.account.clone()],
+            })
+            .await
+            .unwrap();
+        websocket.subscribe(Topic::StoppedElection).await.unwrap();
+        assert!(websocket.get_subscriptions().len() == 2);
+
+        match websocket.next_event().await.unwrap() {
+            Event::Confirmation(confirmation) => assert!(confirmation.block_info.block == block),
+            other => panic!("unexpected event: {other:?}"),
+        }
+        assert!(websocket.next_event().await.unwrap() == Event::StoppedElection([4; 32]));
+        server.await.unwrap();
+
+        // the server is gone, so reconnecting fails
+        websocket.set_reconnect_attempts(2);
+        assert!(matches!(
+            websocket.next_event().await,
+            Err(RpcError::WebSocketError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn events_before_ack() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+
+        let server = tokio::spawn(async move {
+            let mut server = accept(&listener).await;
+            let request = match server.next().await.unwrap().unwrap() {
+                Message::Text(text) => serde_json::from_str::<JsonValue>(&text).unwrap(),
+                other => panic!("unexpected message: {other:?}"),
+            };
+            // an event arrives before the acknowledgement
+            server
+                .send(election_json("started_election", [1; 32]))
+                .await
+                .unwrap();
+            let ack = json!({"ack": "subscribe", "time": "1564935350664", "id": request["id"]});
+            server.send(Message::Text(ack.to_string())).await.unwrap();
+            server
+                .send(election_json("started_election", [2; 32]))
+                .await
+                .unwrap();
+            expect_request(&mut server, "unsubscribe", "started_election").await;
+            server
+        });
+
+        let mut websocket = WebSocket::connect(&url).await.unwrap();
+        websocket.subscribe(Topic::StartedElection).await.unwrap();
+        assert!(websocket.next_event().await.unwrap() == Event::StartedElection([1; 32]));
+        assert!(websocket.next_event().await.unwrap() == Event::StartedElection([2; 32]));
+        websocket
+            .unsubscribe(&Topic::StartedElection)
+            .await
+            .unwrap();
+        assert!(websocket.get_subscriptions().is_empty());
+        let _server = server.await.unwrap();
+        websocket.close().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn wait_for_confirmation() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let block = signed_block();
+
+        // the node never reports the block as confirmed through the RPC
+        let unconfirmed = block.clone();
+        let rpc_url = mock_rpc(move |_| {
+            json!({
+                "height": "58",
+                "local_timestamp": "1564935350",
+                "confirmed": "false",
+                "contents": block_to_json(&unconfirmed),
+                "subtype": "send"
+            })
+        })
+        .await;
+        let rpc = Rpc::new(&rpc_url, None).unwrap();
+
+        let server = tokio::spawn(async move {
+            let mut server = accept(&listener).await;
+            let request = expect_request(&mut server, "subscribe", "confirmation").await;
+            assert!(request["options"]["accounts"] == json!([signed_block().account.to_string()]));
+            server
+                .send(election_json("started_election", [1; 32]))
+                .await
+                .unwrap();
+            let confirmation = confirmation_json(&signed_block()).to_string();
+            server.send(Message::Text(confirmation)).await.unwrap();
+            expect_request(&mut server, "unsubscribe", "confirmation").await;
+            server
+                .send(election_json("stopped_election", [1; 32]))
+                .await
+                .unwrap();
+            server
+        });
+
+        let mut websocket = WebSocket::connect(&url).await.unwrap();
+        let options = ConfirmationOptions {
+            timeout: Duration::from_secs(5),
+            ..Default::default()
+        };
+        let info = rpc
+            .wait_for_confirmation_websocket(&block, &mut websocket, options)
+            .await
+            .unwrap();
+        assert!(info.confirmed);
+        assert!(info.block == block);
+        assert!(websocket.get_subscriptions().is_empty());
+
+        // events received while waiting are kept
+        assert!(websocket.next_event().await.unwrap() == Event::StartedElection([1; 32]));
+        assert!(websocket.next_event().await.unwrap() == Event::StoppedElection([1; 32]));
+        let _server = server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn wait_for_confirmation_timeout() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let block = signed_block();
+
+        let unconfirmed = block.clone();
+        let rpc_url = mock_rpc(move |_| {
+            json!({
+                "height": "58",
+                "local_timestamp": "1564935350",
+                "confirmed": "false",
+                "contents": block_to_json(&unconfirmed),
+                "subtype": "send"
+            })
+        })
+        .await;
+        let rpc = Rpc::new(&rpc_url, None).unwrap();
+
+        let server = tokio::spawn(async move {
+            let mut server = accept(&listener).await;
+            // the subscription is never acknowledged, so the request is still in flight when timing out
+            let request = server.next().await.unwrap().unwrap();
+            assert!(request.to_text().unwrap().contains("subscribe"));
+            expect_request(&mut server, "unsubscribe", "confirmation").await;
+            server
+        });
+
+        let mut websocket = WebSocket::connect(&url).await.unwrap();
+        let options = ConfirmationOptions {
+            timeout: Duration::from_millis(200),
+            ..Default::default()
+        };
+        let result = rpc
+            .wait_for_confirmation_websocket(&block, &mut websocket, options)
+            .await;
+        assert!(matches!(result, Err(RpcError::Timeout)));
+        assert!(websocket.get_subscriptions().is_empty());
+        let _server = server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn wait_for_confirmation_fallback() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let block = signed_block();
+
+        // the block is confirmed on the third `block_info`, after the websocket is lost
+        let confirmed = block.clone();
+        let block_info_calls = Arc::new(AtomicUsize::new(0));
+        let block_confirm_calls = Arc::new(AtomicUsize::new(0));
+        let (info_calls, confirm_calls) = (block_info_calls.clone(), block_confirm_calls.clone());
+        let rpc_url = mock_rpc(move |request| match request["action"].as_str().unwrap() {
+            "block_confirm" => {
+                confirm_calls.fetch_add(1, Ordering::SeqCst);
+                json!({"started": "1"})
+            }
+            _ => json!({
+                "height": "58",
+                "local_timestamp": "1564935350",
+                "confirmed": (info_calls.fetch_add(1, Ordering::SeqCst) >= 2).to_string(),
+                "contents": block_to_json(&confirmed),
+                "subtype": "send"
+            }),
+        })
+        .await;
+        let rpc = Rpc::new(&rpc_url, None).unwrap();
+
+        let server = tokio::spawn(async move {
+            let mut server = accept(&listener).await;
+            expect_request(&mut server, "subscribe", "confirmation").await;
+            // drop the connection, and refuse to reconnect
+            drop(server);
+            drop(listener);
+        });
+
+        let mut websocket = WebSocket::connect(&url).await.unwrap();
+        websocket.set_reconnect_delay(Duration::from_millis(10));
+        websocket.set_reconnect_attempts(1);
+        let options = ConfirmationOptions {
+            timeout: Duration::from_secs(5),
+            poll_interval: Duration::from_millis(10),
+            block_confirm: true,
+        };
+        let info = rpc
+            .wait_for_confirmation_websocket(&block, &mut websocket, options)
+            .await
+            .unwrap();
+        assert!(info.confirmed);
+        assert!(block_info_calls.load(Ordering::SeqCst) == 3);
+        assert!(block_confirm_calls.load(Ordering::SeqCst) == 1);
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn wait_for_confirmation_unacknowledged() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let block = signed_block();
+
+        let unconfirmed = block.clone();
+        let rpc_url = mock_rpc(move |_| {
+            json!({
+                "height": "58",
+                "local_timestamp": "1564935350",
+                "confirmed": "false",
+                "contents": block_to_json(&unconfirmed),
+                "subtype": "send"
+            })
+        })
+        .await;
+        let rpc = Rpc::new(&rpc_url, None).unwrap();
+
+        let server = tokio::spawn(async move {
+            let mut server = accept(&listener).await;
+            expect_request(&mut server, "subscribe", "confirmation").await;
+            // the unsubscription is never acknowledged
+            let request = server.next().await.unwrap().unwrap();
+            assert!(request.to_text().unwrap().contains("unsubscribe"));
+            server
+        });
+
+        let mut websocket = WebSocket::connect(&url).await.unwrap();
+        let options = ConfirmationOptions {
+            timeout: Duration::from_millis(200),
+            ..Default::default()
+        };
+        let wait = rpc.wait_for_confirmation_websocket(&block, &mut websocket, options);
+        let result = tokio::time::timeout(Duration::from_secs(2), wait).await;
+        assert!(matches!(result, Ok(Err(RpcError::Timeout))));
+        assert!(websocket.get_subscriptions().is_empty());
+        let _server = server.await.unwrap();
+    }
+}
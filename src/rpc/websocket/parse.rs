@@ -0,0 +1,283 @@
+use super::{ActiveDifficulty, Confirmation, Event, VoteMessage};
+use crate::rpc::{util::*, BlockInfo, RpcError};
+use crate::Telemetry;
+use serde_json::Value as JsonValue;
+
+/// A message sent by the node
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Incoming {
+    /// Acknowledgement of the request with the given `id`
+    Ack(String),
+    Event(Event),
+}
+
+/// Returns `None` for messages of unknown topics, and for legacy blocks
+pub(crate) fn incoming(raw_json: JsonValue) -> Result<Option<Incoming>, RpcError> {
+    if !raw_json["ack"].is_null() {
+        return Ok(Some(Incoming::Ack(string_from_json(&raw_json["id"])?)));
+    }
+
+    let message = &raw_json["message"];
+    let event = match raw_json["topic"].as_str() {
+        Some("confirmation") => {
+            confirmation(message)?.map(|confirmation| Event::Confirmation(Box::new(confirmation)))
+        }
+        Some("vote") => Some(Event::Vote(Box::new(vote(message)?))),
+        Some("started_election") => {
+            Some(Event::StartedElection(bytes_from_json(&message["hash"])?))
+        }
+        Some("stopped_election") => {
+            Some(Event::StoppedElection(bytes_from_json(&message["hash"])?))
+        }
+        Some("active_difficulty") => Some(Event::ActiveDifficulty(active_difficulty(message)?)),
+        Some("telemetry") => Some(Event::Telemetry(Box::new(telemetry(message)?))),
+        _ => None,
+    };
+    Ok(event.map(Incoming::Event))
+}
+
+pub fn active_difficulty(message: &JsonValue) -> Result<ActiveDifficulty, RpcError> {
+    Ok(ActiveDifficulty {
+        network_current: bytes_from_json(&message["network_current"])?,
+        network_minimum: bytes_from_json(&message["network_minimum"])?,
+        network_receive_current: bytes_from_json(&message["network_receive_current"])?,
+        network_receive_minimum: bytes_from_json(&message["network_receive_minimum"])?,
+    })
+}
+
+/// Legacy blocks will return `None`
+pub fn confirmation(message: &JsonValue) -> Result<Option<Confirmation>, RpcError> {
+    if message["block"]["type"] != "state" {
+        return Ok(None);
+    }
+    let block = block_from_history_json(&message["block"])?;
+
+    let hash: [u8; 32] = bytes_from_json(&message["hash"])?;
+    if hash != block.hash() || account_from_json(&message["account"])? != block.account {
+        return Err(RpcError::InvalidData);
+    }
+    if !block.has_valid_signature() {
+        return Err(RpcError::InvalidData);
+    }
+
+    let sideband = &message["sideband"];
+    Ok(Some(Confirmation {
+        amount: u128_from_json(&message["amount"])?,
+        confirmation_type: string_from_json(&message["confirmation_type"])?,
+        block_info: BlockInfo {
+            height: usize_from_json(&sideband["height"])?,
+            timestamp: u64_from_json(&sideband["local_timestamp"])?,
+            confirmed: true,
+            block,
+        },
+    }))
+}
+
+pub fn telemetry(message: &JsonValue) -> Result<Telemetry, RpcError> {
+    telemetry_from_json(message)
+}
+
+pub fn vote(message: &JsonValue) -> Result<VoteMessage, RpcError> {
+    let hashes = message["blocks"]
+        .as_array()
+        .ok_or(RpcError::InvalidJsonDataType)?
+        .iter()
+        .map(bytes_from_json)
+        .collect::<Result<Vec<[u8; 32]>, RpcError>>()?;
+
+    Ok(VoteMessage {
+        representative: account_from_json(&message["account"])?,
+        signature: bytes_from_json::<64>(&message["signature"])?
+            .try_into()
+            .map_err(|_| RpcError::InvalidData)?,
+        timestamp: u64_from_json(&message["timestamp"])?,
+        hashes,
+        vote_type: string_from_json(&message["type"])?,
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::{Event, Incoming};
+    use crate::rpc::util::{block_to_json, to_uppercase_hex};
+    use crate::rpc::RpcError;
+    use crate::telemetry::{encode_node_id, tests::signed_telemetry};
+    use crate::{constants::ONE_NANO, Block, BlockType, Key, SecretBytes, Signature};
+    use serde_json::{json, Value as JsonValue};
+
+    pub(crate) fn signed_block() -> Block {
+        let key = Key::from_seed(&SecretBytes::from([3; 32]), 0);
+        let mut block = Block {
+            block_type: BlockType::Send,
+            account: key.to_account(),
+            previous: [1; 32],
+            representative: key.to_account(),
+            balance: ONE_NANO,
+            link: [2; 32],
+            signature: Signature::default(),
+            work: [0; 8],
+        };
+        block.sign(&key);
+        block
+    }
+
+    pub(crate) fn confirmation_json(block: &Block) -> JsonValue {
+        let mut json_block = block_to_json(block);
+        json_block.insert("subtype".into(), "send".into());
+        json!({
+            "topic": "confirmation",
+            "time": "1564935350664",
+            "message": {
+                "account": block.account.to_string(),
+                "amount": ONE_NANO.to_string(),
+                "hash": to_uppercase_hex(&block.hash()),
+                "confirmation_type": "active_quorum",
+                "block": json_block,
+                "sideband": {
+                    "height": "58",
+                    "local_timestamp": "1564935350"
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn ack() {
+        let ack = super::incoming(json!({"ack": "subscribe", "time": "1564935350664", "id": "7"}));
+        assert!(ack.unwrap() == Some(Incoming::Ack("7".into())));
+    }
+
+    #[test]
+    fn active_difficulty() {
+        let difficulty = super::active_difficulty(&json!({
+            "multiplier": "1.000000000000000",
+            "network_current": "fffffff800000000",
+            "network_minimum": "fffffff800000000",
+            "network_receive_current": "fffffe0000000000",
+            "network_receive_minimum": "fffffe0000000000"
+        }))
+        .unwrap();
+        assert!(difficulty.network_current == [0xff, 0xff, 0xff, 0xf8, 0, 0, 0, 0]);
+        assert!(difficulty.network_receive_minimum == [0xff, 0xff, 0xfe, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn confirmation() {
+        let block = signed_block();
+        let json = confirmation_json(&block);
+        let confirmation = super::confirmation(&json["message"]).unwrap().unwrap();
+        assert!(confirmation.block_info.block == block);
+        assert!(confirmation.block_info.height == 58);
+        assert!(confirmation.block_info.timestamp == 1564935350);
+        assert!(confirmation.block_info.confirmed);
+        assert!(confirmation.amount == ONE_NANO);
+        assert!(confirmation.confirmation_type == "active_quorum");
+
+        let event = super::incoming(json.clone()).unwrap().unwrap();
+        assert!(matches!(event, Incoming::Event(Event::Confirmation(_))));
+
+        let mut forged = json.clone();
+        forged["message"]["block"]["balance"] = "1".into();
+        assert!(matches!(
+            super::confirmation(&forged["message"]),
+            Err(RpcError::InvalidData)
+        ));
+
+        let mut legacy = json;
+        legacy["message"]["block"]["type"] = "send".into();
+        assert!(super::confirmation(&legacy["message"]).unwrap().is_none());
+    }
+
+    #[test]
+    fn election() {
+        let started = super::incoming(json!({
+            "topic": "started_election",
+            "time": "1564935350664",
+            "message": {"hash": to_uppercase_hex(&[5; 32])}
+        }));
+        assert!(started.unwrap() == Some(Incoming::Event(Event::StartedElection([5; 32]))));
+
+        let stopped = super::incoming(json!({
+            "topic": "stopped_election",
+            "time": "1564935350664",
+            "message": {"hash": to_uppercase_hex(&[6; 32])}
+        }));
+        assert!(stopped.unwrap() == Some(Incoming::Event(Event::StoppedElection([6; 32]))));
+    }
+
+    #[test]
+    fn unknown_topic() {
+        let unknown = super::incoming(json!({
+            "topic": "new_unconfirmed_block",
+            "time": "1564935350664",
+            "message": {}
+        }));
+        assert!(unknown.unwrap().is_none());
+    }
+
+    #[test]
+    fn vote() {
+        let block = signed_block();
+        let mut json = json!({
+            "account": block.account.to_string(),
+            "signature": to_uppercase_hex(&block.signature.to_bytes()),
+            "sequence": "855471574",
+            "timestamp": "18446744073709551615",
+            "duration": "15",
+            "blocks": [to_uppercase_hex(&[9; 32])],
+            "type": "vote"
+        });
+        let vote = super::vote(&json).unwrap();
+        assert!(vote.representative == block.account);
+        assert!(vote.signature == block.signature);
+        assert!(vote.timestamp == u64::MAX);
+        assert!(vote.hashes == vec![[9; 32]]);
+        assert!(vote.vote_type == "vote");
+        assert!(vote.to_vote().is_final() && vote.to_vote().hashes == vote.hashes);
+
+        json["signature"] = "00".repeat(64).into();
+        assert!(matches!(super::vote(&json), Err(RpcError::InvalidData)));
+    }
+
+    #[test]
+    fn telemetry() {
+        let key = Key::from_seed(&SecretBytes::from([3; 32]), 0);
+        let telemetry = signed_telemetry(&key);
+        let mut json = json!({
+            "topic": "telemetry",
+            "message": {
+                "block_count": "1",
+                "cemented_count": "1",
+                "unchecked_count": "3",
+                "account_count": "4",
+                "bandwidth_cap": "5",
+                "peer_count": "6",
+                "protocol_version": "21",
+                "uptime": "7",
+                "genesis_block": to_uppercase_hex(&[8; 32]),
+                "major_version": "27",
+                "minor_version": "1",
+                "patch_version": "0",
+                "pre_release_version": "0",
+                "maker": "0",
+                "timestamp": "9",
+                "active_difficulty": "0a0a0a0a0a0a0a0a",
+                "node_id": encode_node_id(&key.to_account()),
+                "signature": to_uppercase_hex(&telemetry.signature.to_bytes()),
+                "address": "::ffff:127.0.0.1",
+                "port": "7075"
+            }
+        });
+        let event = super::incoming(json.clone()).unwrap();
+        let Some(Incoming::Event(Event::Telemetry(parsed))) = event else {
+            panic!("expected telemetry event")
+        };
+        assert!(*parsed == telemetry && parsed.has_valid_signature());
+
+        json["message"]["block_count"] = "10".into();
+        let Some(Incoming::Event(Event::Telemetry(forged))) = super::incoming(json).unwrap() else {
+            panic!("expected telemetry event")
+        };
+        assert!(!forged.has_valid_signature());
+    }
+}
@@ -0,0 +1,227 @@
+use super::{checked_work, WorkSource};
+use crate::rpc::RpcError;
+use crate::{block::check_work, Account, Block};
+use futures_util::future::{BoxFuture, FutureExt};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+/// Work being generated (or already generated) for an account's next block
+#[derive(Debug)]
+struct Entry {
+    work_hash: [u8; 32],
+    work: watch::Receiver<Option<[u8; 8]>>,
+    task: JoinHandle<()>,
+}
+impl Drop for Entry {
+    fn drop(&mut self) {
+        self.task.abort()
+    }
+}
+
+/// Precomputes work in the background, so that it is ready by the time the next block is created.
+///
+/// There is one entry per account, keyed by the account's frontier (the next block's `work_hash`).
+/// Precomputing work for a new frontier invalidates the old entry, stopping its generation if needed.
+///
+/// Must be used from within a Tokio runtime.
+#[derive(Debug, Clone)]
+pub struct WorkCache {
+    source: Arc<dyn WorkSource>,
+    entries: Arc<Mutex<HashMap<Account, Entry>>>,
+}
+impl WorkCache {
+    pub fn new(source: Arc<dyn WorkSource>) -> WorkCache {
+        WorkCache {
+            source,
+            entries: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Start generating work for the account's next block, replacing any previous entry for the account.
+    ///
+    /// `work_hash` is the account's frontier, or its public key if the account has not been opened.
+    pub fn precompute(&self, account: &Account, work_hash: [u8; 32], difficulty: [u8; 8]) {
+        let (sender, receiver) = watch::channel(None);
+        let source = self.source.clone();
+        let task = tokio::spawn(async move {
+            if let Ok(work) = checked_work(source.as_ref(), work_hash, difficulty).await {
+                let _ = sender.send(Some(work));
+            }
+        });
+
+        let entry = Entry {
+            work_hash,
+            work: receiver,
+            task,
+        };
+        self.entries.lock().unwrap().insert(account.clone(), entry);
+    }
+
+    /// Same as `precompute`, using the block as the account's new frontier.
+    /// Call this right after publishing the block.
+    pub fn precompute_after(&self, block: &Block, difficulty: [u8; 8]) {
+        self.precompute(&block.account, block.hash(), difficulty)
+    }
+
+    /// Remove the account's entry, stopping its generation if needed
+    pub fn invalidate(&self, account: &Account) {
+        self.entries.lock().unwrap().remove(account);
+    }
+
+    /// Remove all entries
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+
+    /// Returns the cached work for `work_hash`, if it meets `difficulty`.
+    /// If the work is still being generated, waits for it.
+    ///
+    /// Returns `None` if there is no such entry, or if generating the work failed.
+    pub async fn cached(&self, work_hash: [u8; 32], difficulty: [u8; 8]) -> Option<[u8; 8]> {
+        let mut receiver = self
+            .entries
+            .lock()
+            .unwrap()
+            .values()
+            .find(|entry| entry.work_hash == work_hash)?
+            .work
+            .clone();
+
+        let work = (*receiver.wait_for(Option::is_some).await.ok()?)?;
+        check_work(work_hash, difficulty, work).then_some(work)
+    }
+
+    /// Returns the cached work for `work_hash` if it meets `difficulty`, and otherwise generates new work.
+    /// The returned work is always checked to meet `difficulty`.
+    pub async fn work(
+        &self,
+        work_hash: [u8; 32],
+        difficulty: [u8; 8],
+    ) -> Result<[u8; 8], RpcError> {
+        match self.cached(work_hash, difficulty).await {
+            Some(work) => Ok(work),
+            None => checked_work(self.source.as_ref(), work_hash, difficulty).await,
+        }
+    }
+}
+impl WorkSource for WorkCache {
+    fn generate_work(
+        &self,
+        work_hash: [u8; 32],
+        difficulty: [u8; 8],
+    ) -> BoxFuture<'_, Result<[u8; 8], RpcError>> {
+        self.work(work_hash, difficulty).boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::CancelOnDrop;
+    use super::*;
+    use crate::{Key, SecretBytes};
+    use futures_util::future;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    const EASY: [u8; 8] = [0xf0, 0, 0, 0, 0, 0, 0, 0];
+    const HARDER: [u8; 8] = [0xff, 0xc0, 0, 0, 0, 0, 0, 0];
+
+    /// Counts how many times work was requested, returning the lowest valid work so that tests are deterministic
+    #[derive(Debug, Default)]
+    struct CountingWork(AtomicUsize);
+    impl WorkSource for CountingWork {
+        fn generate_work(
+            &self,
+            work_hash: [u8; 32],
+            difficulty: [u8; 8],
+        ) -> BoxFuture<'_, Result<[u8; 8], RpcError>> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            let work = (0..u64::MAX)
+                .map(u64::to_be_bytes)
+                .find(|work| check_work(work_hash, difficulty, *work))
+                .ok_or(RpcError::WorkUnavailable);
+            future::ready(work).boxed()
+        }
+    }
+
+    /// Never returns, setting the flag once dropped
+    #[derive(Debug, Default)]
+    struct NoWork(Arc<AtomicBool>);
+    impl WorkSource for NoWork {
+        fn generate_work(
+            &self,
+            _: [u8; 32],
+            _: [u8; 8],
+        ) -> BoxFuture<'_, Result<[u8; 8], RpcError>> {
+            let dropped = CancelOnDrop(self.0.clone());
+            async move {
+                let _dropped = dropped;
+                future::pending().await
+            }
+            .boxed()
+        }
+    }
+
+    fn account() -> Account {
+        Key::from_seed(&SecretBytes::from([9; 32]), 0).to_account()
+    }
+
+    #[tokio::test]
+    async fn precompute() {
+        let source = Arc::new(CountingWork::default());
+        let cache = WorkCache::new(source.clone());
+        cache.precompute(&account(), [1; 32], EASY);
+
+        let work = cache.work([1; 32], EASY).await.unwrap();
+        assert!(check_work([1; 32], EASY, work));
+        assert!(cache.work([1; 32], EASY).await.unwrap() == work);
+        assert!(source.0.load(Ordering::SeqCst) == 1);
+
+        // not cached
+        cache.work([2; 32], EASY).await.unwrap();
+        assert!(source.0.load(Ordering::SeqCst) == 2);
+    }
+
+    #[tokio::test]
+    async fn difficulty_increased() {
+        let source = Arc::new(CountingWork::default());
+        let cache = WorkCache::new(source.clone());
+        cache.precompute(&account(), [1; 32], EASY);
+        let easy = cache.work([1; 32], EASY).await.unwrap();
+
+        assert!(!check_work([1; 32], HARDER, easy));
+
+        let work = cache.work([1; 32], HARDER).await.unwrap();
+        assert!(check_work([1; 32], HARDER, work));
+        assert!(source.0.load(Ordering::SeqCst) == 2);
+    }
+
+    #[tokio::test]
+    async fn frontier_changed() {
+        let source = Arc::new(CountingWork::default());
+        let cache = WorkCache::new(source.clone());
+        cache.precompute(&account(), [1; 32], EASY);
+        cache.precompute(&account(), [2; 32], EASY);
+        assert!(cache.cached([1; 32], EASY).await.is_none());
+        assert!(cache.cached([2; 32], EASY).await.is_some());
+
+        cache.invalidate(&account());
+        assert!(cache.cached([2; 32], EASY).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn generation_stopped() {
+        let source = Arc::new(NoWork::default());
+        let dropped = source.0.clone();
+        let cache = WorkCache::new(source);
+        cache.precompute(&account(), [1; 32], EASY);
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!dropped.load(Ordering::SeqCst));
+
+        cache.clear();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(dropped.load(Ordering::SeqCst));
+    }
+}
@@ -0,0 +1,331 @@
+//! Sources of proof-of-work.
+//!
+//! Code which needs work can take a `&dyn WorkSource`, without caring where the work comes from.
+
+mod cache;
+
+#[cfg(feature = "work-server")]
+pub mod server;
+
+pub use cache::WorkCache;
+
+use super::{encode, parse, Rpc, RpcError};
+use crate::block::check_work;
+use crate::nanopy::get_local_work_cancellable;
+use futures_util::future::{self, BoxFuture, FutureExt};
+use json::{Map, Value as JsonValue};
+use reqwest::Client;
+use serde_json as json;
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use tokio::sync::oneshot;
+
+/// Something which can generate work
+pub trait WorkSource: Debug + Send + Sync {
+    /// Generate work for the given work hash (see `Block::work_hash`), meeting `difficulty`
+    fn generate_work(
+        &self,
+        work_hash: [u8; 32],
+        difficulty: [u8; 8],
+    ) -> BoxFuture<'_, Result<[u8; 8], RpcError>>;
+}
+
+/// Generate work, returning an error if it does not meet `difficulty`
+pub(crate) async fn checked_work(
+    source: &dyn WorkSource,
+    work_hash: [u8; 32],
+    difficulty: [u8; 8],
+) -> Result<[u8; 8], RpcError> {
+    let work = source.generate_work(work_hash, difficulty).await?;
+    match check_work(work_hash, difficulty, work) {
+        true => Ok(work),
+        false => Err(RpcError::InvalidData),
+    }
+}
+
+/// Generates work using the local CPU (likely very slow), in a separate thread.
+///
+/// If the returned future is dropped, the thread will stop.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LocalWork;
+impl WorkSource for LocalWork {
+    fn generate_work(
+        &self,
+        work_hash: [u8; 32],
+        difficulty: [u8; 8],
+    ) -> BoxFuture<'_, Result<[u8; 8], RpcError>> {
+        let cancel = CancelOnDrop(Arc::new(AtomicBool::new(false)));
+        let thread_cancel = cancel.0.clone();
+        let (sender, receiver) = oneshot::channel();
+        thread::spawn(move || {
+            if let Some(work) = get_local_work_cancellable(work_hash, difficulty, &thread_cancel) {
+                let _ = sender.send(work);
+            }
+        });
+
+        async move {
+            let _cancel = cancel;
+            receiver.await.map_err(|_| RpcError::WorkUnavailable)
+        }
+        .boxed()
+    }
+}
+
+/// Sets the flag when dropped
+struct CancelOnDrop(Arc<AtomicBool>);
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Relaxed)
+    }
+}
+
+/// Uses the node's `work_generate`
+impl WorkSource for Rpc {
+    fn generate_work(
+        &self,
+        work_hash: [u8; 32],
+        difficulty: [u8; 8],
+    ) -> BoxFuture<'_, Result<[u8; 8], RpcError>> {
+        self.work_generate(work_hash, Some(difficulty)).boxed()
+    }
+}
+
+/// A remote work server (such as a dPoW or BoomPoW-style server, or `nano-work-server`),
+/// which accepts `work_generate` requests over HTTP
+#[derive(Debug, Clone)]
+pub struct WorkServer {
+    client: Client,
+    url: String,
+    /// Extra fields sent with each request
+    credentials: Map<String, JsonValue>,
+}
+impl WorkServer {
+    pub fn new(url: &str) -> Result<WorkServer, RpcError> {
+        reqwest::Url::parse(url).map_err(|_| RpcError::InvalidRPC)?;
+        Ok(WorkServer {
+            client: Client::builder().build()?,
+            url: url.into(),
+            credentials: Map::new(),
+        })
+    }
+
+    /// Get the URL of this work server
+    pub fn get_url(&self) -> &str {
+        &self.url
+    }
+
+    /// Set the `user` and `api_key` fields sent with each request, as required by dPoW and BoomPoW
+    pub fn set_credentials(&mut self, user: &str, api_key: &str) {
+        self.credentials.insert("user".into(), user.into());
+        self.credentials.insert("api_key".into(), api_key.into());
+    }
+
+    async fn request(&self, work_hash: [u8; 32], difficulty: [u8; 8]) -> Result<[u8; 8], RpcError> {
+        let mut request = encode::work_generate(work_hash, Some(difficulty));
+        if let JsonValue::Object(arguments) = &mut request {
+            arguments.remove("use_peers");
+            arguments.extend(self.credentials.clone());
+        }
+
+        let response = self
+            .client
+            .post(&self.url)
+            .json(&request)
+            .send()
+            .await?
+            .json::<JsonValue>()
+            .await?;
+        parse::work_generate(response, work_hash, Some(difficulty))
+    }
+}
+impl WorkSource for WorkServer {
+    fn generate_work(
+        &self,
+        work_hash: [u8; 32],
+        difficulty: [u8; 8],
+    ) -> BoxFuture<'_, Result<[u8; 8], RpcError>> {
+        self.request(work_hash, difficulty).boxed()
+    }
+}
+
+/// How `CompositeWork` uses its sources
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorkStrategy {
+    /// Ask all sources at once, using the first valid work (the others are dropped)
+    Race,
+    /// Ask each source in order, moving on to the next one if it fails
+    Fallback,
+}
+
+/// Combines multiple work sources.
+///
+/// All work is checked to meet the difficulty, no matter which source it comes from.
+#[derive(Debug)]
+pub struct CompositeWork {
+    sources: Vec<Box<dyn WorkSource>>,
+    strategy: WorkStrategy,
+}
+impl CompositeWork {
+    pub fn new(sources: Vec<Box<dyn WorkSource>>, strategy: WorkStrategy) -> CompositeWork {
+        CompositeWork { sources, strategy }
+    }
+
+    pub fn get_strategy(&self) -> WorkStrategy {
+        self.strategy
+    }
+
+    async fn race(&self, work_hash: [u8; 32], difficulty: [u8; 8]) -> Result<[u8; 8], RpcError> {
+        if self.sources.is_empty() {
+            return Err(RpcError::WorkUnavailable);
+        }
+        let attempts = self
+            .sources
+            .iter()
+            .map(|source| checked_work(source.as_ref(), work_hash, difficulty).boxed());
+        let (work, _) = future::select_ok(attempts).await?;
+        Ok(work)
+    }
+
+    async fn fallback(
+        &self,
+        work_hash: [u8; 32],
+        difficulty: [u8; 8],
+    ) -> Result<[u8; 8], RpcError> {
+        let mut last_error = RpcError::WorkUnavailable;
+        for source in &self.sources {
+            match checked_work(source.as_ref(), work_hash, difficulty).await {
+                Ok(work) => return Ok(work),
+                Err(err) => last_error = err,
+            }
+        }
+        Err(last_error)
+    }
+}
+impl WorkSource for CompositeWork {
+    fn generate_work(
+        &self,
+        work_hash: [u8; 32],
+        difficulty: [u8; 8],
+    ) -> BoxFuture<'_, Result<[u8; 8], RpcError>> {
+        match self.strategy {
+            WorkStrategy::Race => self.race(work_hash, difficulty).boxed(),
+            WorkStrategy::Fallback => self.fallback(work_hash, difficulty).boxed(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::mock::mock_rpc;
+    use serde_json::json;
+
+    const HASH: [u8; 32] = [7; 32];
+    const EASY: [u8; 8] = [0xf0, 0, 0, 0, 0, 0, 0, 0];
+    const IMPOSSIBLE: [u8; 8] = [0xff; 8];
+
+    /// Always returns the same work, valid or not
+    #[derive(Debug)]
+    struct FixedWork([u8; 8]);
+    impl WorkSource for FixedWork {
+        fn generate_work(
+            &self,
+            _: [u8; 32],
+            _: [u8; 8],
+        ) -> BoxFuture<'_, Result<[u8; 8], RpcError>> {
+            future::ready(Ok(self.0)).boxed()
+        }
+    }
+
+    /// Never returns
+    #[derive(Debug)]
+    struct NoWork;
+    impl WorkSource for NoWork {
+        fn generate_work(
+            &self,
+            _: [u8; 32],
+            _: [u8; 8],
+        ) -> BoxFuture<'_, Result<[u8; 8], RpcError>> {
+            future::pending().boxed()
+        }
+    }
+
+    #[tokio::test]
+    async fn local() {
+        let work = LocalWork.generate_work(HASH, EASY).await.unwrap();
+        assert!(check_work(HASH, EASY, work));
+    }
+
+    #[tokio::test]
+    async fn work_server() {
+        let valid = LocalWork.generate_work(HASH, EASY).await.unwrap();
+        let url = mock_rpc(move |request| {
+            assert!(request["user"] == "user" && request["api_key"] == "key");
+            assert!(request["hash"] == "07".repeat(32));
+            assert!(request["use_peers"].is_null());
+            json!({"work": hex::encode(valid), "hash": request["hash"]})
+        })
+        .await;
+
+        let mut server = WorkServer::new(&url).unwrap();
+        server.set_credentials("user", "key");
+        assert!(server.generate_work(HASH, EASY).await.unwrap() == valid);
+        assert!(matches!(
+            server.generate_work(HASH, IMPOSSIBLE).await,
+            Err(RpcError::InvalidData)
+        ));
+    }
+
+    #[tokio::test]
+    async fn fallback() {
+        let invalid = (0..=u64::MAX)
+            .map(u64::to_be_bytes)
+            .find(|work| !check_work(HASH, EASY, *work))
+            .unwrap();
+        let composite = CompositeWork::new(
+            vec![Box::new(FixedWork(invalid)), Box::new(LocalWork)],
+            WorkStrategy::Fallback,
+        );
+        let work = composite.generate_work(HASH, EASY).await.unwrap();
+        assert!(work != invalid);
+        assert!(check_work(HASH, EASY, work));
+
+        let composite =
+            CompositeWork::new(vec![Box::new(FixedWork(invalid))], WorkStrategy::Fallback);
+        assert!(matches!(
+            composite.generate_work(HASH, EASY).await,
+            Err(RpcError::InvalidData)
+        ));
+    }
+
+    #[tokio::test]
+    async fn race() {
+        let composite = CompositeWork::new(
+            vec![Box::new(NoWork), Box::new(LocalWork)],
+            WorkStrategy::Race,
+        );
+        let work = composite.generate_work(HASH, EASY).await.unwrap();
+        assert!(check_work(HASH, EASY, work));
+
+        // whichever source finishes first is used, and the other is dropped
+        let valid = LocalWork.generate_work(HASH, EASY).await.unwrap();
+        let composite = CompositeWork::new(
+            vec![Box::new(LocalWork), Box::new(FixedWork(valid))],
+            WorkStrategy::Race,
+        );
+        assert!(composite.generate_work(HASH, EASY).await.unwrap() == valid);
+    }
+
+    #[tokio::test]
+    async fn no_sources() {
+        for strategy in [WorkStrategy::Race, WorkStrategy::Fallback] {
+            let composite = CompositeWork::new(vec![], strategy);
+            assert!(matches!(
+                composite.generate_work(HASH, EASY).await,
+                Err(RpcError::WorkUnavailable)
+            ));
+        }
+    }
+}
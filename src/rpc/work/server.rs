@@ -0,0 +1,255 @@
+//! Serves the node's `work_generate`, `work_cancel` and `work_validate` actions over HTTP,
+//! so that `Rpc::work_generate` can be pointed at it instead of a node.
+
+use super::{checked_work, WorkSource};
+use crate::rpc::parse::{self, WorkRequest};
+use crate::rpc::{encode, RpcError};
+use crate::Network;
+use futures_util::future::{self, Either};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{header, Body, Request, Response, Server};
+use json::Value as JsonValue;
+use serde_json as json;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use tokio::sync::watch;
+
+/// Used to cancel the requests for each work hash
+type Pending = Arc<Mutex<HashMap<[u8; 32], Arc<watch::Sender<bool>>>>>;
+
+/// Handles work requests, using the given work source
+#[derive(Debug, Clone)]
+pub struct WorkService {
+    source: Arc<dyn WorkSource>,
+    network: Network,
+    difficulty: [u8; 8],
+    pending: Pending,
+}
+impl WorkService {
+    pub fn new(source: Arc<dyn WorkSource>) -> WorkService {
+        let network = Network::live();
+        WorkService {
+            source,
+            difficulty: network.send_work_difficulty,
+            network,
+            pending: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Set the network whose thresholds `work_validate` checks against (default: `Network::live()`).
+    ///
+    /// This also resets the default difficulty to the network's `send_work_difficulty`.
+    pub fn set_network(&mut self, network: Network) {
+        self.difficulty = network.send_work_difficulty;
+        self.network = network
+    }
+
+    /// Set the difficulty used when a request does not specify one (default: the network's `send_work_difficulty`)
+    pub fn set_default_difficulty(&mut self, difficulty: [u8; 8]) {
+        self.difficulty = difficulty
+    }
+
+    /// Serve requests on the listener, until an error occurs
+    pub async fn serve(self, listener: TcpListener) -> Result<(), RpcError> {
+        let make_service = make_service_fn(move |_| {
+            let service = self.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| {
+                    let service = service.clone();
+                    async move { service.respond(request).await }
+                }))
+            }
+        });
+        Server::from_tcp(listener)?.serve(make_service).await?;
+        Ok(())
+    }
+
+    async fn respond(&self, request: Request<Body>) -> Result<Response<Body>, hyper::Error> {
+        let body = hyper::body::to_bytes(request.into_body()).await?;
+        let response = match json::from_slice(&body) {
+            Ok(request) => self.handle(request).await,
+            Err(_) => encode::error_response("Unable to parse JSON"),
+        };
+        Ok(Response::builder()
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(response.to_string()))
+            .unwrap())
+    }
+
+    /// Handle a single request, returning the response
+    pub async fn handle(&self, request: JsonValue) -> JsonValue {
+        match parse::work_request(&request) {
+            Ok(WorkRequest::Generate {
+                work_hash,
+                custom_difficulty,
+            }) => {
+                let difficulty = custom_difficulty.unwrap_or(self.difficulty);
+                self.work_generate(work_hash, difficulty).await
+            }
+            Ok(WorkRequest::Cancel { work_hash }) => self.work_cancel(work_hash),
+            Ok(WorkRequest::Validate {
+                work_hash,
+                work,
+                custom_difficulty,
+            }) => encode::work_validate_response(work_hash, work, custom_difficulty, &self.network),
+            Err(message) => encode::error_response(message),
+        }
+    }
+
+    async fn work_generate(&self, work_hash: [u8; 32], difficulty: [u8; 8]) -> JsonValue {
+        let sender = self
+            .pending
+            .lock()
+            .unwrap()
+            .entry(work_hash)
+            .or_insert_with(|| Arc::new(watch::channel(false).0))
+            .clone();
+        let mut cancelled = sender.subscribe();
+
+        let generate = Box::pin(checked_work(self.source.as_ref(), work_hash, difficulty));
+        let cancel = Box::pin(cancelled.wait_for(|cancelled| *cancelled));
+        let result = match future::select(generate, cancel).await {
+            Either::Left((result, _)) => Some(result),
+            Either::Right(_) => None,
+        };
+
+        let mut pending = self.pending.lock().unwrap();
+        if pending
+            .get(&work_hash)
+            .is_some_and(|current| Arc::ptr_eq(current, &sender))
+            && Arc::strong_count(&sender) == 2
+        {
+            pending.remove(&work_hash);
+        }
+        drop(pending);
+
+        match result {
+            Some(Ok(work)) => encode::work_generate_response(work_hash, work, self.difficulty),
+            Some(Err(_)) => encode::error_response("Failed to generate work"),
+            None => encode::error_response("Cancelled"),
+        }
+    }
+
+    fn work_cancel(&self, work_hash: [u8; 32]) -> JsonValue {
+        if let Some(sender) = self.pending.lock().unwrap().remove(&work_hash) {
+            sender.send_replace(true);
+        }
+        encode::work_cancel_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::LocalWork;
+    use super::*;
+    use crate::block::{check_work, get_work_difficulty};
+    use crate::constants::SEND_WORK_DIFFICULTY;
+    use crate::rpc::util::{bytes_from_json, to_uppercase_hex};
+    use crate::rpc::Rpc;
+    use json::json;
+    use std::time::Duration;
+
+    const HASH: [u8; 32] = [3; 32];
+    const EASY: [u8; 8] = [0xf0, 0, 0, 0, 0, 0, 0, 0];
+
+    async fn start(service: WorkService) -> Rpc {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(service.serve(listener));
+        Rpc::new(&url, None).unwrap()
+    }
+
+    #[tokio::test]
+    async fn work_generate() {
+        let rpc = start(WorkService::new(Arc::new(LocalWork))).await;
+        let work = rpc.work_generate(HASH, Some(EASY)).await.unwrap();
+        assert!(check_work(HASH, EASY, work));
+
+        let mut service = WorkService::new(Arc::new(LocalWork));
+        service.set_default_difficulty(EASY);
+        let response = service
+            .handle(json!({"action": "work_generate", "hash": to_uppercase_hex(&HASH)}))
+            .await;
+        // the node returns the difficulty of the work itself
+        let work: [u8; 8] = bytes_from_json(&response["work"]).unwrap();
+        assert!(response["difficulty"] == hex::encode(get_work_difficulty(HASH, work)));
+    }
+
+    #[tokio::test]
+    async fn work_validate() {
+        let rpc = start(WorkService::new(Arc::new(LocalWork))).await;
+        let work = rpc.work_generate(HASH, Some(EASY)).await.unwrap();
+
+        assert!(rpc.work_validate(HASH, work, Some(EASY)).await.unwrap());
+        let achieved = get_work_difficulty(HASH, work);
+        let harder = (u64::from_be_bytes(achieved) + 1).to_be_bytes();
+        assert!(!rpc.work_validate(HASH, work, Some(harder)).await.unwrap());
+        assert!(
+            rpc.work_validate(HASH, work, None).await.unwrap()
+                == (achieved >= SEND_WORK_DIFFICULTY)
+        );
+    }
+
+    #[tokio::test]
+    async fn work_validate_network() {
+        let network = Network::dev(crate::constants::get_genesis_account());
+        let mut service = WorkService::new(Arc::new(LocalWork));
+        service.set_network(network.clone());
+
+        let response = service
+            .handle(json!({"action": "work_generate", "hash": to_uppercase_hex(&HASH)}))
+            .await;
+        let work: [u8; 8] = bytes_from_json(&response["work"]).unwrap();
+        assert!(check_work(HASH, network.send_work_difficulty, work));
+
+        let response = service
+            .handle(json!({
+                "action": "work_validate",
+                "hash": to_uppercase_hex(&HASH),
+                "work": hex::encode(work)
+            }))
+            .await;
+        assert!(response["valid_all"] == "1" && response["valid_receive"] == "1");
+    }
+
+    #[tokio::test]
+    async fn work_cancel() {
+        let service = WorkService::new(Arc::new(LocalWork));
+        let rpc = start(service.clone()).await;
+
+        let impossible = tokio::spawn({
+            let service = service.clone();
+            async move {
+                service
+                    .handle(json!({
+                        "action": "work_generate",
+                        "hash": to_uppercase_hex(&HASH),
+                        "difficulty": "ffffffffffffffff"
+                    }))
+                    .await
+            }
+        });
+        while service.pending.lock().unwrap().is_empty() {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        rpc.work_cancel(HASH).await.unwrap();
+        assert!(impossible.await.unwrap() == json!({"error": "Cancelled"}));
+        assert!(service.pending.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn bad_requests() {
+        let service = WorkService::new(Arc::new(LocalWork));
+        let bad_hash = service
+            .handle(json!({"action": "work_generate", "hash": "1234"}))
+            .await;
+        assert!(bad_hash == json!({"error": "Bad block hash"}));
+
+        let unknown = service
+            .handle(json!({"action": "account_info", "hash": to_uppercase_hex(&HASH)}))
+            .await;
+        assert!(unknown == json!({"error": "Unknown command"}));
+    }
+}
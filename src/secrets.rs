@@ -30,6 +30,13 @@ macro_rules! scalar {
     }};
 }
 
+/// Get random bytes from the operating system
+pub(crate) fn random_bytes<const N: usize>() -> SecretBytes<N> {
+    let mut bytes = SecretBytes::from([0; N]);
+    getrandom::getrandom(bytes.as_mut()).expect("could not get random bytes from the OS");
+    bytes
+}
+
 /// A wrapper for `[u8; N]` that automatically calls `zeroize` when dropped
 #[derive(Clone, Zeroize, ZeroizeOnDrop, PartialEq, Eq)]
 pub struct SecretBytes<const N: usize> {
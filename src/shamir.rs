@@ -0,0 +1,252 @@
+//! Shamir secret sharing of seeds, for backups.
+//!
+//! A seed is split into `count` shares, any `threshold` of which can recover it.
+//! Fewer shares reveal nothing about the seed. Each byte of the seed is shared separately, over GF(256).
+//!
+//! Shares can be written down as text: `share_` followed by 64 base32 characters, encoding the following:
+//!
+//! | Bytes | Contents                                                  |
+//! |-------|-----------------------------------------------------------|
+//! | 2     | Random identifier, shared by all shares of the same seed  |
+//! | 1     | Threshold                                                 |
+//! | 1     | Index of the share (from `1` to `count`)                  |
+//! | 32    | Share data                                                |
+//! | 4     | Checksum: the first 4 bytes of `blake2b256` of the above  |
+
+use crate::base32;
+use crate::hashes::blake2b256;
+use crate::secrets::random_bytes;
+use crate::{NanoError, SecretBytes};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+/// Prefix of shares, when encoded as text
+pub const SHARE_PREFIX: &str = "share_";
+
+const ENCODED_LENGTH: usize = 40;
+
+/// Multiply in GF(256), in constant time
+fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut result = 0;
+    for _ in 0..8 {
+        result ^= a & (b & 1).wrapping_neg();
+        let carry = (a >> 7).wrapping_neg();
+        a = (a << 1) ^ (0x1b & carry);
+        b >>= 1;
+    }
+    result
+}
+
+/// Invert in GF(256), in constant time (`a^254`)
+fn gf_inv(a: u8) -> u8 {
+    let mut result = 1;
+    for _ in 0..7 {
+        result = gf_mul(gf_mul(result, result), a);
+    }
+    gf_mul(result, result)
+}
+
+/// One share of a seed
+#[derive(Debug, Clone, PartialEq, Eq, Zeroize, ZeroizeOnDrop)]
+pub struct SeedShare {
+    /// Random identifier, shared by all shares of the same seed
+    pub identifier: [u8; 2],
+    /// The number of shares needed to recover the seed
+    pub threshold: u8,
+    /// Index of the share, from `1` to the number of shares
+    pub index: u8,
+    pub data: SecretBytes<32>,
+}
+impl SeedShare {
+    /// Encode as text (`share_...`)
+    pub fn to_text(&self) -> Zeroizing<String> {
+        let mut bytes = Zeroizing::new([0; ENCODED_LENGTH]);
+        bytes[..2].copy_from_slice(&self.identifier);
+        bytes[2] = self.threshold;
+        bytes[3] = self.index;
+        bytes[4..36].copy_from_slice(self.data.as_slice());
+        let checksum = blake2b256(&bytes[..36]);
+        bytes[36..].copy_from_slice(&checksum.as_slice()[..4]);
+
+        let encoded = Zeroizing::new(base32::encode(bytes.as_slice()));
+        Zeroizing::new([SHARE_PREFIX, &encoded].concat())
+    }
+
+    /// Decode from text (`share_...`).
+    ///
+    /// Returns `NanoError::InvalidShareText` if the length, prefix, encoding or checksum is wrong,
+    /// or if the threshold or index is zero.
+    pub fn from_text(text: &str) -> Result<SeedShare, NanoError> {
+        let text = text.trim();
+        if text.len() != SHARE_PREFIX.len() + ENCODED_LENGTH * 8 / 5 {
+            return Err(NanoError::InvalidShareText);
+        }
+        if text.get(..SHARE_PREFIX.len()) != Some(SHARE_PREFIX) {
+            return Err(NanoError::InvalidShareText);
+        }
+        let bytes =
+            base32::decode(&text[SHARE_PREFIX.len()..]).ok_or(NanoError::InvalidShareText)?;
+        let bytes = Zeroizing::new(bytes);
+
+        let checksum = blake2b256(&bytes[..36]);
+        if bytes[36..] != checksum.as_slice()[..4] || bytes[2] == 0 || bytes[3] == 0 {
+            return Err(NanoError::InvalidShareText);
+        }
+        let mut data = SecretBytes::from([0; 32]);
+        data.as_mut().copy_from_slice(&bytes[4..36]);
+        Ok(SeedShare {
+            identifier: [bytes[0], bytes[1]],
+            threshold: bytes[2],
+            index: bytes[3],
+            data,
+        })
+    }
+}
+
+/// Split the seed into `count` shares, any `threshold` of which can recover it with `combine_shares()`
+pub fn split_seed(
+    seed: &SecretBytes<32>,
+    threshold: u8,
+    count: u8,
+) -> Result<Vec<SeedShare>, NanoError> {
+    if threshold == 0 || threshold > count {
+        return Err(NanoError::InvalidThreshold);
+    }
+    let identifier: [u8; 2] = random_bytes::<2>().as_bytes().to_owned();
+    let mut coefficients = vec![seed.clone()];
+    coefficients.extend((1..threshold).map(|_| random_bytes::<32>()));
+
+    let shares = (1..=count)
+        .map(|index| {
+            let mut data = SecretBytes::from([0; 32]);
+            for coefficient in coefficients.iter().rev() {
+                for (byte, c) in data.as_mut().iter_mut().zip(coefficient.as_slice()) {
+                    *byte = gf_mul(*byte, index) ^ c;
+                }
+            }
+            SeedShare {
+                identifier,
+                threshold,
+                index,
+                data,
+            }
+        })
+        .collect();
+    Ok(shares)
+}
+
+/// Recover the seed from at least `threshold` of its shares.
+///
+/// Returns `NanoError::InvalidKeySet` if there are too few shares, if any are duplicated,
+/// or if they do not belong to the same seed, and `NanoError::InvalidThreshold` if their threshold is zero.
+pub fn combine_shares(shares: &[SeedShare]) -> Result<SecretBytes<32>, NanoError> {
+    let first = shares.first().ok_or(NanoError::InvalidKeySet)?;
+    if first.threshold == 0 {
+        return Err(NanoError::InvalidThreshold);
+    }
+    let mut indexes: Vec<u8> = shares.iter().map(|share| share.index).collect();
+    indexes.sort();
+    indexes.dedup();
+    if shares.len() < first.threshold as usize
+        || indexes.len() != shares.len()
+        || indexes.contains(&0)
+        || shares
+            .iter()
+            .any(|share| share.identifier != first.identifier || share.threshold != first.threshold)
+    {
+        return Err(NanoError::InvalidKeySet);
+    }
+
+    let shares = &shares[..first.threshold as usize];
+    let mut seed = SecretBytes::from([0; 32]);
+    for share in shares {
+        // the Lagrange coefficient of this share, at zero
+        let mut lagrange = 1;
+        for other in shares.iter().filter(|other| other.index != share.index) {
+            lagrange = gf_mul(
+                lagrange,
+                gf_mul(other.index, gf_inv(other.index ^ share.index)),
+            );
+        }
+        for (byte, data) in seed.as_mut().iter_mut().zip(share.data.as_slice()) {
+            *byte ^= gf_mul(lagrange, *data);
+        }
+    }
+    Ok(seed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seed() -> SecretBytes<32> {
+        SecretBytes::from([17; 32])
+    }
+
+    #[test]
+    fn galois_field() {
+        assert!(gf_mul(0x53, 0xca) == 1);
+        assert!((1..=255).all(|a| gf_mul(a, gf_inv(a)) == 1));
+    }
+
+    #[test]
+    fn split_and_combine() {
+        let shares = split_seed(&seed(), 3, 5).unwrap();
+        assert!(shares.len() == 5);
+        assert!(shares.iter().all(|share| share.data != seed()));
+
+        for subset in [[0, 1, 2], [4, 2, 0], [1, 3, 4]] {
+            let subset: Vec<SeedShare> = subset.iter().map(|i| shares[*i].clone()).collect();
+            assert!(combine_shares(&subset).unwrap() == seed());
+        }
+        assert!(combine_shares(&shares).unwrap() == seed());
+
+        let too_few = [shares[0].clone(), shares[1].clone()];
+        assert!(combine_shares(&too_few).unwrap_err() == NanoError::InvalidKeySet);
+        let duplicated = [shares[0].clone(), shares[0].clone(), shares[1].clone()];
+        assert!(combine_shares(&duplicated).unwrap_err() == NanoError::InvalidKeySet);
+        let mut other = split_seed(&seed(), 3, 5).unwrap().remove(2);
+        other.identifier = [!shares[0].identifier[0], shares[0].identifier[1]];
+        let mixed = [shares[0].clone(), shares[1].clone(), other];
+        assert!(combine_shares(&mixed).unwrap_err() == NanoError::InvalidKeySet);
+
+        let single = split_seed(&seed(), 1, 2).unwrap();
+        assert!(single.iter().all(|share| share.data == seed()));
+        assert!(split_seed(&seed(), 0, 2).unwrap_err() == NanoError::InvalidThreshold);
+        assert!(split_seed(&seed(), 3, 2).unwrap_err() == NanoError::InvalidThreshold);
+    }
+
+    #[test]
+    fn text() {
+        let share = split_seed(&seed(), 2, 3).unwrap().remove(1);
+        let text = share.to_text();
+        assert!(text.starts_with(SHARE_PREFIX) && text.len() == 70);
+        assert!(SeedShare::from_text(&text).unwrap() == share);
+
+        let mut typo = text.to_string();
+        let last = if typo.ends_with('1') { "3" } else { "1" };
+        typo.replace_range(69.., last);
+        assert!(SeedShare::from_text(&typo).unwrap_err() == NanoError::InvalidShareText);
+        assert!(SeedShare::from_text(&text[1..]).unwrap_err() == NanoError::InvalidShareText);
+        let mut not_base32 = text.to_string();
+        not_base32.replace_range(69.., "l");
+        assert!(SeedShare::from_text(&not_base32).unwrap_err() == NanoError::InvalidShareText);
+        let wrong_prefix = text.replacen("share_", "shard_", 1);
+        assert!(SeedShare::from_text(&wrong_prefix).unwrap_err() == NanoError::InvalidShareText);
+    }
+
+    #[test]
+    fn zero_threshold_or_index() {
+        let mut shares = split_seed(&seed(), 2, 3).unwrap();
+        for share in &mut shares {
+            share.threshold = 0;
+        }
+        assert!(combine_shares(&shares).unwrap_err() == NanoError::InvalidThreshold);
+        assert!(
+            SeedShare::from_text(&shares[0].to_text()).unwrap_err() == NanoError::InvalidShareText
+        );
+
+        let mut share = split_seed(&seed(), 2, 3).unwrap().remove(0);
+        share.index = 0;
+        assert!(SeedShare::from_text(&share.to_text()).unwrap_err() == NanoError::InvalidShareText);
+    }
+}
@@ -0,0 +1,215 @@
+//! Telemetry which nodes sign with their node ID, as carried by `telemetry_ack` messages,
+//! the `telemetry` RPC action and the node's WebSocket `telemetry` topic.
+
+use crate::nanopy::{account_decode_with_prefix, account_encode_with_prefix};
+use crate::p2p::{MessageError, Reader};
+use crate::{Account, Key, NanoError, Signature};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Prefix of node IDs, when encoded as a string
+pub const NODE_ID_PREFIX: &str = "node_";
+
+/// Encode a node ID as a string (e.g. `node_1abc...`)
+pub fn encode_node_id(node_id: &Account) -> String {
+    account_encode_with_prefix(&node_id.compressed, NODE_ID_PREFIX)
+}
+
+/// Decode a node ID from a string (e.g. `node_1abc...`)
+pub fn decode_node_id(node_id: &str) -> Result<Account, NanoError> {
+    Account::try_from(account_decode_with_prefix(node_id, NODE_ID_PREFIX)?)
+}
+
+/// Telemetry of a single node, signed with its node ID
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Telemetry {
+    /// The node's node ID
+    pub node_id: Account,
+    /// Signature of the `signed_bytes()`, made with the node ID
+    pub signature: Signature,
+    pub block_count: u64,
+    pub cemented_count: u64,
+    pub unchecked_count: u64,
+    pub account_count: u64,
+    pub bandwidth_cap: u64,
+    pub peer_count: u32,
+    pub protocol_version: u8,
+    /// Uptime, in seconds
+    pub uptime: u64,
+    pub genesis_block: [u8; 32],
+    pub major_version: u8,
+    pub minor_version: u8,
+    pub patch_version: u8,
+    pub pre_release_version: u8,
+    pub maker: u8,
+    /// Timestamp, in milliseconds
+    pub timestamp: u64,
+    pub active_difficulty: [u8; 8],
+    /// Any extra data, added by newer versions of the node.
+    ///
+    /// This is signed, but is not included in the node's JSON,
+    /// so the signature of telemetry parsed from JSON is only valid if there is no extra data.
+    pub unknown_data: Vec<u8>,
+}
+impl Telemetry {
+    /// Length of the known fields, in the binary format
+    pub const LENGTH: usize = 202;
+
+    /// The bytes which are signed: every field other than `signature`, in the binary format
+    pub fn signed_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(Telemetry::LENGTH - 64 + self.unknown_data.len());
+        bytes.extend_from_slice(self.node_id.compressed.as_bytes());
+        self.encode_fields(&mut bytes);
+        bytes
+    }
+
+    /// Set the `node_id` to that of the key, and sign the telemetry
+    pub fn sign(&mut self, key: &Key) {
+        self.node_id = key.to_account();
+        self.signature = key.sign_message(&self.signed_bytes());
+    }
+
+    /// Check if the node ID's signature for this telemetry is valid
+    pub fn has_valid_signature(&self) -> bool {
+        self.node_id
+            .is_valid_signature(&self.signed_bytes(), &self.signature)
+    }
+
+    /// Encode as the payload of a `telemetry_ack` message
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(Telemetry::LENGTH + self.unknown_data.len());
+        bytes.extend_from_slice(self.node_id.compressed.as_bytes());
+        bytes.extend_from_slice(&self.signature.to_bytes());
+        self.encode_fields(&mut bytes);
+        bytes
+    }
+
+    /// Decode the payload of a `telemetry_ack` message.
+    /// Any bytes after the known fields are kept in `unknown_data`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Telemetry, MessageError> {
+        let mut reader = Reader(bytes);
+        let u64 = |reader: &mut Reader| reader.take().map(u64::from_be_bytes);
+        let u8 = |reader: &mut Reader| reader.take().map(|[byte]: [u8; 1]| byte);
+        Ok(Telemetry {
+            node_id: reader.account()?,
+            signature: reader.signature()?,
+            block_count: u64(&mut reader)?,
+            cemented_count: u64(&mut reader)?,
+            unchecked_count: u64(&mut reader)?,
+            account_count: u64(&mut reader)?,
+            bandwidth_cap: u64(&mut reader)?,
+            peer_count: u32::from_be_bytes(reader.take()?),
+            protocol_version: u8(&mut reader)?,
+            uptime: u64(&mut reader)?,
+            genesis_block: reader.take()?,
+            major_version: u8(&mut reader)?,
+            minor_version: u8(&mut reader)?,
+            patch_version: u8(&mut reader)?,
+            pre_release_version: u8(&mut reader)?,
+            maker: u8(&mut reader)?,
+            timestamp: u64(&mut reader)?,
+            active_difficulty: reader.take()?,
+            unknown_data: reader.rest().to_vec(),
+        })
+    }
+
+    /// Every field, other than `node_id` and `signature`
+    fn encode_fields(&self, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(&self.block_count.to_be_bytes());
+        bytes.extend_from_slice(&self.cemented_count.to_be_bytes());
+        bytes.extend_from_slice(&self.unchecked_count.to_be_bytes());
+        bytes.extend_from_slice(&self.account_count.to_be_bytes());
+        bytes.extend_from_slice(&self.bandwidth_cap.to_be_bytes());
+        bytes.extend_from_slice(&self.peer_count.to_be_bytes());
+        bytes.push(self.protocol_version);
+        bytes.extend_from_slice(&self.uptime.to_be_bytes());
+        bytes.extend_from_slice(&self.genesis_block);
+        bytes.extend_from_slice(&[
+            self.major_version,
+            self.minor_version,
+            self.patch_version,
+            self.pre_release_version,
+            self.maker,
+        ]);
+        bytes.extend_from_slice(&self.timestamp.to_be_bytes());
+        bytes.extend_from_slice(&self.active_difficulty);
+        bytes.extend_from_slice(&self.unknown_data);
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::SecretBytes;
+
+    pub(crate) fn signed_telemetry(key: &Key) -> Telemetry {
+        let mut telemetry = Telemetry {
+            node_id: key.to_account(),
+            signature: Signature::default(),
+            block_count: 1,
+            cemented_count: 1,
+            unchecked_count: 3,
+            account_count: 4,
+            bandwidth_cap: 5,
+            peer_count: 6,
+            protocol_version: 0x15,
+            uptime: 7,
+            genesis_block: [8; 32],
+            major_version: 27,
+            minor_version: 1,
+            patch_version: 0,
+            pre_release_version: 0,
+            maker: 0,
+            timestamp: 9,
+            active_difficulty: [10; 8],
+            unknown_data: vec![],
+        };
+        telemetry.sign(key);
+        telemetry
+    }
+
+    #[test]
+    fn signature() {
+        let key = Key::from_seed(&SecretBytes::from([4; 32]), 0);
+        let telemetry = signed_telemetry(&key);
+        assert!(telemetry.has_valid_signature());
+
+        let bytes = telemetry.signed_bytes();
+        assert!(bytes.len() == Telemetry::LENGTH - 64);
+        assert!(bytes[..32] == *key.to_account().compressed.as_bytes());
+        assert!(bytes[32..40] == 1_u64.to_be_bytes());
+
+        let mut forged = telemetry.clone();
+        forged.block_count += 1;
+        assert!(!forged.has_valid_signature());
+        let mut forged = telemetry.clone();
+        forged.node_id = Key::from_seed(&SecretBytes::from([4; 32]), 1).to_account();
+        assert!(!forged.has_valid_signature());
+    }
+
+    #[test]
+    fn binary_format() {
+        let key = Key::from_seed(&SecretBytes::from([4; 32]), 0);
+        let mut telemetry = signed_telemetry(&key);
+        telemetry.unknown_data = vec![11, 12];
+        telemetry.sign(&key);
+
+        let bytes = telemetry.to_bytes();
+        assert!(bytes.len() == Telemetry::LENGTH + 2);
+        assert!(bytes[96..104] == 1_u64.to_be_bytes());
+        let decoded = Telemetry::from_bytes(&bytes).unwrap();
+        assert!(decoded == telemetry && decoded.has_valid_signature());
+        assert!(Telemetry::from_bytes(&bytes[..Telemetry::LENGTH - 1]).is_err());
+    }
+
+    #[test]
+    fn node_id() {
+        let node_id = Key::from_seed(&SecretBytes::from([4; 32]), 0).to_account();
+        let encoded = encode_node_id(&node_id);
+        assert!(encoded.starts_with("node_") && encoded[5..] == node_id.to_string()[5..]);
+        assert!(decode_node_id(&encoded).unwrap() == node_id);
+        assert!(decode_node_id(&node_id.to_string()).is_err());
+    }
+}
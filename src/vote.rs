@@ -0,0 +1,194 @@
+//! Votes by representatives, as carried by `confirm_ack` messages and the node's WebSocket `vote` topic.
+
+use crate::hashes::blake2b256;
+use crate::{Account, Key, Signature};
+use std::collections::HashMap;
+use std::time::Duration;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Lower 4 bits of the `timestamp` field, which hold the duration
+const DURATION_MASK: u64 = 0x0f;
+
+/// A representative's vote for one or more blocks
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Vote {
+    pub representative: Account,
+    /// Signature of the vote's `hash()`
+    pub signature: Signature,
+    /// Timestamp in milliseconds, with the lower 4 bits replaced by the duration.
+    /// `Vote::FINAL_TIMESTAMP` for final votes.
+    pub timestamp: u64,
+    /// Hashes of the blocks being voted for
+    pub hashes: Vec<[u8; 32]>,
+}
+impl Vote {
+    /// The `timestamp` of final votes, which can not be replaced by a later vote
+    pub const FINAL_TIMESTAMP: u64 = u64::MAX;
+
+    /// Create and sign a (non-final) vote.
+    ///
+    /// `timestamp` is in milliseconds. Its lower 4 bits are replaced by `duration_bits` (see `Vote::duration`).
+    pub fn new(key: &Key, timestamp: u64, duration_bits: u8, hashes: Vec<[u8; 32]>) -> Vote {
+        let timestamp = (timestamp & !DURATION_MASK) | (duration_bits as u64 & DURATION_MASK);
+        Vote::new_raw(key, timestamp, hashes)
+    }
+
+    /// Create and sign a final vote
+    pub fn new_final(key: &Key, hashes: Vec<[u8; 32]>) -> Vote {
+        Vote::new_raw(key, Vote::FINAL_TIMESTAMP, hashes)
+    }
+
+    fn new_raw(key: &Key, timestamp: u64, hashes: Vec<[u8; 32]>) -> Vote {
+        let mut vote = Vote {
+            representative: key.to_account(),
+            signature: Signature::default(),
+            timestamp,
+            hashes,
+        };
+        vote.signature = key.sign_message(&vote.hash());
+        vote
+    }
+
+    /// The hash which is signed: `blake2b("vote " || hashes || timestamp)`, with the timestamp in little-endian
+    pub fn hash(&self) -> [u8; 32] {
+        let mut data = b"vote ".to_vec();
+        for hash in &self.hashes {
+            data.extend_from_slice(hash);
+        }
+        data.extend_from_slice(&self.timestamp.to_le_bytes());
+        *blake2b256(&data).as_ref()
+    }
+
+    /// Returns `true` if this is a final vote
+    pub fn is_final(&self) -> bool {
+        self.timestamp == Vote::FINAL_TIMESTAMP
+    }
+
+    /// The time at which the vote was created, in milliseconds, or `None` for final votes
+    pub fn timestamp_millis(&self) -> Option<u64> {
+        match self.is_final() {
+            true => None,
+            false => Some(self.timestamp & !DURATION_MASK),
+        }
+    }
+
+    /// How long the representative intends the vote to be valid for: `2^(duration_bits + 4)` milliseconds
+    pub fn duration(&self) -> Duration {
+        Duration::from_millis(1 << ((self.timestamp & DURATION_MASK) + 4))
+    }
+
+    /// Check if the representative's signature for this vote is valid
+    pub fn has_valid_signature(&self) -> bool {
+        self.representative
+            .is_valid_signature(&self.hash(), &self.signature)
+    }
+}
+
+/// The voting weight behind a block
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Tally {
+    /// Weight of the representatives which voted for the block
+    pub weight: u128,
+    /// Weight of the representatives which cast a final vote for the block
+    pub final_weight: u128,
+}
+
+/// Tally the votes for each block hash, given the representatives' voting weights.
+///
+/// Votes with invalid signatures are skipped, and each representative's weight is counted at most once per block.
+/// Representatives which are not in `weights` have no weight.
+pub fn tally(votes: &[Vote], weights: &HashMap<Account, u128>) -> HashMap<[u8; 32], Tally> {
+    // whether each (representative, hash) has been counted, and whether it was final
+    let mut counted: HashMap<(&Account, [u8; 32]), bool> = HashMap::new();
+    let mut tallies: HashMap<[u8; 32], Tally> = HashMap::new();
+    for vote in votes.iter().filter(|vote| vote.has_valid_signature()) {
+        let weight = weights.get(&vote.representative).copied().unwrap_or(0);
+        for hash in &vote.hashes {
+            let tally = tallies.entry(*hash).or_default();
+            match counted.get(&(&vote.representative, *hash)) {
+                None => tally.weight += weight,
+                Some(true) => continue,
+                Some(false) if !vote.is_final() => continue,
+                Some(false) => (),
+            }
+            if vote.is_final() {
+                tally.final_weight += weight;
+            }
+            counted.insert((&vote.representative, *hash), vote.is_final());
+        }
+    }
+    tallies
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SecretBytes;
+
+    fn key(i: u32) -> Key {
+        Key::from_seed(&SecretBytes::from([10; 32]), i)
+    }
+
+    #[test]
+    fn hash() {
+        let vote = Vote::new(&key(0), 0x0102030405060708, 0, vec![[1; 32], [2; 32]]);
+        let mut data = b"vote ".to_vec();
+        data.extend_from_slice(&[1; 32]);
+        data.extend_from_slice(&[2; 32]);
+        data.extend_from_slice(&[0x00, 0x07, 0x06, 0x05, 0x04, 0x03, 0x02, 0x01]);
+        assert!(vote.hash() == *blake2b256(&data).as_ref());
+        assert!(vote.has_valid_signature());
+
+        let mut forged = vote.clone();
+        forged.hashes.push([3; 32]);
+        assert!(!forged.has_valid_signature());
+    }
+
+    #[test]
+    fn timestamps() {
+        let vote = Vote::new(&key(0), 1_700_000_000_123, 3, vec![[1; 32]]);
+        assert!(vote.timestamp & DURATION_MASK == 3);
+        assert!(vote.timestamp_millis() == Some(1_700_000_000_112));
+        assert!(vote.duration() == Duration::from_millis(128));
+        assert!(!vote.is_final());
+
+        let vote = Vote::new_final(&key(0), vec![[1; 32]]);
+        assert!(vote.is_final() && vote.timestamp_millis().is_none());
+        assert!(vote.has_valid_signature());
+    }
+
+    #[test]
+    fn tally() {
+        let weights = HashMap::from([(key(0).to_account(), 100), (key(1).to_account(), 50)]);
+        let mut invalid = Vote::new_final(&key(1), vec![[2; 32]]);
+        invalid.timestamp = 0;
+        let votes = [
+            Vote::new(&key(0), 1000, 0, vec![[1; 32], [2; 32]]),
+            Vote::new(&key(0), 2000, 0, vec![[1; 32]]),
+            Vote::new_final(&key(0), vec![[1; 32]]),
+            Vote::new_final(&key(0), vec![[1; 32]]),
+            Vote::new(&key(1), 1000, 0, vec![[1; 32]]),
+            Vote::new_final(&key(2), vec![[1; 32]]),
+            invalid,
+        ];
+
+        let tallies = super::tally(&votes, &weights);
+        assert!(
+            tallies[&[1; 32]]
+                == Tally {
+                    weight: 150,
+                    final_weight: 100
+                }
+        );
+        assert!(
+            tallies[&[2; 32]]
+                == Tally {
+                    weight: 100,
+                    final_weight: 0
+                }
+        );
+    }
+}